@@ -195,6 +195,26 @@ impl<'a> HasReader for &'a ZBuf {
     }
 }
 
+impl<'a> ZBufReader<'a> {
+    /// Reads the next `len` bytes without copying them if they are contiguous, i.e. if they are
+    /// in the current slice. Nothing is read and `None` is returned otherwise.
+    pub fn read_contiguous(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len == 0 {
+            return Some(&[]);
+        }
+        let slice = self.inner.slices.get(self.cursor.slice)?;
+        let bytes = slice
+            .as_slice()
+            .get(self.cursor.byte..self.cursor.byte + len)?;
+        self.cursor.byte += len;
+        if self.cursor.byte == slice.len() {
+            self.cursor.slice += 1;
+            self.cursor.byte = 0;
+        }
+        Some(bytes)
+    }
+}
+
 impl Reader for ZBufReader<'_> {
     fn read(&mut self, mut into: &mut [u8]) -> Result<NonZeroUsize, DidntRead> {
         let mut read = 0;
@@ -16,10 +16,10 @@ use zenoh_buffers::{
     writer::{DidntWrite, Writer},
 };
 use zenoh_protocol::{
-    common::imsg,
+    common::{iext, imsg},
     transport::{
         id,
-        keepalive::{flag, KeepAlive},
+        keepalive::{ext, flag, KeepAlive},
     },
 };
 
@@ -32,11 +32,26 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &KeepAlive) -> Self::Output {
-        let KeepAlive = x;
+        let KeepAlive { ext_ping, ext_pong } = x;
 
         // Header
-        let header = id::KEEP_ALIVE;
+        let mut header = id::KEEP_ALIVE;
+        let mut n_exts = (ext_ping.is_some() as u8) + (ext_pong.is_some() as u8);
+        if n_exts != 0 {
+            header |= flag::Z;
+        }
         self.write(&mut *writer, header)?;
+
+        // Extensions
+        if let Some(ping) = ext_ping.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (ping, n_exts != 0))?;
+        }
+        if let Some(pong) = ext_pong.as_ref() {
+            n_exts -= 1;
+            self.write(&mut *writer, (pong, n_exts != 0))?;
+        }
+
         Ok(())
     }
 }
@@ -66,11 +81,30 @@ where
         }
 
         // Extensions
-        let has_ext = imsg::has_flag(self.header, flag::Z);
-        if has_ext {
-            extension::skip_all(reader, "Unknown KeepAlive ext")?;
+        let mut ext_ping = None;
+        let mut ext_pong = None;
+
+        let mut has_ext = imsg::has_flag(self.header, flag::Z);
+        while has_ext {
+            let ext: u8 = self.codec.read(&mut *reader)?;
+            let eodec = Zenoh080Header::new(ext);
+            match iext::eid(ext) {
+                ext::Ping::ID => {
+                    let (ping, ext): (ext::Ping, bool) = eodec.read(&mut *reader)?;
+                    ext_ping = Some(ping);
+                    has_ext = ext;
+                }
+                ext::Pong::ID => {
+                    let (pong, ext): (ext::Pong, bool) = eodec.read(&mut *reader)?;
+                    ext_pong = Some(pong);
+                    has_ext = ext;
+                }
+                _ => {
+                    has_ext = extension::skip(reader, "KeepAlive", ext)?;
+                }
+            }
         }
 
-        Ok(KeepAlive)
+        Ok(KeepAlive { ext_ping, ext_pong })
     }
 }
@@ -307,6 +307,8 @@ impl Default for AclConfig {
             rules: None,
             subjects: None,
             policies: None,
+            policy_file: None,
+            audit: None,
         }
     }
 }
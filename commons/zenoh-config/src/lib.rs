@@ -104,6 +104,80 @@ pub struct DownsamplingItemConf {
     pub flow: InterceptorFlow,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitStrategy {
+    /// Messages exceeding the budget are dropped.
+    #[default]
+    Drop,
+    /// Messages exceeding the budget are held back until enough budget is available, without
+    /// delaying the other messages of the transport. They are dropped if the wait would exceed `max_delay_ms`.
+    Delay,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimitRuleConf {
+    /// The key-expression to which the rate limit will be applied.
+    pub key_expr: OwnedKeyExpr,
+    /// The maximum number of messages per second.
+    pub messages_per_sec: Option<f64>,
+    /// The maximum number of payload bytes (including attachments) per second.
+    pub bytes_per_sec: Option<f64>,
+    /// The maximum number of messages that can be sent in a burst (default: `messages_per_sec`).
+    pub messages_burst: Option<f64>,
+    /// The maximum number of payload bytes that can be sent in a burst (default: `bytes_per_sec`).
+    /// Messages bigger than the burst size are always rejected.
+    pub bytes_burst: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimitItemConf {
+    /// A list of interfaces to which the rate limiting will be applied.
+    /// Rate limiting will be applied for all interfaces if the parameter is None.
+    pub interfaces: Option<Vec<Interface>>,
+    /// A list of TLS certificate common names to which the rate limiting will be applied.
+    /// Rate limiting will be applied for all common names if the parameter is None.
+    pub cert_common_names: Option<Vec<CertCommonName>>,
+    /// A list of usernames to which the rate limiting will be applied.
+    /// Rate limiting will be applied for all usernames if the parameter is None.
+    pub usernames: Option<Vec<Username>>,
+    /// Rate limiting flow direction: egress, ingress
+    pub flow: InterceptorFlow,
+    /// Behavior when the budget is exceeded: drop (default), delay
+    #[serde(default)]
+    pub strategy: RateLimitStrategy,
+    /// The maximum time in milliseconds a message can be delayed with the `delay` strategy (default: 100).
+    pub max_delay_ms: Option<u64>,
+    /// A list of rate limiting rules. Each transport matching this item gets its own budget per rule.
+    pub rules: Vec<RateLimitRuleConf>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct KeyRewritingRuleConf {
+    /// The key-expression prefix as used by the remote end of the transport.
+    pub remote: OwnedKeyExpr,
+    /// The key-expression prefix as used locally.
+    pub local: OwnedKeyExpr,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct KeyRewritingItemConf {
+    /// A list of interfaces to which the key rewriting will be applied.
+    /// Key rewriting will be applied for all interfaces if the parameter is None.
+    pub interfaces: Option<Vec<Interface>>,
+    /// A list of TLS certificate common names to which the key rewriting will be applied.
+    /// Key rewriting will be applied for all common names if the parameter is None.
+    pub cert_common_names: Option<Vec<CertCommonName>>,
+    /// A list of usernames to which the key rewriting will be applied.
+    /// Key rewriting will be applied for all usernames if the parameter is None.
+    pub usernames: Option<Vec<Username>>,
+    /// Key rewriting flow directions (default: both).
+    /// Ingress rewrites `remote` prefixes into `local` ones, egress rewrites `local` prefixes into `remote` ones.
+    pub flows: Option<Vec<InterceptorFlow>>,
+    /// A list of key rewriting rules. The rule with the longest matching prefix is applied.
+    pub rules: Vec<KeyRewritingRuleConf>,
+}
+
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct AclConfigRule {
     pub id: String,
@@ -119,6 +193,9 @@ pub struct AclConfigSubjects {
     pub interfaces: Option<Vec<Interface>>,
     pub cert_common_names: Option<Vec<CertCommonName>>,
     pub usernames: Option<Vec<Username>>,
+    pub pubkey_fingerprints: Option<Vec<PubKeyFingerprint>>,
+    pub zids: Option<Vec<ZenohId>>,
+    pub whatamis: Option<Vec<WhatAmI>>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
@@ -148,14 +225,58 @@ impl std::fmt::Display for Username {
     }
 }
 
+/// Hex-encoded SHA3-256 digest of the PKCS#1 DER encoding of an RSA public key.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct PubKeyFingerprint(pub String);
+
+impl std::fmt::Display for PubKeyFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PubKeyFingerprint({})", self.0)
+    }
+}
+
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct AclConfigPolicyEntry {
     pub rules: Vec<String>,
     pub subjects: Vec<String>,
 }
 
+#[derive(Serialize, Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AclAuditConf {
+    /// Publish the audit events on `@/<zid>/<whatami>/acl/events` (default: true).
+    pub publish: Option<bool>,
+    /// Path of a file to which the audit events are appended as JSON lines.
+    pub file: Option<String>,
+    /// The decisions to audit (default: `["deny"]`).
+    pub decisions: Option<Vec<Permission>>,
+}
+
+/// Content of an access control policy file (see `access_control.policy_file`).
+///
+/// Any property set in the file overrides the corresponding inline `access_control` property.
+#[derive(Serialize, Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AclPolicyFile {
+    pub default_permission: Option<Permission>,
+    pub rules: Option<Vec<AclConfigRule>>,
+    pub subjects: Option<Vec<AclConfigSubjects>>,
+    pub policies: Option<Vec<AclConfigPolicyEntry>>,
+}
+
+impl AclPolicyFile {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> ZResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| zerror!("Couldn't read ACL policy file {:?}: {}", path, e))?;
+        json5::from_str(&content)
+            .map_err(|e| zerror!("Invalid ACL policy file {:?}: {}", path, e).into())
+    }
+}
+
 #[derive(Clone, Serialize, Debug, Deserialize)]
 pub struct PolicyRule {
+    pub rule_id: String,
     pub subject_id: usize,
     pub key_expr: String,
     pub message: AclMessage,
@@ -583,9 +704,25 @@ validated_struct::validator! {
 
         },
 
+        /// Configuration of the statistics collected when zenoh is built with the `stats` feature.
+        pub stats: #[derive(Default)]
+        StatsConf {
+            /// Key expressions on which the traffic routed by this node is accounted.
+            key_exprs: Vec<OwnedKeyExpr>,
+            /// Account the traffic routed by this node per prefix made of the first `key_expr_depth`
+            /// chunks of the messages key expressions.
+            key_expr_depth: Option<usize>,
+        },
+
         /// Configuration of the downsampling.
         downsampling: Vec<DownsamplingItemConf>,
 
+        /// Configuration of the rate limiting.
+        rate_limiting: Vec<RateLimitItemConf>,
+
+        /// Configuration of the key expressions rewriting.
+        key_rewriting: Vec<KeyRewritingItemConf>,
+
         ///Configuration of the access control (ACL)
         pub access_control: AclConfig {
             pub enabled: bool,
@@ -593,6 +730,11 @@ validated_struct::validator! {
             pub rules: Option<Vec<AclConfigRule>>,
             pub subjects: Option<Vec<AclConfigSubjects>>,
             pub policies: Option<Vec<AclConfigPolicyEntry>>,
+            /// Path to a JSON5 file containing `default_permission`, `rules`, `subjects` and/or `policies`.
+            /// The file is watched and the access control is reloaded whenever its content changes.
+            pub policy_file: Option<String>,
+            /// Audit of the access control decisions. Modifying it requires a restart.
+            pub audit: Option<AclAuditConf>,
         },
 
         /// A list of directories where plugins may be searched for if no `__path__` was specified for them.
@@ -843,6 +985,29 @@ impl Config {
     }
 }
 
+impl AclConfig {
+    /// Returns this configuration with the content of its `policy_file` (if any) applied on top of it.
+    pub fn with_policy_file(&self) -> ZResult<AclConfig> {
+        let mut config = self.clone();
+        if let Some(path) = &self.policy_file {
+            let file = AclPolicyFile::from_file(path)?;
+            if let Some(default_permission) = file.default_permission {
+                config.default_permission = default_permission;
+            }
+            if file.rules.is_some() {
+                config.rules = file.rules;
+            }
+            if file.subjects.is_some() {
+                config.subjects = file.subjects;
+            }
+            if file.policies.is_some() {
+                config.policies = file.policies;
+            }
+        }
+        Ok(config)
+    }
+}
+
 impl std::fmt::Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         serde_json::to_value(self)
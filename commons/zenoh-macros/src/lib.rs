@@ -555,6 +555,65 @@ pub fn register_param(input: proc_macro::TokenStream) -> proc_macro::TokenStream
         .into()
 }
 
+mod zenoh_serialization_derive;
+use zenoh_serialization_derive::{derive_deserialize, derive_schema, derive_serialize};
+
+/// Derive `zenoh_ext::Serialize` for a struct or an enum, following the
+/// [Zenoh serialization format](https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md).
+///
+/// A struct is serialized as the tuple of its fields, in declaration order. An enum is serialized
+/// as the index of its variant in declaration order, encoded as a LEB128 variable length integer,
+/// followed by the tuple of the variant fields.
+///
+/// As the declaration order defines the format, reordering fields or variants, or inserting a
+/// variant anywhere but at the end, breaks the compatibility with the already serialized data.
+/// Explicit enum discriminants are not used by the format, so enums having some are rejected.
+/// ```rust,ignore
+/// #[derive(zenoh_ext::Serialize)]
+/// struct Struct {
+///    ...
+/// }
+/// ```
+#[proc_macro_derive(Serialize)]
+pub fn serialize(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    derive_serialize(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `zenoh_ext::Deserialize` for a struct or an enum, following the same format as the
+/// [`Serialize`](macro@Serialize) derive.
+/// ```rust,ignore
+/// #[derive(zenoh_ext::Deserialize)]
+/// struct Struct {
+///    ...
+/// }
+/// ```
+#[proc_macro_derive(Deserialize)]
+pub fn deserialize(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    derive_deserialize(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `zenoh_ext::Schema` for a struct or an enum, describing the format used by the
+/// [`Serialize`](macro@Serialize) derive. Unnamed fields are named by their index.
+/// ```rust,ignore
+/// #[derive(zenoh_ext::Serialize, zenoh_ext::Schema)]
+/// struct Struct {
+///    ...
+/// }
+/// ```
+#[proc_macro_derive(Schema)]
+pub fn schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    derive_schema(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// Macro `#[internal_trait]` should precede
 /// `impl Trait for Struct { ... }`
 ///
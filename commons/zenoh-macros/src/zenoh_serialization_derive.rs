@@ -0,0 +1,235 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This crate is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DataEnum, DataStruct, DeriveInput, Fields, GenericParam, Generics, Ident,
+};
+
+/// Add a `T: #bound` bound to every type parameter of the generics.
+fn add_trait_bounds(mut generics: Generics, bound: TokenStream) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(#bound));
+        }
+    }
+    generics
+}
+
+/// Bindings of the fields of a struct or of an enum variant, used to destructure it.
+fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|i| format_ident!("__f{}", i))
+        .collect()
+}
+
+/// Pattern destructuring `fields` into their bindings.
+fn destructure(path: TokenStream, fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path ( #(#bindings),* )),
+        Fields::Unit => path,
+    }
+}
+
+/// Reject the explicit discriminants of an enum: variants are encoded by their declaration index,
+/// so a discriminant would suggest an encoding that is not the one used.
+fn check_discriminants(data: &DataEnum, derive: &str) -> syn::Result<()> {
+    match data.variants.iter().find_map(|v| v.discriminant.as_ref()) {
+        Some((_, expr)) => Err(syn::Error::new_spanned(
+            expr,
+            format!(
+                "{derive} cannot be derived for enums with explicit discriminants, \
+                 variants are encoded by their index in declaration order"
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Expression building `fields`, each one being deserialized in order.
+fn construct(path: TokenStream, fields: &Fields) -> TokenStream {
+    let deserialize = quote!(::zenoh_ext::Deserialize::deserialize(deserializer)?);
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#names: #deserialize),* })
+        }
+        Fields::Unnamed(unnamed) => {
+            let values = unnamed.unnamed.iter().map(|_| &deserialize);
+            quote!(#path ( #(#values),* ))
+        }
+        Fields::Unit => path,
+    }
+}
+
+pub(crate) fn derive_serialize(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = add_trait_bounds(input.generics.clone(), quote!(::zenoh_ext::Serialize));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        // A struct is serialized as the tuple of its fields
+        Data::Struct(DataStruct { fields, .. }) => {
+            let bindings = field_bindings(fields);
+            let pattern = destructure(quote!(Self), fields, &bindings);
+            quote! {
+                let #pattern = self;
+                #(::zenoh_ext::Serialize::serialize(#bindings, serializer);)*
+            }
+        }
+        // An enum is serialized as the index of its variant, followed by the tuple of its fields
+        Data::Enum(data @ DataEnum { variants, .. }) => {
+            check_discriminants(data, "Serialize")?;
+            let arms = variants.iter().enumerate().map(|(i, variant)| {
+                let ident = &variant.ident;
+                let bindings = field_bindings(&variant.fields);
+                let pattern = destructure(quote!(Self::#ident), &variant.fields, &bindings);
+                quote! {
+                    #pattern => {
+                        serializer.serialize(::zenoh_ext::__private::VarInt(#i));
+                        #(::zenoh_ext::Serialize::serialize(#bindings, serializer);)*
+                    }
+                }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "Serialize cannot be derived for unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::zenoh_ext::Serialize for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn serialize(&self, serializer: &mut ::zenoh_ext::ZSerializer) {
+                #body
+            }
+        }
+    })
+}
+
+pub(crate) fn derive_deserialize(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = add_trait_bounds(input.generics.clone(), quote!(::zenoh_ext::Deserialize));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => {
+            let value = construct(quote!(Self), fields);
+            quote!(Ok(#value))
+        }
+        Data::Enum(data @ DataEnum { variants, .. }) => {
+            check_discriminants(data, "Deserialize")?;
+            let arms = variants.iter().enumerate().map(|(i, variant)| {
+                let ident = &variant.ident;
+                let value = construct(quote!(Self::#ident), &variant.fields);
+                quote!(#i => Ok(#value),)
+            });
+            quote! {
+                match deserializer.deserialize::<::zenoh_ext::__private::VarInt<usize>>()?.0 {
+                    #(#arms)*
+                    _ => Err(::zenoh_ext::ZDeserializeError),
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "Deserialize cannot be derived for unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::zenoh_ext::Deserialize for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn deserialize(
+                deserializer: &mut ::zenoh_ext::ZDeserializer,
+            ) -> ::core::result::Result<Self, ::zenoh_ext::ZDeserializeError> {
+                #body
+            }
+        }
+    })
+}
+
+/// Expression building the `(name, schema)` pairs of `fields`, unnamed fields being named by their index.
+fn fields_schema(fields: &Fields) -> TokenStream {
+    let fields = fields.iter().enumerate().map(|(i, field)| {
+        let name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => i.to_string(),
+        };
+        let ty = &field.ty;
+        quote!((#name.to_string(), <#ty as ::zenoh_ext::Schema>::schema()))
+    });
+    quote!(vec![#(#fields),*])
+}
+
+pub(crate) fn derive_schema(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let generics = add_trait_bounds(input.generics.clone(), quote!(::zenoh_ext::Schema));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let body = match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => {
+            let fields = fields_schema(fields);
+            quote! {
+                ::zenoh_ext::ZSchema::Struct {
+                    name: #name_str.to_string(),
+                    fields: #fields,
+                }
+            }
+        }
+        Data::Enum(data @ DataEnum { variants, .. }) => {
+            check_discriminants(data, "Schema")?;
+            let variants = variants.iter().map(|variant| {
+                let variant_str = variant.ident.to_string();
+                let fields = fields_schema(&variant.fields);
+                quote!((#variant_str.to_string(), #fields))
+            });
+            quote! {
+                ::zenoh_ext::ZSchema::Enum {
+                    name: #name_str.to_string(),
+                    variants: vec![#(#variants),*],
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "Schema cannot be derived for unions",
+            ))
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics ::zenoh_ext::Schema for #name #ty_generics #where_clause {
+            fn schema() -> ::zenoh_ext::ZSchema {
+                #body
+            }
+        }
+    })
+}
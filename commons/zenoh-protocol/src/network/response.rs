@@ -57,8 +57,8 @@ pub struct Response {
 
 pub mod ext {
     use crate::{
-        common::{ZExtZ64, ZExtZBuf},
-        zextz64, zextzbuf,
+        common::{ZExtUnit, ZExtZ64, ZExtZBuf},
+        zextunit, zextz64, zextzbuf,
     };
     pub type QoS = zextz64!(0x1, false);
     pub type QoSType = crate::network::ext::QoSType<{ QoS::ID }>;
@@ -68,6 +68,12 @@ pub mod ext {
 
     pub type ResponderId = zextzbuf!(0x3, false);
     pub type ResponderIdType = crate::network::ext::EntityGlobalIdType<{ ResponderId::ID }>;
+
+    /// # Cancel extension
+    /// Mandatory: a receiver that does not support it must not mistake the cancellation for the
+    /// end of one of its own requests. As older peers close the transport on it, it is only sent
+    /// to peers that negotiated the query cancellation patch.
+    pub type Cancel = zextunit!(0x3, true);
 }
 
 impl Response {
@@ -113,11 +119,15 @@ impl Response {
 ///
 /// (*) The resolution of the request id is negotiated during the session establishment.
 ///     This implementation limits the resolution to 32bit.
+///
+/// A ResponseFinal carrying the Cancel extension travels in the opposite direction: it is sent
+/// by the requester to cancel the request with the given request id.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResponseFinal {
     pub rid: RequestId,
     pub ext_qos: ext::QoSType,
     pub ext_tstamp: Option<ext::TimestampType>,
+    pub ext_cancel: Option<ext::Cancel>,
 }
 
 impl ResponseFinal {
@@ -129,11 +139,13 @@ impl ResponseFinal {
         let rid: RequestId = rng.gen();
         let ext_qos = ext::QoSType::rand();
         let ext_tstamp = rng.gen_bool(0.5).then(ext::TimestampType::rand);
+        let ext_cancel = rng.gen_bool(0.5).then(ext::Cancel::rand);
 
         Self {
             rid,
             ext_qos,
             ext_tstamp,
+            ext_cancel,
         }
     }
 }
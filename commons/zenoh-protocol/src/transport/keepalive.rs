@@ -75,18 +75,43 @@ pub mod flag {
 /// +---------------+
 /// ```
 ///
+/// A [`KeepAlive`] message MAY carry a Ping extension, in which case the receiver SHOULD reply
+/// with a [`KeepAlive`] message carrying a Pong extension with the same value. This allows the
+/// sender to measure the round trip time of the link.
+///
 /// NOTE: 16 bits (2 bytes) may be prepended to the serialized message indicating the total length
 ///       in bytes of the message, resulting in the maximum length of a message being 65535 bytes.
 ///       This is necessary in those stream-oriented transports (e.g., TCP) that do not preserve
 ///       the boundary of the serialized messages. The length is encoded as little-endian.
 ///       In any case, the length of a message must not exceed 65535 bytes.
 ///
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct KeepAlive;
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KeepAlive {
+    pub ext_ping: Option<ext::Ping>,
+    pub ext_pong: Option<ext::Pong>,
+}
+
+// Extensions
+pub mod ext {
+    use crate::{common::ZExtZ64, zextz64};
+
+    /// # Ping extension
+    /// Opaque value set by the sender, to be echoed in a Pong extension by the receiver
+    pub type Ping = zextz64!(0x1, false);
+
+    /// # Pong extension
+    /// Value of the Ping extension being replied to
+    pub type Pong = zextz64!(0x2, false);
+}
 
 impl KeepAlive {
     #[cfg(feature = "test")]
     pub fn rand() -> Self {
-        Self
+        use rand::Rng;
+
+        let mut rng = rand::thread_rng();
+        let ext_ping = rng.gen_bool(0.5).then_some(ext::Ping::rand());
+        let ext_pong = rng.gen_bool(0.5).then_some(ext::Pong::rand());
+        Self { ext_ping, ext_pong }
     }
 }
@@ -152,15 +152,19 @@ impl BatchHeader {
 
 // WRITE BATCH
 #[cfg(feature = "stats")]
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct WBatchStats {
     pub t_msgs: usize,
+    // The instants the network messages (or fragments) of the batch were pushed in the
+    // pipeline, along with their priority
+    pub push_times: Vec<(std::time::Instant, zenoh_protocol::core::Priority)>,
 }
 
 #[cfg(feature = "stats")]
 impl WBatchStats {
     fn clear(&mut self) {
         self.t_msgs = 0;
+        self.push_times.clear();
     }
 }
 
@@ -605,7 +609,7 @@ mod tests {
         };
         let mut batch = WBatch::new(config);
 
-        let tmsg: TransportMessage = KeepAlive.into();
+        let tmsg: TransportMessage = KeepAlive::default().into();
         let mut nmsg: NetworkMessage = Push {
             wire_expr: WireExpr::empty(),
             ext_qos: ext::QoSType::new(Priority::DEFAULT, CongestionControl::Block, false),
@@ -258,6 +258,9 @@ impl StageIn {
         priority: Priority,
         deadline: &mut Deadline,
     ) -> Result<bool, TransportClosed> {
+        #[cfg(feature = "stats")]
+        let push_time = Instant::now();
+
         // Lock the current serialization batch.
         let mut c_guard = self.mutex.current();
 
@@ -298,6 +301,8 @@ impl StageIn {
 
         macro_rules! zretok {
             ($batch:expr, $msg:expr) => {{
+                #[cfg(feature = "stats")]
+                $batch.stats.push_times.push((push_time, $msg.priority()));
                 if !self.batching || $msg.is_express() {
                     // Move out existing batch
                     self.s_out.move_batch($batch);
@@ -392,6 +397,8 @@ impl StageIn {
             // Serialize the message fragment
             match batch.encode((&mut reader, &mut fragment)) {
                 Ok(_) => {
+                    #[cfg(feature = "stats")]
+                    batch.stats.push_times.push((push_time, msg.priority()));
                     // Update the SN
                     fragment.sn = tch.sn.get();
                     fragment.ext_first = None;
@@ -45,14 +45,23 @@ macro_rules! stats_struct {
         }
     };
     (@increment $vis:vis $field_name:ident $field_type:ident) => {};
-    (@openmetrics($stats:expr, $string:expr) $field_name:ident) => {
+    (@openmetrics($stats:expr, $string:expr, $prefix:expr, $labels:expr) $field_name:ident) => {
+        $string.push_str($prefix);
         $string.push_str(stringify!($field_name));
+        if !$labels.is_empty() {
+            $string.push_str("{");
+            $string.push_str($labels);
+            $string.push_str("}");
+        }
         $string.push_str(" ");
         $string.push_str($stats.$field_name.to_string().as_str());
         $string.push_str("\n");
     };
-    (@openmetrics($stats:expr, $string:expr) $field_name:ident $field_type:ident) => {
-        $string.push_str(&$stats.$field_name.sub_openmetrics_text(stringify!($field_name)));
+    (@openmetrics($stats:expr, $string:expr, $prefix:expr, $labels:expr) $field_name:ident $field_type:ident) => {
+        $string.push_str(&$stats.$field_name.sub_openmetrics_text(
+            &format!("{}{}", $prefix, stringify!($field_name)),
+            $labels,
+        ));
     };
     (@openmetrics_val($stats:expr) $field_name:ident) => {
         $stats.$field_name.to_string().as_str()
@@ -118,11 +127,16 @@ macro_rules! stats_struct {
 
             impl [<$struct_name Report>] {
                 #[allow(dead_code)]
-                fn sub_openmetrics_text(&self, prefix: &str) -> String {
+                fn sub_openmetrics_text(&self, prefix: &str, labels: &str) -> String {
                     let mut s = String::new();
                     $(
                         s.push_str(prefix);
-                        s.push_str("{space=\"");
+                        s.push_str("{");
+                        if !labels.is_empty() {
+                            s.push_str(labels);
+                            s.push_str(",");
+                        }
+                        s.push_str("space=\"");
                         s.push_str(stringify!($field_name));
                         s.push_str("\"} ");
                         s.push_str(
@@ -134,10 +148,17 @@ macro_rules! stats_struct {
                 }
 
                 $vis fn openmetrics_text(&self) -> String {
+                    Self::labelled_openmetrics_text("", &[("", self)])
+                }
+
+                /// Renders the reports, each one identified by its OpenMetrics `labels`
+                /// (e.g. `zid="..."`), with the metric names prefixed by `prefix`.
+                $vis fn labelled_openmetrics_text(prefix: &str, reports: &[(&str, &Self)]) -> String {
                     let mut s = String::new();
                     $(
                         $(
                             s.push_str("# HELP ");
+                            s.push_str(prefix);
                             s.push_str(stringify!($field_name));
                             s.push_str(" ");
                             s.push_str($help);
@@ -145,12 +166,15 @@ macro_rules! stats_struct {
                         )?
                         $(
                             s.push_str("# TYPE ");
+                            s.push_str(prefix);
                             s.push_str(stringify!($field_name));
                             s.push_str(" ");
                             s.push_str($type);
                             s.push_str("\n");
                         )?
-                        stats_struct!(@openmetrics(self, s) $field_name $($field_type)?);
+                        for (labels, report) in reports {
+                            stats_struct!(@openmetrics(report, s, prefix, *labels) $field_name $($field_type)?);
+                        }
                     )*
                     s
                 }
@@ -167,9 +191,141 @@ macro_rules! stats_struct {
     }
 }
 
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 use serde::{Deserialize, Serialize};
+
+/// The upper bounds of the buckets of a [`Histogram`], in increasing order.
+pub trait HistogramBuckets {
+    const BOUNDS: &'static [f64];
+}
+
+/// Buckets for durations, in seconds, from 10 µs to 10 s.
+pub struct LatencyBuckets;
+
+impl HistogramBuckets for LatencyBuckets {
+    const BOUNDS: &'static [f64] = &[
+        0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0,
+    ];
+}
+
+/// Buckets for ratios, from 0.1 to 1.
+pub struct RatioBuckets;
+
+impl HistogramBuckets for RatioBuckets {
+    const BOUNDS: &'static [f64] = &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+}
+
+/// A histogram field of a [`stats_struct!`], the observations are propagated to the parent histogram.
+pub struct Histogram<B: HistogramBuckets> {
+    parent: Option<Arc<Histogram<B>>>,
+    // One bucket per bound plus the +Inf one, not cumulative
+    buckets: Box<[AtomicUsize]>,
+    count: AtomicUsize,
+    // The bits of the f64 sum of the observations
+    sum: AtomicU64,
+    _buckets: PhantomData<B>,
+}
+
+impl<B: HistogramBuckets> Histogram<B> {
+    pub fn new(parent: Option<Arc<Histogram<B>>>) -> Self {
+        Histogram {
+            parent,
+            buckets: (0..=B::BOUNDS.len()).map(|_| AtomicUsize::new(0)).collect(),
+            count: AtomicUsize::new(0),
+            sum: AtomicU64::new(0.0_f64.to_bits()),
+            _buckets: PhantomData,
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let i = B::BOUNDS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(B::BOUNDS.len());
+        self.buckets[i].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        let _ = self
+            .sum
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sum| {
+                Some((f64::from_bits(sum) + value).to_bits())
+            });
+        if let Some(parent) = self.parent.as_ref() {
+            parent.observe(value);
+        }
+    }
+
+    pub fn report(&self) -> HistogramReport {
+        let mut cumulative = 0;
+        HistogramReport {
+            buckets: B::BOUNDS
+                .iter()
+                .zip(self.buckets.iter())
+                .map(|(bound, n)| {
+                    cumulative += n.load(Ordering::Relaxed);
+                    (*bound, cumulative)
+                })
+                .collect(),
+            count: self.count.load(Ordering::Relaxed),
+            sum: f64::from_bits(self.sum.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<B: HistogramBuckets> Default for Histogram<B> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HistogramReport {
+    /// The `(upper bound, cumulative count)` of the buckets, the +Inf bucket being `count`.
+    pub buckets: Vec<(f64, usize)>,
+    pub count: usize,
+    pub sum: f64,
+}
+
+impl HistogramReport {
+    fn sub_openmetrics_text(&self, prefix: &str, labels: &str) -> String {
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{labels},")
+        };
+        let mut s = String::new();
+        for (bound, n) in &self.buckets {
+            s.push_str(&format!("{prefix}_bucket{{{labels}le=\"{bound}\"}} {n}\n"));
+        }
+        s.push_str(&format!(
+            "{prefix}_bucket{{{labels}le=\"+Inf\"}} {}\n",
+            self.count
+        ));
+        let labels = labels.trim_end_matches(',');
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        s.push_str(&format!("{prefix}_sum{labels} {}\n", self.sum));
+        s.push_str(&format!("{prefix}_count{labels} {}\n", self.count));
+        s
+    }
+}
+
+/// A [`Histogram`] of durations, in seconds.
+pub type LatencyHistogram = Histogram<LatencyBuckets>;
+pub type LatencyHistogramReport = HistogramReport;
+
+/// A [`Histogram`] of ratios.
+pub type RatioHistogram = Histogram<RatioBuckets>;
+pub type RatioHistogramReport = HistogramReport;
 stats_struct! {
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct DiscriminatedStats {
@@ -229,6 +385,14 @@ stats_struct! {
         # TYPE "counter"
         pub tx_z_reply_pl_bytes DiscriminatedStats,
 
+        # HELP "Histogram of the fill ratio of the sent batches, i.e. their size over the MTU."
+        # TYPE "histogram"
+        pub tx_batch_fill_ratio RatioHistogram,
+
+        # HELP "Histogram of the keep alive round trip time, in seconds."
+        # TYPE "histogram"
+        pub keepalive_rtt LatencyHistogram,
+
         # HELP "Counter of received bytes."
         # TYPE "counter"
         pub rx_bytes,
@@ -278,3 +442,85 @@ stats_struct! {
         pub rx_z_reply_pl_bytes DiscriminatedStats,
     }
 }
+
+stats_struct! {
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct KeyExprStats {
+        # HELP "Counter of sent zenoh put messages."
+        # TYPE "counter"
+        pub tx_z_put_msgs,
+
+        # HELP "Counter of sent bytes in zenoh put message payloads."
+        # TYPE "counter"
+        pub tx_z_put_pl_bytes,
+
+        # HELP "Counter of sent zenoh del messages."
+        # TYPE "counter"
+        pub tx_z_del_msgs,
+
+        # HELP "Counter of sent zenoh query messages."
+        # TYPE "counter"
+        pub tx_z_query_msgs,
+
+        # HELP "Counter of sent bytes in zenoh query message payloads."
+        # TYPE "counter"
+        pub tx_z_query_pl_bytes,
+
+        # HELP "Counter of sent zenoh reply messages."
+        # TYPE "counter"
+        pub tx_z_reply_msgs,
+
+        # HELP "Counter of sent bytes in zenoh reply message payloads."
+        # TYPE "counter"
+        pub tx_z_reply_pl_bytes,
+
+        # HELP "Counter of received zenoh put messages."
+        # TYPE "counter"
+        pub rx_z_put_msgs,
+
+        # HELP "Counter of received bytes in zenoh put message payloads."
+        # TYPE "counter"
+        pub rx_z_put_pl_bytes,
+
+        # HELP "Counter of received zenoh del messages."
+        # TYPE "counter"
+        pub rx_z_del_msgs,
+
+        # HELP "Counter of received zenoh query messages."
+        # TYPE "counter"
+        pub rx_z_query_msgs,
+
+        # HELP "Counter of received bytes in zenoh query message payloads."
+        # TYPE "counter"
+        pub rx_z_query_pl_bytes,
+
+        # HELP "Counter of received zenoh reply messages."
+        # TYPE "counter"
+        pub rx_z_reply_msgs,
+
+        # HELP "Counter of received bytes in zenoh reply message payloads."
+        # TYPE "counter"
+        pub rx_z_reply_pl_bytes,
+    }
+}
+
+stats_struct! {
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct TransportPriorityStats {
+        # HELP "Counter of sent network messages."
+        # TYPE "counter"
+        pub tx_n_msgs,
+
+        # HELP "Counter of dropped network messages."
+        # TYPE "counter"
+        pub tx_n_dropped,
+
+        # HELP "Histogram of the time network messages spend in the transmission pipeline, in seconds."
+        # TYPE "histogram"
+        pub tx_queuing_delay LatencyHistogram,
+
+        # HELP "Counter of received network messages."
+        # TYPE "counter"
+        pub rx_n_msgs,
+    }
+}
@@ -110,16 +110,16 @@ impl TransportMulticast {
         }
     }
 
+    /// Schedules a message for transmission, returning `false` if it was dropped.
     #[inline(always)]
-    pub fn schedule(&self, message: NetworkMessage) -> ZResult<()> {
+    pub fn schedule(&self, message: NetworkMessage) -> ZResult<bool> {
         let transport = self.get_transport()?;
-        transport.schedule(message)?;
-        Ok(())
+        transport.schedule(message)
     }
 
     #[inline(always)]
     pub fn handle_message(&self, message: NetworkMessage) -> ZResult<()> {
-        self.schedule(message)
+        self.schedule(message).map(|_| ())
     }
 
     #[cfg(feature = "stats")]
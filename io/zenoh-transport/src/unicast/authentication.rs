@@ -13,6 +13,8 @@
 //
 use zenoh_link::{LinkAuthId, LinkAuthType};
 
+#[cfg(feature = "auth_pubkey")]
+use super::establishment::ext::auth::PubKeyId;
 #[cfg(feature = "auth_usrpwd")]
 use super::establishment::ext::auth::UsrPwdId;
 
@@ -20,6 +22,7 @@ use super::establishment::ext::auth::UsrPwdId;
 pub enum AuthId {
     CertCommonName(String),
     Username(String),
+    PubKeyFingerprint(String),
     None,
 }
 
@@ -52,3 +55,13 @@ impl From<UsrPwdId> for AuthId {
         }
     }
 }
+
+#[cfg(feature = "auth_pubkey")]
+impl From<PubKeyId> for AuthId {
+    fn from(pubkey_id: PubKeyId) -> Self {
+        match pubkey_id.0 {
+            Some(fingerprint) => AuthId::PubKeyFingerprint(fingerprint),
+            None => AuthId::None,
+        }
+    }
+}
@@ -31,6 +31,8 @@ use zenoh_protocol::{
 };
 use zenoh_result::ZResult;
 
+#[cfg(feature = "auth_pubkey")]
+use super::ext::auth::PubKeyId;
 #[cfg(feature = "auth_usrpwd")]
 use super::ext::auth::UsrPwdId;
 #[cfg(feature = "shared-memory")]
@@ -116,6 +118,8 @@ struct RecvOpenSynOut {
     other_initial_sn: TransportSn,
     #[cfg(feature = "auth_usrpwd")]
     other_auth_id: UsrPwdId,
+    #[cfg(feature = "auth_pubkey")]
+    other_pubkey_id: PubKeyId,
 }
 
 // OpenAck
@@ -535,13 +539,12 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
         }
 
         // Extension Auth
-        #[cfg(feature = "auth_usrpwd")]
-        let user_password_id = self
+        #[cfg(feature = "transport_auth")]
+        let auth_out = self
             .ext_auth
             .recv_open_syn((&mut state.link.ext_auth, open_syn.ext_auth))
             .await
-            .map_err(|e| (e, Some(close::reason::GENERIC)))?
-            .auth_id;
+            .map_err(|e| (e, Some(close::reason::GENERIC)))?;
 
         // Extension MultiLink
         #[cfg(feature = "transport_multilink")]
@@ -569,7 +572,9 @@ impl<'a, 'b: 'a> AcceptFsm for &'a mut AcceptLink<'b> {
             other_lease: open_syn.lease,
             other_initial_sn: open_syn.initial_sn,
             #[cfg(feature = "auth_usrpwd")]
-            other_auth_id: user_password_id,
+            other_auth_id: auth_out.auth_id,
+            #[cfg(feature = "auth_pubkey")]
+            other_pubkey_id: auth_out.pubkey_id,
         };
         Ok((state, output))
     }
@@ -806,6 +811,8 @@ pub(crate) async fn accept_link(link: LinkUnicast, manager: &TransportManager) -
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
         #[cfg(feature = "auth_usrpwd")]
         auth_id: osyn_out.other_auth_id,
+        #[cfg(feature = "auth_pubkey")]
+        pubkey_id: osyn_out.other_pubkey_id,
         patch: state.transport.ext_patch.get(),
     };
 
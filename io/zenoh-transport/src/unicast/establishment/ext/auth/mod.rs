@@ -478,6 +478,8 @@ impl<'a> OpenFsm for &'a AuthFsm<'a> {
 pub(crate) struct RecvOpenSynOut {
     #[cfg(feature = "auth_usrpwd")]
     pub(crate) auth_id: UsrPwdId,
+    #[cfg(feature = "auth_pubkey")]
+    pub(crate) pubkey_id: PubKeyId,
 }
 
 #[async_trait]
@@ -594,14 +596,19 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
             .read(&mut reader)
             .map_err(|_| zerror!("{S} Decoding error."))?;
 
+        #[cfg(feature = "auth_pubkey")]
+        let pubkey_id: PubKeyId;
+
         #[cfg(feature = "auth_pubkey")]
         {
             match (self.pubkey.as_ref(), state.pubkey.as_mut()) {
                 (Some(e), Some(s)) => {
                     let x = ztake!(exts, id::PUBKEY);
-                    e.recv_open_syn((s, ztryinto!(x, S))).await?;
+                    pubkey_id = e.recv_open_syn((s, ztryinto!(x, S))).await?;
+                }
+                (None, None) => {
+                    pubkey_id = PubKeyId(None);
                 }
-                (None, None) => {}
                 _ => bail!("{S} Invalid PubKey configuration."),
             }
         }
@@ -626,6 +633,8 @@ impl<'a> AcceptFsm for &'a AuthFsm<'a> {
         Ok(RecvOpenSynOut {
             #[cfg(feature = "auth_usrpwd")]
             auth_id,
+            #[cfg(feature = "auth_pubkey")]
+            pubkey_id,
         })
     }
 
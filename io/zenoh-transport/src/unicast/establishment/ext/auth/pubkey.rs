@@ -11,15 +11,21 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use std::{collections::HashSet, fmt, ops::Deref, path::Path};
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+    ops::Deref,
+    path::Path,
+};
 
 use async_trait::async_trait;
 use rand::Rng;
 use rsa::{
-    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
+    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodeRsaPublicKey},
     traits::PublicKeyParts,
     BigUint, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
 };
+use sha3::{Digest, Sha3_256};
 use tokio::sync::{Mutex, RwLock};
 use zenoh_buffers::{
     reader::{DidntRead, HasReader, Reader},
@@ -150,6 +156,22 @@ impl fmt::Debug for ZPublicKey {
     }
 }
 
+impl ZPublicKey {
+    /// Returns the hex-encoded SHA3-256 digest of the PKCS#1 DER encoding of the public key.
+    pub fn fingerprint(&self) -> ZResult<String> {
+        let der = self
+            .0
+            .to_pkcs1_der()
+            .map_err(|e| zerror!("Failed to encode public key: {}", e))?;
+        Ok(Sha3_256::digest(der.as_bytes())
+            .iter()
+            .fold(String::new(), |mut s, b| {
+                let _ = write!(s, "{b:02x}");
+                s
+            }))
+    }
+}
+
 impl From<RsaPublicKey> for ZPublicKey {
     fn from(x: RsaPublicKey) -> Self {
         Self(x)
@@ -483,13 +505,19 @@ impl<'a> OpenFsm for &'a AuthPubKeyFsm<'a> {
 pub(crate) struct StateAccept {
     nonce: Vec<u8>,
     challenge: u64,
+    fingerprint: String,
 }
 
+/// The fingerprint of the public key used by the remote node to authenticate, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PubKeyId(pub Option<String>);
+
 impl StateAccept {
     pub(crate) const fn new() -> Self {
         Self {
             nonce: vec![],
             challenge: 0,
+            fingerprint: String::new(),
         }
     }
 
@@ -498,9 +526,13 @@ impl StateAccept {
         let mut rng = rand::thread_rng();
         let mut nonce = vec![0u8; rng.gen_range(0..=64)];
         rng.fill(&mut nonce[..]);
+        let fingerprint = (0..rng.gen_range(0..=64))
+            .map(|_| rng.gen_range(b'a'..=b'f') as char)
+            .collect();
         Self {
             nonce,
             challenge: rng.gen(),
+            fingerprint,
         }
     }
 }
@@ -513,7 +545,8 @@ where
     type Output = Result<(), DidntWrite>;
 
     fn write(self, writer: &mut W, x: &StateAccept) -> Self::Output {
-        self.write(&mut *writer, x.challenge)
+        self.write(&mut *writer, x.challenge)?;
+        self.write(&mut *writer, x.fingerprint.as_str())
     }
 }
 
@@ -525,16 +558,18 @@ where
 
     fn read(self, reader: &mut R) -> Result<StateAccept, Self::Error> {
         let challenge: u64 = self.read(&mut *reader)?;
+        let fingerprint: String = self.read(&mut *reader)?;
         Ok(StateAccept {
             nonce: vec![],
             challenge,
+            fingerprint,
         })
     }
 }
 
 impl PartialEq for StateAccept {
     fn eq(&self, other: &Self) -> bool {
-        self.challenge == other.challenge
+        self.challenge == other.challenge && self.fingerprint == other.fingerprint
     }
 }
 
@@ -570,6 +605,8 @@ impl<'a> AcceptFsm for &'a AuthPubKeyFsm<'a> {
             }
         }
 
+        state.fingerprint = init_syn.alice_pubkey.fingerprint()?;
+
         let mut prng = zasynclock!(self.prng);
         state.challenge = prng.gen();
         state.nonce = init_syn
@@ -605,7 +642,7 @@ impl<'a> AcceptFsm for &'a AuthPubKeyFsm<'a> {
     }
 
     type RecvOpenSynIn = (&'a mut StateAccept, Option<ext::OpenSyn>);
-    type RecvOpenSynOut = ();
+    type RecvOpenSynOut = PubKeyId;
     async fn recv_open_syn(
         self,
         input: Self::RecvOpenSynIn,
@@ -640,7 +677,7 @@ impl<'a> AcceptFsm for &'a AuthPubKeyFsm<'a> {
             bail!("{S} Invalid nonce.");
         }
 
-        Ok(())
+        Ok(PubKeyId(Some(state.fingerprint.clone())))
     }
 
     type SendOpenAckIn = &'a StateAccept;
@@ -331,7 +331,8 @@ impl<'a> AcceptFsm for &'a MultiLinkFsm<'a> {
         };
 
         fsm.recv_open_syn((&mut pubkey.0, ext.map(|x| x.transmute())))
-            .await
+            .await?;
+        Ok(())
     }
 
     type SendOpenAckIn = &'a StateAccept;
@@ -32,6 +32,8 @@ use zenoh_result::ZResult;
 use super::ext::shm::AuthSegment;
 #[cfg(feature = "shared-memory")]
 use crate::shm::TransportShmConfig;
+#[cfg(feature = "auth_pubkey")]
+use crate::unicast::establishment::ext::auth::PubKeyId;
 #[cfg(feature = "auth_usrpwd")]
 use crate::unicast::establishment::ext::auth::UsrPwdId;
 use crate::{
@@ -686,6 +688,8 @@ pub(crate) async fn open_link(
         is_lowlatency: state.transport.ext_lowlatency.is_lowlatency(),
         #[cfg(feature = "auth_usrpwd")]
         auth_id: UsrPwdId(None),
+        #[cfg(feature = "auth_pubkey")]
+        pubkey_id: PubKeyId(None),
         patch: state.transport.ext_patch.get(),
     };
 
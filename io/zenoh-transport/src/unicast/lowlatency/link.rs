@@ -225,7 +225,7 @@ async fn keepalive_task(
         tokio::select! {
             _ = interval.tick() => {
                 let keepailve = TransportMessageLowLatency {
-                    body: TransportBodyLowLatency::KeepAlive(KeepAlive),
+                    body: TransportBodyLowLatency::KeepAlive(KeepAlive::default()),
                 };
 
                 let guard = zasyncwrite!(link);
@@ -43,6 +43,8 @@ impl TransportUnicastLowlatency {
                     }
                 }
             }
+            #[cfg(feature = "stats")]
+            self.stats.inc_rx_n_msgs(1);
             callback.handle_message(msg)
         } else {
             tracing::debug!(
@@ -195,6 +195,9 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
         // Convert usrpwd auth id to AuthId
         #[cfg(feature = "auth_usrpwd")]
         auth_ids.push(self.config.auth_id.clone().into());
+        // Convert pubkey auth id to AuthId
+        #[cfg(feature = "auth_pubkey")]
+        auth_ids.push(self.config.pubkey_id.clone().into());
         auth_ids
     }
 
@@ -224,11 +227,32 @@ impl TransportUnicastTrait for TransportUnicastLowlatency {
         self.stats.clone()
     }
 
+    #[cfg(feature = "stats")]
+    fn links_stats(&self) -> Vec<(Link, crate::stats::TransportStatsReport)> {
+        // The transport has a single link: its statistics are the transport ones
+        let report = self.stats.report();
+        self.get_links()
+            .into_iter()
+            .map(|link| (link, report.clone()))
+            .collect()
+    }
+
+    #[cfg(feature = "stats")]
+    fn priorities_stats(
+        &self,
+    ) -> Vec<(
+        zenoh_protocol::core::Priority,
+        crate::stats::TransportPriorityStatsReport,
+    )> {
+        // Priorities are not supported by the low latency transport
+        vec![]
+    }
+
     /*************************************/
     /*                TX                 */
     /*************************************/
-    fn schedule(&self, msg: NetworkMessage) -> ZResult<()> {
-        self.internal_schedule(msg)
+    fn schedule(&self, msg: NetworkMessage) -> ZResult<bool> {
+        self.internal_schedule(msg).map(|_| true)
     }
 
     /*************************************/
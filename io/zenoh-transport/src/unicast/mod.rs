@@ -43,6 +43,8 @@ use super::{TransportPeer, TransportPeerEventHandler};
 #[cfg(feature = "shared-memory")]
 use crate::shm::TransportShmConfig;
 use crate::unicast::authentication::AuthId;
+#[cfg(feature = "auth_pubkey")]
+use crate::unicast::establishment::ext::auth::PubKeyId;
 #[cfg(feature = "auth_usrpwd")]
 use crate::unicast::establishment::ext::auth::UsrPwdId;
 
@@ -63,6 +65,8 @@ pub(crate) struct TransportConfigUnicast {
     pub(crate) is_lowlatency: bool,
     #[cfg(feature = "auth_usrpwd")]
     pub(crate) auth_id: UsrPwdId,
+    #[cfg(feature = "auth_pubkey")]
+    pub(crate) pubkey_id: PubKeyId,
     pub(crate) patch: PatchType,
 }
 
@@ -91,6 +95,12 @@ impl TransportUnicast {
         Ok(transport.get_whatami())
     }
 
+    #[inline(always)]
+    pub fn get_patch(&self) -> ZResult<PatchType> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_config().patch)
+    }
+
     #[cfg(feature = "shared-memory")]
     #[inline(always)]
     pub fn is_shm(&self) -> ZResult<bool> {
@@ -128,8 +138,9 @@ impl TransportUnicast {
         Ok(transport.get_auth_ids())
     }
 
+    /// Schedules a message for transmission, returning `false` if it was dropped.
     #[inline(always)]
-    pub fn schedule(&self, message: NetworkMessage) -> ZResult<()> {
+    pub fn schedule(&self, message: NetworkMessage) -> ZResult<bool> {
         let transport = self.get_inner()?;
         transport.schedule(message)
     }
@@ -147,6 +158,23 @@ impl TransportUnicast {
     pub fn get_stats(&self) -> ZResult<Arc<crate::stats::TransportStats>> {
         Ok(self.get_inner()?.stats())
     }
+
+    #[cfg(feature = "stats")]
+    pub fn get_links_stats(&self) -> ZResult<Vec<(Link, crate::stats::TransportStatsReport)>> {
+        Ok(self.get_inner()?.links_stats())
+    }
+
+    #[cfg(feature = "stats")]
+    pub fn get_priorities_stats(
+        &self,
+    ) -> ZResult<
+        Vec<(
+            zenoh_protocol::core::Priority,
+            crate::stats::TransportPriorityStatsReport,
+        )>,
+    > {
+        Ok(self.get_inner()?.priorities_stats())
+    }
 }
 
 impl From<&Arc<dyn TransportUnicastTrait>> for TransportUnicast {
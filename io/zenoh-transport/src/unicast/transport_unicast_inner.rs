@@ -70,6 +70,15 @@ pub(crate) trait TransportUnicastTrait: Send + Sync {
     fn get_config(&self) -> &TransportConfigUnicast;
     #[cfg(feature = "stats")]
     fn stats(&self) -> Arc<crate::stats::TransportStats>;
+    #[cfg(feature = "stats")]
+    fn links_stats(&self) -> Vec<(Link, crate::stats::TransportStatsReport)>;
+    #[cfg(feature = "stats")]
+    fn priorities_stats(
+        &self,
+    ) -> Vec<(
+        zenoh_protocol::core::Priority,
+        crate::stats::TransportPriorityStatsReport,
+    )>;
 
     /*************************************/
     /*               LINK                */
@@ -84,7 +93,8 @@ pub(crate) trait TransportUnicastTrait: Send + Sync {
     /*************************************/
     /*                TX                 */
     /*************************************/
-    fn schedule(&self, msg: NetworkMessage) -> ZResult<()>;
+    /// Schedules a message for transmission, returning `false` if it was dropped.
+    fn schedule(&self, msg: NetworkMessage) -> ZResult<bool>;
 
     /*************************************/
     /*            TERMINATION            */
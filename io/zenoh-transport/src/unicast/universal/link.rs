@@ -20,7 +20,14 @@ use zenoh_protocol::transport::{KeepAlive, TransportMessage};
 use zenoh_result::{zerror, ZResult};
 use zenoh_sync::{RecyclingObject, RecyclingObjectPool};
 #[cfg(feature = "stats")]
-use {crate::common::stats::TransportStats, std::sync::Arc};
+use {
+    crate::common::stats::{TransportPriorityStats, TransportStats},
+    std::{
+        sync::{Arc, OnceLock},
+        time::Instant,
+    },
+    zenoh_protocol::transport::keepalive,
+};
 
 use super::transport::TransportUnicastUniversal;
 use crate::{
@@ -41,6 +48,9 @@ pub(super) struct TransportLinkUnicastUniversal {
     pub(super) link: TransportLinkUnicast,
     // The transmission pipeline
     pub(super) pipeline: TransmissionPipelineProducer,
+    // The link statistics, accounted in the transport statistics as well
+    #[cfg(feature = "stats")]
+    pub(super) stats: Arc<TransportStats>,
     // The task handling substruct
     tracker: TaskTracker,
     token: CancellationToken,
@@ -74,6 +84,8 @@ impl TransportLinkUnicastUniversal {
         let result = Self {
             link,
             pipeline: producer,
+            #[cfg(feature = "stats")]
+            stats: Arc::new(TransportStats::new(Some(transport.stats.clone()))),
             tracker: TaskTracker::new(),
             token: CancellationToken::new(),
         };
@@ -90,6 +102,10 @@ impl TransportLinkUnicastUniversal {
         // Spawn the TX task
         let mut tx = self.link.tx();
         let token = self.token.clone();
+        #[cfg(feature = "stats")]
+        let stats = self.stats.clone();
+        #[cfg(feature = "stats")]
+        let priority_stats = transport.priority_stats.clone();
         let task = async move {
             let res = tx_task(
                 consumer,
@@ -97,7 +113,9 @@ impl TransportLinkUnicastUniversal {
                 keep_alive,
                 token,
                 #[cfg(feature = "stats")]
-                transport.stats.clone(),
+                stats,
+                #[cfg(feature = "stats")]
+                priority_stats,
             )
             .await;
 
@@ -119,6 +137,8 @@ impl TransportLinkUnicastUniversal {
         let reliability = self.link.config.reliability;
         let mut rx = self.link.rx();
         let token = self.token.clone();
+        #[cfg(feature = "stats")]
+        let stats = self.stats.clone();
         let task = async move {
             // Start the consume task
             let res = rx_task(
@@ -127,6 +147,8 @@ impl TransportLinkUnicastUniversal {
                 lease,
                 transport.manager.config.link_rx_buffer_size,
                 token,
+                #[cfg(feature = "stats")]
+                stats,
             )
             .await;
 
@@ -168,6 +190,14 @@ impl TransportLinkUnicastUniversal {
     }
 }
 
+/// Microseconds elapsed since the first call, used as the value of the keep alive
+/// Ping extension to measure the round trip time of the link.
+#[cfg(feature = "stats")]
+pub(super) fn keepalive_timestamp() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_micros() as u64
+}
+
 /*************************************/
 /*              TASKS                */
 /*************************************/
@@ -177,6 +207,7 @@ async fn tx_task(
     keep_alive: Duration,
     token: CancellationToken,
     #[cfg(feature = "stats")] stats: Arc<TransportStats>,
+    #[cfg(feature = "stats")] priority_stats: Arc<[TransportPriorityStats]>,
 ) -> ZResult<()> {
     loop {
         tokio::select! {
@@ -189,6 +220,15 @@ async fn tx_task(
                         {
                             stats.inc_tx_t_msgs(batch.stats.t_msgs);
                             stats.inc_tx_bytes(batch.len() as usize);
+                            stats
+                                .tx_batch_fill_ratio
+                                .observe(batch.len() as f64 / batch.config.mtu as f64);
+                            let now = Instant::now();
+                            for (push_time, priority) in batch.stats.push_times.iter() {
+                                priority_stats[*priority as usize]
+                                    .tx_queuing_delay
+                                    .observe((now - *push_time).as_secs_f64());
+                            }
                         }
 
                         // Reinsert the batch into the queue
@@ -201,7 +241,14 @@ async fn tx_task(
                     Err(_) => {
                         // A timeout occurred, no control/data messages have been sent during
                         // the keep_alive period, we need to send a KeepAlive message
-                        let message: TransportMessage = KeepAlive.into();
+                        #[allow(unused_mut)] // Used when stats feature is enabled
+                        let mut keep_alive = KeepAlive::default();
+                        #[cfg(feature = "stats")]
+                        {
+                            keep_alive.ext_ping =
+                                Some(keepalive::ext::Ping::new(keepalive_timestamp()));
+                        }
+                        let message: TransportMessage = keep_alive.into();
 
                         #[allow(unused_variables)] // Used when stats feature is enabled
                         let n = link.send(&message).await?;
@@ -242,6 +289,7 @@ async fn rx_task(
     lease: Duration,
     rx_buffer_size: usize,
     token: CancellationToken,
+    #[cfg(feature = "stats")] stats: Arc<TransportStats>,
 ) -> ZResult<()> {
     async fn read<T, F>(
         link: &mut TransportLinkUnicastRx,
@@ -278,10 +326,14 @@ async fn rx_task(
                 let batch = batch.map_err(|_| zerror!("{}: expired after {} milliseconds", link, lease.as_millis()))??;
                 #[cfg(feature = "stats")]
                 {
-
-                    transport.stats.inc_rx_bytes(2 + batch.len()); // Account for the batch len encoding (16 bits)
+                    stats.inc_rx_bytes(2 + batch.len()); // Account for the batch len encoding (16 bits)
                 }
-                transport.read_messages(batch, &l)?;
+                transport.read_messages(
+                    batch,
+                    &l,
+                    #[cfg(feature = "stats")]
+                    &stats,
+                )?;
             }
 
             _ = token.cancelled() => break
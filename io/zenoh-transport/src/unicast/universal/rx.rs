@@ -18,11 +18,15 @@ use zenoh_link::Link;
 use zenoh_protocol::{
     core::{Priority, Reliability},
     network::NetworkMessage,
-    transport::{Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn},
+    transport::{
+        keepalive, Close, Fragment, Frame, KeepAlive, TransportBody, TransportMessage, TransportSn,
+    },
 };
 use zenoh_result::{bail, zerror, ZResult};
 
 use super::transport::TransportUnicastUniversal;
+#[cfg(feature = "stats")]
+use crate::stats::TransportStats;
 use crate::{
     common::{
         batch::{Decode, RBatch},
@@ -41,6 +45,7 @@ impl TransportUnicastUniversal {
         callback: &dyn TransportPeerEventHandler,
         #[allow(unused_mut)] // shared-memory feature requires mut
         mut msg: NetworkMessage,
+        #[cfg(feature = "stats")] stats: &TransportStats,
     ) -> ZResult<()> {
         #[cfg(feature = "shared-memory")]
         {
@@ -51,6 +56,11 @@ impl TransportUnicastUniversal {
                 }
             }
         }
+        #[cfg(feature = "stats")]
+        {
+            stats.inc_rx_n_msgs(1);
+            self.priority_stats[msg.priority() as usize].inc_rx_n_msgs(1);
+        }
         callback.handle_message(msg)
     }
 
@@ -71,7 +81,42 @@ impl TransportUnicastUniversal {
         Ok(())
     }
 
-    fn handle_frame(&self, frame: Frame) -> ZResult<()> {
+    fn handle_keepalive(&self, keep_alive: KeepAlive, link: &Link) {
+        if keep_alive.ext_ping.is_none() && keep_alive.ext_pong.is_none() {
+            return;
+        }
+        let links = zread!(self.links);
+        let Some(tl) = links.iter().find(|tl| {
+            Link::new_unicast(
+                &tl.link.link,
+                tl.link.config.priorities.clone(),
+                tl.link.config.reliability,
+            )
+            .eq(link)
+        }) else {
+            return;
+        };
+        // Echo the ping for the remote to measure the round trip time
+        if let Some(ping) = keep_alive.ext_ping {
+            let pong = KeepAlive {
+                ext_ping: None,
+                ext_pong: Some(keepalive::ext::Pong::new(ping.value)),
+            };
+            tl.pipeline
+                .push_transport_message(pong.into(), Priority::Control);
+        }
+        #[cfg(feature = "stats")]
+        if let Some(pong) = keep_alive.ext_pong {
+            let rtt = super::link::keepalive_timestamp().saturating_sub(pong.value);
+            tl.stats.keepalive_rtt.observe(rtt as f64 / 1_000_000.0);
+        }
+    }
+
+    fn handle_frame(
+        &self,
+        frame: Frame,
+        #[cfg(feature = "stats")] stats: &TransportStats,
+    ) -> ZResult<()> {
         let Frame {
             reliability,
             sn,
@@ -104,7 +149,12 @@ impl TransportUnicastUniversal {
         let callback = zread!(self.callback).clone();
         if let Some(callback) = callback.as_ref() {
             for msg in payload.drain(..) {
-                self.trigger_callback(callback.as_ref(), msg)?;
+                self.trigger_callback(
+                    callback.as_ref(),
+                    msg,
+                    #[cfg(feature = "stats")]
+                    stats,
+                )?;
             }
         } else {
             tracing::debug!(
@@ -117,7 +167,11 @@ impl TransportUnicastUniversal {
         Ok(())
     }
 
-    fn handle_fragment(&self, fragment: Fragment) -> ZResult<()> {
+    fn handle_fragment(
+        &self,
+        fragment: Fragment,
+        #[cfg(feature = "stats")] stats: &TransportStats,
+    ) -> ZResult<()> {
         let Fragment {
             reliability,
             more,
@@ -177,7 +231,12 @@ impl TransportUnicastUniversal {
             if let Some(msg) = guard.defrag.defragment() {
                 let callback = zread!(self.callback).clone();
                 if let Some(callback) = callback.as_ref() {
-                    return self.trigger_callback(callback.as_ref(), msg);
+                    return self.trigger_callback(
+                        callback.as_ref(),
+                        msg,
+                        #[cfg(feature = "stats")]
+                        stats,
+                    );
                 } else {
                     tracing::debug!(
                         "Transport: {}. No callback available, dropping messages: {:?}",
@@ -214,7 +273,13 @@ impl TransportUnicastUniversal {
         Ok(true)
     }
 
-    pub(super) fn read_messages(&self, mut batch: RBatch, link: &Link) -> ZResult<()> {
+    /// Reads the messages of a batch received on `link`, whose statistics are `stats`.
+    pub(super) fn read_messages(
+        &self,
+        mut batch: RBatch,
+        link: &Link,
+        #[cfg(feature = "stats")] stats: &TransportStats,
+    ) -> ZResult<()> {
         while !batch.is_empty() {
             let msg: TransportMessage = batch
                 .decode()
@@ -224,16 +289,24 @@ impl TransportUnicastUniversal {
 
             #[cfg(feature = "stats")]
             {
-                self.stats.inc_rx_t_msgs(1);
+                stats.inc_rx_t_msgs(1);
             }
 
             match msg.body {
-                TransportBody::Frame(msg) => self.handle_frame(msg)?,
-                TransportBody::Fragment(fragment) => self.handle_fragment(fragment)?,
+                TransportBody::Frame(msg) => self.handle_frame(
+                    msg,
+                    #[cfg(feature = "stats")]
+                    stats,
+                )?,
+                TransportBody::Fragment(fragment) => self.handle_fragment(
+                    fragment,
+                    #[cfg(feature = "stats")]
+                    stats,
+                )?,
                 TransportBody::Close(Close { reason, session }) => {
                     self.handle_close(link, reason, session)?
                 }
-                TransportBody::KeepAlive(KeepAlive { .. }) => {}
+                TransportBody::KeepAlive(keep_alive) => self.handle_keepalive(keep_alive, link),
                 _ => {
                     tracing::debug!(
                         "Transport: {}. Message handling not implemented: {:?}",
@@ -29,7 +29,9 @@ use zenoh_protocol::{
 use zenoh_result::{bail, zerror, ZResult};
 
 #[cfg(feature = "stats")]
-use crate::stats::TransportStats;
+use crate::stats::{
+    TransportPriorityStats, TransportPriorityStatsReport, TransportStats, TransportStatsReport,
+};
 use crate::{
     common::priority::{TransportPriorityRx, TransportPriorityTx},
     unicast::{
@@ -66,6 +68,9 @@ pub(crate) struct TransportUnicastUniversal {
     // Transport statistics
     #[cfg(feature = "stats")]
     pub(super) stats: Arc<TransportStats>,
+    // Transport statistics per priority
+    #[cfg(feature = "stats")]
+    pub(super) priority_stats: Arc<[TransportPriorityStats]>,
 }
 
 impl TransportUnicastUniversal {
@@ -110,6 +115,10 @@ impl TransportUnicastUniversal {
             alive: Arc::new(AsyncMutex::new(false)),
             #[cfg(feature = "stats")]
             stats,
+            #[cfg(feature = "stats")]
+            priority_stats: (0..Priority::NUM)
+                .map(|_| TransportPriorityStats::default())
+                .collect(),
         });
 
         Ok(t)
@@ -350,6 +359,23 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         self.stats.clone()
     }
 
+    #[cfg(feature = "stats")]
+    fn links_stats(&self) -> Vec<(Link, TransportStatsReport)> {
+        zread!(self.links)
+            .iter()
+            .map(|l| (l.link.link(), l.stats.report()))
+            .collect()
+    }
+
+    #[cfg(feature = "stats")]
+    fn priorities_stats(&self) -> Vec<(Priority, TransportPriorityStatsReport)> {
+        self.priority_stats
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| Some((Priority::try_from(i as u8).ok()?, s.report())))
+            .collect()
+    }
+
     /*************************************/
     /*           TERMINATION             */
     /*************************************/
@@ -391,14 +417,17 @@ impl TransportUnicastTrait for TransportUnicastUniversal {
         // Convert usrpwd auth id to AuthId
         #[cfg(feature = "auth_usrpwd")]
         auth_ids.push(self.config.auth_id.clone().into());
+        // Convert pubkey auth id to AuthId
+        #[cfg(feature = "auth_pubkey")]
+        auth_ids.push(self.config.pubkey_id.clone().into());
         auth_ids
     }
 
     /*************************************/
     /*                TX                 */
     /*************************************/
-    fn schedule(&self, msg: NetworkMessage) -> ZResult<()> {
-        self.internal_schedule(msg).map(|_| ())
+    fn schedule(&self, msg: NetworkMessage) -> ZResult<bool> {
+        self.internal_schedule(msg)
     }
 
     fn add_debug_fields<'a, 'b: 'a, 'c>(
@@ -94,6 +94,8 @@ impl TransportUnicastUniversal {
             );
 
             // No Link found
+            #[cfg(feature = "stats")]
+            self.stats.inc_tx_n_dropped(1);
             return Ok(false);
         };
 
@@ -102,6 +104,8 @@ impl TransportUnicastUniversal {
             .expect("transport link index should be valid");
 
         let pipeline = transport_link.pipeline.clone();
+        #[cfg(feature = "stats")]
+        let stats = transport_link.stats.clone();
         tracing::trace!(
             "Scheduled {:?} for transmission to {} ({})",
             msg,
@@ -114,6 +118,13 @@ impl TransportUnicastUniversal {
         drop(transport_links);
         let droppable = msg.is_droppable();
         let push = pipeline.push_network_message(msg)?;
+        // The link statistics are accounted in the transport ones as well
+        #[cfg(feature = "stats")]
+        if push {
+            stats.inc_tx_n_msgs(1);
+        } else {
+            stats.inc_tx_n_dropped(1);
+        }
         if !push && !droppable {
             tracing::error!(
                 "Unable to push non droppable network message to {}. Closing transport!",
@@ -147,13 +158,16 @@ impl TransportUnicastUniversal {
             }
         }
 
+        #[cfg(feature = "stats")]
+        let priority_stats = &self.priority_stats[msg.priority() as usize];
+
         let res = self.schedule_on_link(msg)?;
 
         #[cfg(feature = "stats")]
         if res {
-            self.stats.inc_tx_n_msgs(1);
+            priority_stats.inc_tx_n_msgs(1);
         } else {
-            self.stats.inc_tx_n_dropped(1);
+            priority_stats.inc_tx_n_dropped(1);
         }
 
         Ok(res)
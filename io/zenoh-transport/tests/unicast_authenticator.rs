@@ -103,7 +103,8 @@ async fn auth_pubkey(endpoint: &EndPoint, lowlatency_transport: bool) {
     use rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
     use zenoh_transport::{
         unicast::{
-            establishment::ext::auth::AuthPubKey,
+            authentication::AuthId,
+            establishment::ext::auth::{AuthPubKey, ZPublicKey},
             test_helpers::make_basic_transport_manager_builder,
         },
         TransportManager,
@@ -121,6 +122,9 @@ async fn auth_pubkey(endpoint: &EndPoint, lowlatency_transport: bool) {
     ]);
     let e = BigUint::from_bytes_le(&[0x01, 0x00, 0x01]);
     let client01_pub_key = RsaPublicKey::new(n, e).unwrap();
+    let client01_fingerprint = ZPublicKey::from(client01_pub_key.clone())
+        .fingerprint()
+        .unwrap();
 
     let n = BigUint::from_bytes_le(&[
         0x41, 0x74, 0xc6, 0x40, 0x18, 0x63, 0xbd, 0x59, 0xe6, 0x0d, 0xe9, 0x23, 0x3e, 0x95, 0xca,
@@ -320,6 +324,12 @@ async fn auth_pubkey(endpoint: &EndPoint, lowlatency_transport: bool) {
     println!("Transport Authenticator PubKey [2a1]");
     let c_ses1 = ztimeout!(client01_manager.open_transport_unicast(endpoint.clone())).unwrap();
     assert_eq!(c_ses1.get_links().unwrap().len(), 1);
+    // -> The router should report the fingerprint of the client01 public key
+    let r_ses1 = ztimeout!(router_manager.get_transport_unicast(&client01_id)).unwrap();
+    assert!(r_ses1
+        .get_auth_ids()
+        .unwrap()
+        .contains(&AuthId::PubKeyFingerprint(client01_fingerprint)));
 
     /* [2b] */
     // Open a first transport from client02 to the router
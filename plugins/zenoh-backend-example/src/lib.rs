@@ -23,8 +23,8 @@ use zenoh::{
 };
 use zenoh_backend_traits::{
     config::{StorageConfig, VolumeConfig},
-    Capability, History, Persistence, Storage, StorageInsertionResult, StoredData, Volume,
-    VolumeInstance,
+    Capability, History, KeyNotFound, Persistence, Storage, StorageInsertionResult, StoredData,
+    Volume, VolumeInstance,
 };
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin};
 
@@ -67,6 +67,7 @@ impl Volume for ExampleBackend {
         Capability {
             persistence: Persistence::Volatile,
             history: History::Latest,
+            scan: false,
         }
     }
     async fn create_storage(&self, _props: StorageConfig) -> ZResult<Box<dyn Storage>> {
@@ -123,7 +124,7 @@ impl Storage for ExampleStorage {
     ) -> ZResult<Vec<StoredData>> {
         match self.map.read().await.get(&key) {
             Some(v) => Ok(vec![v.clone()]),
-            None => Err(format!("Key {:?} is not present", key).into()),
+            None => Err(KeyNotFound(key).into()),
         }
     }
 
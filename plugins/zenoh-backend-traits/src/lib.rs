@@ -58,6 +58,7 @@
 //!         Capability{
 //!             persistence: Persistence::Volatile,
 //!             history: History::Latest,
+//!             scan: false,
 //!         }
 //!     }
 //!
@@ -120,10 +121,14 @@
 //! }
 //! ```
 
+use std::fmt;
+
 use async_trait::async_trait;
 use const_format::concatcp;
+use futures::stream::{self, BoxStream, StreamExt};
 use zenoh::{
     bytes::{Encoding, ZBytes},
+    internal::bail,
     key_expr::{keyexpr, OwnedKeyExpr},
     time::Timestamp,
     Result as ZResult,
@@ -145,6 +150,9 @@ const FEATURES: &str =
 pub struct Capability {
     pub persistence: Persistence,
     pub history: History,
+    /// Whether the storages of this backend implement [`Storage::scan`].
+    /// If not, wildcard queries are resolved by listing all the entries and getting the matching keys one by one.
+    pub scan: bool,
 }
 
 /// Persistence is the guarantee expected from a storage in case of failures
@@ -181,6 +189,20 @@ pub struct StoredData {
     pub timestamp: Timestamp,
 }
 
+/// Error returned by [`Storage::get`] when the storage has no sample for the requested key.
+///
+/// Unlike the other errors, which are replied to the querier, it results in no reply at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyNotFound(pub Option<OwnedKeyExpr>);
+
+impl fmt::Display for KeyNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Key {:?} is not present", self.0)
+    }
+}
+
+impl std::error::Error for KeyNotFound {}
+
 /// Trait to be implemented by a Backend.
 #[async_trait]
 pub trait Volume: Send + Sync {
@@ -191,15 +213,26 @@ pub trait Volume: Send + Sync {
     /// Returns the capability of this backend
     fn get_capability(&self) -> Capability;
 
+    /// Returns the capability of a storage created with the given properties.
+    /// Backends whose storages can be configured to provide other guarantees than the backend's
+    /// defaults (e.g. to keep all the values of a key) should override this function.
+    fn get_storage_capability(&self, _props: &StorageConfig) -> Capability {
+        self.get_capability()
+    }
+
     /// Creates a storage configured with some properties.
     async fn create_storage(&self, props: StorageConfig) -> ZResult<Box<dyn Storage>>;
 }
 
 pub type VolumeInstance = Box<dyn Volume + 'static>;
 
+/// A stream of the results of a [`Storage`] operation, which may be produced lazily by the backend.
+pub type StorageStream<'a, T> = BoxStream<'a, ZResult<T>>;
+
 impl StructVersion for VolumeInstance {
+    // Bumped whenever the layout of `Capability` or the `Volume` and `Storage` traits change
     fn struct_version() -> u64 {
-        1
+        2
     }
     fn struct_features() -> &'static str {
         concatcp!(zenoh::FEATURES, crate::FEATURES)
@@ -247,6 +280,13 @@ pub trait Storage: Send + Sync {
     /// A key can be `None` if it matches the `strip_prefix` exactly.
     /// In order to avoid data loss, the storage must retrieve the `value` and `timestamp` associated with the `None` key
     /// in a manner suitable for the given backend technology
+    ///
+    /// Storages with the [`History::All`] capability are expected to return all the samples whose timestamp belongs
+    /// to the `_time` range of the `parameters` if it is present, and only the latest sample otherwise. The storage
+    /// manager resolves the range before calling this function, so that it only contains absolute bounds.
+    ///
+    /// A key without any sample must be reported with a [`KeyNotFound`] error: the other errors returned by this
+    /// function are replied to the querier as failures of the storage.
     async fn get(
         &mut self,
         key: Option<OwnedKeyExpr>,
@@ -257,4 +297,43 @@ pub trait Storage: Send + Sync {
     /// The latest Timestamp corresponding to each key is either the timestamp of the delete or put whichever is the latest.
     /// Remember to fetch the entry corresponding to the `None` key
     async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>>;
+
+    /// Function to retrieve the samples of all the keys intersecting a key expression in a single operation.
+    /// The `key_expr` is relative to the `strip_prefix` and may contain wildcards: backends are expected to
+    /// resolve it natively, e.g. as a range scan on its longest non-wild prefix.
+    /// The entry associated with the `None` key is retrieved separately with [`Storage::get`] and must not be returned.
+    /// The `_time` range of the `parameters` is handled as in [`Storage::get`], for each key.
+    ///
+    /// This function is only called if the backend advertises the [`Capability::scan`] flag.
+    async fn scan(
+        &mut self,
+        key_expr: &keyexpr,
+        _parameters: &str,
+    ) -> ZResult<Vec<(OwnedKeyExpr, StoredData)>> {
+        bail!("Storage does not support scanning key expression {key_expr}")
+    }
+
+    /// Streamed version of [`Storage::get`], for the storage manager to reply to queries without
+    /// holding all the samples in memory.
+    ///
+    /// The default implementation streams the result of [`Storage::get`]: backends able to retrieve
+    /// the samples incrementally should override it.
+    async fn get_stream<'a>(
+        &'a mut self,
+        key: Option<OwnedKeyExpr>,
+        parameters: &'a str,
+    ) -> ZResult<StorageStream<'a, StoredData>> {
+        let stored_data = self.get(key, parameters).await?;
+        Ok(stream::iter(stored_data.into_iter().map(Ok)).boxed())
+    }
+
+    /// Streamed version of [`Storage::scan`], with the same default as [`Storage::get_stream`].
+    async fn scan_stream<'a>(
+        &'a mut self,
+        key_expr: &'a keyexpr,
+        parameters: &'a str,
+    ) -> ZResult<StorageStream<'a, (OwnedKeyExpr, StoredData)>> {
+        let stored_data = self.scan(key_expr, parameters).await?;
+        Ok(stream::iter(stored_data.into_iter().map(Ok)).boxed())
+    }
 }
@@ -33,6 +33,8 @@ pub struct Config {
     pub work_thread_num: usize,
     #[serde(default = "default_max_block_thread_num")]
     pub max_block_thread_num: usize,
+    #[serde(default)]
+    pub metrics: bool,
     #[serde(default, deserialize_with = "deserialize_path")]
     __path__: Option<Vec<String>>,
     __required__: Option<bool>,
@@ -472,6 +472,36 @@ async fn query(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Respons
     }
 }
 
+async fn metrics(req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
+    tracing::trace!("Incoming GET metrics request: {:?}", req);
+
+    let key_expr = format!("@/{}/*/metrics", req.state().1);
+    match req.state().0.get(&key_expr).await {
+        Ok(receiver) => {
+            let mut metrics = String::new();
+            while let Ok(reply) = receiver.recv_async().await {
+                match reply.result() {
+                    Ok(sample) => {
+                        metrics.push_str(&sample.payload().try_to_string().unwrap_or_default())
+                    }
+                    Err(err) => tracing::warn!("Error retrieving metrics: {:?}", err),
+                }
+            }
+            // The metrics are rendered in the Prometheus text exposition format
+            Ok(response(
+                StatusCode::Ok,
+                "text/plain; version=0.0.4; charset=utf-8",
+                &metrics,
+            ))
+        }
+        Err(e) => Ok(response(
+            StatusCode::InternalServerError,
+            "text/plain",
+            &e.to_string(),
+        )),
+    }
+}
+
 async fn write(mut req: Request<(Arc<Session>, String)>) -> tide::Result<Response> {
     tracing::trace!("Incoming PUT request: {:?}", req);
     match req.body_bytes().await {
@@ -536,6 +566,9 @@ pub async fn run(runtime: Runtime, conf: Config) -> ZResult<()> {
             .allow_credentials(false),
     );
 
+    if conf.metrics {
+        app.at("/metrics").get(metrics);
+    }
     app.at("/")
         .get(query)
         .post(query)
@@ -0,0 +1,343 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
+use zenoh::{internal::bail, key_expr::OwnedKeyExpr, time::Timestamp, Result as ZResult};
+
+pub(crate) const LOG_FILE_NAME: &str = "log";
+const COMPACTION_FILE_NAME: &str = "log.compaction";
+
+/// Each record is preceded by the length of its content (u32) and the xxh3 checksum of its
+/// content (u64), both in little endian.
+const HEADER_LEN: u64 = 12;
+
+/// An update of a key, as written in the [Log].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub(crate) enum Record {
+    Put {
+        key: Option<OwnedKeyExpr>,
+        timestamp: Timestamp,
+        encoding: String,
+        payload: Vec<u8>,
+    },
+    Delete {
+        key: Option<OwnedKeyExpr>,
+        timestamp: Timestamp,
+    },
+}
+
+impl Record {
+    pub(crate) fn key(&self) -> &Option<OwnedKeyExpr> {
+        match self {
+            Record::Put { key, .. } | Record::Delete { key, .. } => key,
+        }
+    }
+
+    pub(crate) fn timestamp(&self) -> &Timestamp {
+        match self {
+            Record::Put { timestamp, .. } | Record::Delete { timestamp, .. } => timestamp,
+        }
+    }
+}
+
+/// The location in the [Log] of the latest [Record] of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IndexEntry {
+    pub(crate) timestamp: Timestamp,
+    pub(crate) deleted: bool,
+    offset: u64,
+    len: u64,
+}
+
+/// An append-only log of [Record]s, along with the index of the latest record of each key.
+///
+/// The index is not persisted: it is rebuilt by replaying the log when opening it. An invalid
+/// record reaching the end of the log, as a crash in the middle of a write would leave it, is
+/// discarded. An invalid record followed by other ones can't be the result of a crash: the log is
+/// then left untouched and fails to open.
+///
+/// Records superseded by a more recent one are only removed when the log is compacted: the live
+/// records are copied in a new file which then atomically replaces the log.
+pub(crate) struct Log {
+    dir: PathBuf,
+    file: File,
+    len: u64,
+    obsolete_len: u64,
+    index: HashMap<Option<OwnedKeyExpr>, IndexEntry>,
+    fsync: bool,
+}
+
+impl Log {
+    /// Opens the log stored in `dir`, creating it if needed.
+    ///
+    /// If `fsync` is set, every write is synchronised to the disk before returning, so that no
+    /// acknowledged update can be lost in case of a power failure.
+    pub(crate) fn open(dir: &Path, fsync: bool) -> ZResult<Log> {
+        fs::create_dir_all(dir)?;
+        // A compaction that did not complete leaves the log untouched
+        match fs::remove_file(dir.join(COMPACTION_FILE_NAME)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(dir.join(LOG_FILE_NAME))?;
+
+        let mut log = Log {
+            dir: dir.to_path_buf(),
+            file,
+            len: 0,
+            obsolete_len: 0,
+            index: HashMap::new(),
+            fsync,
+        };
+        log.replay()?;
+        Ok(log)
+    }
+
+    fn replay(&mut self) -> ZResult<()> {
+        let file_len = self.file.metadata()?.len();
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(0))?;
+
+        let mut records = Vec::new();
+        let mut offset = 0;
+        while let Some((record, len)) = read_record(&mut reader, file_len - offset)? {
+            records.push((record, offset, len));
+            offset += len;
+        }
+        drop(reader);
+
+        if offset < file_len {
+            if !self.is_torn_tail(offset, file_len)? {
+                bail!(
+                    "Corrupted record at offset {} of {:?}",
+                    offset,
+                    self.dir.join(LOG_FILE_NAME)
+                );
+            }
+            tracing::warn!(
+                "Discarding the last {} bytes of {:?}: incomplete or corrupted record",
+                file_len - offset,
+                self.dir.join(LOG_FILE_NAME)
+            );
+            self.file.set_len(offset)?;
+            self.file.sync_all()?;
+        }
+        self.len = offset;
+
+        for (record, offset, len) in records {
+            self.index_record(&record, offset, len);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the invalid record at `offset` reaches the end of the log.
+    fn is_torn_tail(&mut self, offset: u64, file_len: u64) -> ZResult<bool> {
+        if file_len - offset < HEADER_LEN {
+            return Ok(true);
+        }
+        let mut header = [0u8; HEADER_LEN as usize];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut header)?;
+        let content_len = u32::from_le_bytes(header[..4].try_into().unwrap()) as u64;
+        Ok(offset + HEADER_LEN + content_len >= file_len)
+    }
+
+    /// Returns the index of the latest record of each key, including the deleted ones.
+    pub(crate) fn index(&self) -> &HashMap<Option<OwnedKeyExpr>, IndexEntry> {
+        &self.index
+    }
+
+    /// Returns `true` if the superseded records take more than `threshold` bytes and more than
+    /// the live ones.
+    pub(crate) fn needs_compaction(&self, threshold: u64) -> bool {
+        self.obsolete_len > threshold.max(self.len - self.obsolete_len)
+    }
+
+    /// Appends a record at the end of the log and indexes it.
+    pub(crate) fn append(&mut self, record: &Record) -> ZResult<()> {
+        let buf = encode_record(record)?;
+        self.file.seek(SeekFrom::Start(self.len))?;
+        if let Err(e) = self.file.write_all(&buf) {
+            // Do not leave a partial record behind, it would end the replay of the log
+            let _ = self.file.set_len(self.len);
+            return Err(e.into());
+        }
+        if self.fsync {
+            self.file.sync_data()?;
+        }
+        let offset = self.len;
+        self.len += buf.len() as u64;
+        self.index_record(record, offset, buf.len() as u64);
+        Ok(())
+    }
+
+    /// Reads the record pointed by an entry of the index.
+    pub(crate) fn read(&mut self, entry: &IndexEntry) -> ZResult<Record> {
+        self.file.seek(SeekFrom::Start(entry.offset))?;
+        match read_record(&mut &self.file, entry.len)? {
+            Some((record, _)) => Ok(record),
+            None => bail!(
+                "Corrupted record at offset {} of {:?}",
+                entry.offset,
+                self.dir.join(LOG_FILE_NAME)
+            ),
+        }
+    }
+
+    /// Rewrites the log with only the latest record of each key, dropping the deletions older
+    /// than `tombstone_lifespan`.
+    pub(crate) fn compact(&mut self, tombstone_lifespan: Duration) -> ZResult<()> {
+        let now = SystemTime::now();
+        let mut entries: Vec<(Option<OwnedKeyExpr>, IndexEntry)> = self
+            .index
+            .iter()
+            .filter(|(_, entry)| {
+                !entry.deleted
+                    || now
+                        .duration_since(entry.timestamp.get_time().to_system_time())
+                        .map_or(true, |age| age < tombstone_lifespan)
+            })
+            .map(|(key, entry)| (key.clone(), *entry))
+            .collect();
+        entries.sort_by_key(|(_, entry)| entry.offset);
+
+        let compaction_path = self.dir.join(COMPACTION_FILE_NAME);
+        let mut compaction_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&compaction_path)?;
+        let mut index = HashMap::with_capacity(entries.len());
+        let mut offset = 0;
+        for (key, entry) in entries {
+            let buf = encode_record(&self.read(&entry)?)?;
+            compaction_file.write_all(&buf)?;
+            index.insert(key, IndexEntry { offset, ..entry });
+            offset += buf.len() as u64;
+        }
+        compaction_file.sync_all()?;
+
+        fs::rename(&compaction_path, self.dir.join(LOG_FILE_NAME))?;
+        // From now on the compacted file is the log, whatever happens next
+        tracing::debug!(
+            "Compacted {:?} from {} to {} bytes",
+            self.dir.join(LOG_FILE_NAME),
+            self.len,
+            offset
+        );
+        self.file = compaction_file;
+        self.len = offset;
+        self.obsolete_len = 0;
+        self.index = index;
+
+        // Persist the rename itself. If it fails, the old log may come back after a power failure,
+        // which loses nothing but the compaction.
+        if let Err(e) = sync_dir(&self.dir) {
+            tracing::warn!("Unable to synchronise directory {:?}: {e}", self.dir);
+        }
+        Ok(())
+    }
+
+    fn index_record(&mut self, record: &Record, offset: u64, len: u64) {
+        let entry = IndexEntry {
+            timestamp: *record.timestamp(),
+            deleted: matches!(record, Record::Delete { .. }),
+            offset,
+            len,
+        };
+        match self.index.get_mut(record.key()) {
+            Some(latest) if latest.timestamp > entry.timestamp => {
+                self.obsolete_len += entry.len;
+            }
+            Some(latest) => {
+                self.obsolete_len += latest.len;
+                *latest = entry;
+            }
+            None => {
+                self.index.insert(record.key().clone(), entry);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+// Directories can't be opened as files on other platforms
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+fn encode_record(record: &Record) -> ZResult<Vec<u8>> {
+    let content = bincode::serialize(record)?;
+    let Ok(content_len) = u32::try_from(content.len()) else {
+        bail!("Record of {} bytes is too large", content.len());
+    };
+    let mut buf = Vec::with_capacity(HEADER_LEN as usize + content.len());
+    buf.extend_from_slice(&content_len.to_le_bytes());
+    buf.extend_from_slice(&xxh3_64(&content).to_le_bytes());
+    buf.extend_from_slice(&content);
+    Ok(buf)
+}
+
+/// Reads the next record and its length, returning `None` at the end of the log or if the record
+/// is incomplete or corrupted. No more than `max_len` bytes are read.
+fn read_record<R: Read>(reader: &mut R, max_len: u64) -> ZResult<Option<(Record, u64)>> {
+    let mut header = [0u8; HEADER_LEN as usize];
+    if max_len < HEADER_LEN || !read_exact_or_eof(reader, &mut header)? {
+        return Ok(None);
+    }
+    let content_len = u32::from_le_bytes(header[..4].try_into().unwrap()) as u64;
+    let checksum = u64::from_le_bytes(header[4..].try_into().unwrap());
+    if HEADER_LEN + content_len > max_len {
+        return Ok(None);
+    }
+    let mut content = vec![0u8; content_len as usize];
+    if !read_exact_or_eof(reader, &mut content)? || xxh3_64(&content) != checksum {
+        return Ok(None);
+    }
+    Ok(bincode::deserialize(&content)
+        .ok()
+        .map(|record| (record, HEADER_LEN + content_len)))
+}
+
+fn read_exact_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> ZResult<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/log.test.rs"]
+mod tests;
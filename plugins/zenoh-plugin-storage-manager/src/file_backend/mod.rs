@@ -0,0 +1,380 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde_json::Value;
+use zenoh::{
+    bytes::{Encoding, ZBytes},
+    internal::{bail, zenoh_home, zerror, zlock},
+    key_expr::{keyexpr, OwnedKeyExpr},
+    time::Timestamp,
+    Result as ZResult,
+};
+use zenoh_backend_traits::{
+    config::{StorageConfig, VolumeConfig},
+    *,
+};
+use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin};
+
+use self::log::{Log, Record};
+use crate::FILE_BACKEND_NAME;
+
+mod log;
+
+/// Directory of the storages when the volume has no `root` configured, relative to `ZENOH_HOME`.
+const DEFAULT_ROOT_DIR: &str = "zenoh_backend_file";
+const DEFAULT_COMPACTION_THRESHOLD: u64 = 1024 * 1024;
+
+/// A backend storing each of its storages in an append-only log file, under the directory set by
+/// the `root` field of its volume configuration:
+/// ```json5
+/// volumes: {
+///   file: {
+///     // defaults to "${ZENOH_HOME}/zenoh_backend_file"
+///     root: "/var/zenoh/storages",
+///   },
+/// },
+/// ```
+pub struct FileBackend {
+    config: VolumeConfig,
+    root: PathBuf,
+}
+
+impl Plugin for FileBackend {
+    type StartArgs = VolumeConfig;
+    type Instance = VolumeInstance;
+
+    const DEFAULT_NAME: &'static str = FILE_BACKEND_NAME;
+    const PLUGIN_VERSION: &'static str = plugin_version!();
+    const PLUGIN_LONG_VERSION: &'static str = plugin_long_version!();
+
+    fn start(_: &str, args: &VolumeConfig) -> ZResult<VolumeInstance> {
+        let root = match args.rest.get("root") {
+            Some(Value::String(root)) => PathBuf::from(root),
+            None => zenoh_home().join(DEFAULT_ROOT_DIR),
+            Some(_) => bail!(
+                "Invalid type for field `root` of volume `{}`. Only strings are accepted.",
+                args.name
+            ),
+        };
+        tracing::debug!("File backend storing its storages in {:?}", root);
+        Ok(Box::new(FileBackend {
+            config: args.clone(),
+            root,
+        }))
+    }
+}
+
+#[async_trait]
+impl Volume for FileBackend {
+    fn get_admin_status(&self) -> serde_json::Value {
+        self.config.to_json_value()
+    }
+
+    fn get_capability(&self) -> Capability {
+        Capability {
+            persistence: Persistence::Durable,
+            history: History::Latest,
+            scan: true,
+        }
+    }
+
+    async fn create_storage(&self, properties: StorageConfig) -> ZResult<Box<dyn Storage>> {
+        tracing::debug!("Create File Storage with configuration: {:?}", properties);
+        // Opening the log replays it, and may compact it
+        let root = self.root.clone();
+        let storage = tokio::task::spawn_blocking(move || FileStorage::new(&root, properties))
+            .await
+            .map_err(|e| zerror!("File storage creation failed: {e}"))??;
+        Ok(Box::new(storage))
+    }
+}
+
+/// A storage of the file backend, configured with the fields of its `volume` object:
+/// ```json5
+/// volume: {
+///   id: "file",
+///   // directory of the storage, relative to the volume's `root`. Defaults to the storage name.
+///   dir: "demo",
+///   // synchronise each update to the disk before acknowledging it. Defaults to false.
+///   fsync: true,
+///   // size, in bytes, that the superseded updates must exceed for the log to be compacted.
+///   compaction_threshold: 1048576,
+/// }
+/// ```
+/// Deletions are kept in the log, so that older updates received afterwards are discarded, until
+/// they are older than the `lifespan` of the `garbage_collection` configuration of the storage.
+///
+/// The I/O on the log, including its compaction, runs on blocking threads.
+struct FileStorage {
+    config: StorageConfig,
+    log: Arc<Mutex<StorageLog>>,
+}
+
+/// The log of a [`FileStorage`], along with its compaction settings.
+struct StorageLog {
+    name: String,
+    log: Log,
+    compaction_threshold: u64,
+    tombstone_lifespan: Duration,
+}
+
+impl FileStorage {
+    fn new(root: &Path, config: StorageConfig) -> ZResult<FileStorage> {
+        let volume_cfg = match &config.volume_cfg {
+            Value::Object(volume_cfg) => volume_cfg.clone(),
+            _ => Default::default(),
+        };
+        let dir = match volume_cfg.get("dir") {
+            Some(Value::String(dir)) => PathBuf::from(dir),
+            None => PathBuf::from(&config.name),
+            Some(_) => bail!(
+                "Invalid type for field `dir` of the file storage `{}`. Only strings are accepted.",
+                config.name
+            ),
+        };
+        if !dir
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!(
+                "Invalid value for field `dir` of the file storage `{}`: {:?} must be a relative \
+                 path within the volume's root.",
+                config.name,
+                dir
+            )
+        }
+        let fsync = match volume_cfg.get("fsync") {
+            Some(Value::Bool(fsync)) => *fsync,
+            None => false,
+            Some(_) => bail!(
+                "Invalid type for field `fsync` of the file storage `{}`. Only booleans are \
+                 accepted.",
+                config.name
+            ),
+        };
+        let compaction_threshold = match volume_cfg.get("compaction_threshold") {
+            Some(threshold) => match threshold.as_u64() {
+                Some(threshold) => threshold,
+                None => bail!(
+                    "Invalid type for field `compaction_threshold` of the file storage `{}`. Only \
+                     integer values are accepted.",
+                    config.name
+                ),
+            },
+            None => DEFAULT_COMPACTION_THRESHOLD,
+        };
+
+        let mut log = StorageLog {
+            name: config.name.clone(),
+            log: Log::open(&root.join(dir), fsync)?,
+            compaction_threshold,
+            tombstone_lifespan: config.garbage_collection_config.lifespan,
+        };
+        log.compact_if_needed();
+        Ok(FileStorage {
+            config,
+            log: Arc::new(Mutex::new(log)),
+        })
+    }
+
+    /// Runs an operation on the log on a blocking thread.
+    async fn with_log<T, F>(&self, f: F) -> ZResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut StorageLog) -> ZResult<T> + Send + 'static,
+    {
+        run_with_log(self.log.clone(), f).await
+    }
+}
+
+async fn run_with_log<T, F>(log: Arc<Mutex<StorageLog>>, f: F) -> ZResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut StorageLog) -> ZResult<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&mut zlock!(log)))
+        .await
+        .map_err(|e| zerror!("File storage operation failed: {e}"))?
+}
+
+impl StorageLog {
+    /// Returns `true` if an update of the key with the given timestamp is older than its latest.
+    fn is_outdated(&self, key: &Option<OwnedKeyExpr>, timestamp: &Timestamp) -> bool {
+        self.log
+            .index()
+            .get(key)
+            .is_some_and(|entry| entry.timestamp >= *timestamp)
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.log.needs_compaction(self.compaction_threshold) {
+            if let Err(e) = self.log.compact(self.tombstone_lifespan) {
+                tracing::warn!("Compaction of file storage '{}' failed: {e}", self.name);
+            }
+        }
+    }
+
+    fn read(&mut self, key: &Option<OwnedKeyExpr>) -> ZResult<Option<StoredData>> {
+        let Some(entry) = self.log.index().get(key).filter(|e| !e.deleted).copied() else {
+            return Ok(None);
+        };
+        match self.log.read(&entry)? {
+            Record::Put {
+                timestamp,
+                encoding,
+                payload,
+                ..
+            } => Ok(Some(StoredData {
+                payload: payload.into(),
+                encoding: encoding.into(),
+                timestamp,
+            })),
+            Record::Delete { .. } => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for FileStorage {
+    fn get_admin_status(&self) -> serde_json::Value {
+        self.config.to_json_value()
+    }
+
+    async fn put(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        payload: ZBytes,
+        encoding: Encoding,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        tracing::trace!("put for {:?}", key);
+        let record = Record::Put {
+            key,
+            timestamp,
+            encoding: encoding.to_string(),
+            payload: payload.to_bytes().into_owned(),
+        };
+        self.with_log(move |log| {
+            let key = record.key();
+            if log.is_outdated(key, &timestamp) {
+                return Ok(StorageInsertionResult::Outdated);
+            }
+            let replaced = log.log.index().get(key).is_some_and(|entry| !entry.deleted);
+            log.log.append(&record)?;
+            log.compact_if_needed();
+            if replaced {
+                Ok(StorageInsertionResult::Replaced)
+            } else {
+                Ok(StorageInsertionResult::Inserted)
+            }
+        })
+        .await
+    }
+
+    async fn delete(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        timestamp: Timestamp,
+    ) -> ZResult<StorageInsertionResult> {
+        tracing::trace!("delete for {:?}", key);
+        self.with_log(move |log| {
+            if log.is_outdated(&key, &timestamp) {
+                return Ok(StorageInsertionResult::Outdated);
+            }
+            log.log.append(&Record::Delete { key, timestamp })?;
+            log.compact_if_needed();
+            Ok(StorageInsertionResult::Deleted)
+        })
+        .await
+    }
+
+    async fn get(
+        &mut self,
+        key: Option<OwnedKeyExpr>,
+        _parameters: &str,
+    ) -> ZResult<Vec<StoredData>> {
+        tracing::trace!("get for {:?}", key);
+        let read_key = key.clone();
+        match self.with_log(move |log| log.read(&read_key)).await? {
+            Some(data) => Ok(vec![data]),
+            None => Err(KeyNotFound(key).into()),
+        }
+    }
+
+    async fn get_all_entries(&self) -> ZResult<Vec<(Option<OwnedKeyExpr>, Timestamp)>> {
+        self.with_log(|log| {
+            Ok(log
+                .log
+                .index()
+                .iter()
+                .filter(|(_, entry)| !entry.deleted)
+                .map(|(key, entry)| (key.clone(), entry.timestamp))
+                .collect())
+        })
+        .await
+    }
+
+    async fn scan(
+        &mut self,
+        key_expr: &keyexpr,
+        parameters: &str,
+    ) -> ZResult<Vec<(OwnedKeyExpr, StoredData)>> {
+        self.scan_stream(key_expr, parameters)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    async fn scan_stream<'a>(
+        &'a mut self,
+        key_expr: &'a keyexpr,
+        _parameters: &'a str,
+    ) -> ZResult<StorageStream<'a, (OwnedKeyExpr, StoredData)>> {
+        tracing::trace!("scan for {}", key_expr);
+        let ke = key_expr.to_owned();
+        let keys: Vec<OwnedKeyExpr> = self
+            .with_log(move |log| {
+                Ok(log
+                    .log
+                    .index()
+                    .iter()
+                    .filter_map(|(key, entry)| match key {
+                        Some(key) if !entry.deleted && ke.intersects(key) => Some(key.clone()),
+                        _ => None,
+                    })
+                    .collect())
+            })
+            .await?;
+        // The values are only read from the log when the stream is polled
+        let log = self.log.clone();
+        let entries = stream::iter(keys).filter_map(move |key| {
+            let log = log.clone();
+            let entry_key = Some(key.clone());
+            async move {
+                run_with_log(log, move |log| log.read(&entry_key))
+                    .await
+                    .transpose()
+                    .map(|data| data.map(|data| (key, data)))
+            }
+        });
+        Ok(entries.boxed())
+    }
+}
@@ -0,0 +1,201 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use uhlc::{Timestamp, HLC, NTP64};
+use zenoh::key_expr::OwnedKeyExpr;
+
+use super::{Log, Record, LOG_FILE_NAME};
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "zenoh-file-backend-{}",
+        uuid::Uuid::new_v4().simple()
+    ))
+}
+
+fn put(key: &str, timestamp: Timestamp, payload: &str) -> Record {
+    Record::Put {
+        key: Some(OwnedKeyExpr::from_str(key).unwrap()),
+        timestamp,
+        encoding: "text/plain".to_string(),
+        payload: payload.as_bytes().to_vec(),
+    }
+}
+
+fn delete(key: &str, timestamp: Timestamp) -> Record {
+    Record::Delete {
+        key: Some(OwnedKeyExpr::from_str(key).unwrap()),
+        timestamp,
+    }
+}
+
+fn latest(log: &mut Log, key: &str) -> Option<Record> {
+    let entry = *log
+        .index()
+        .get(&Some(OwnedKeyExpr::from_str(key).unwrap()))?;
+    Some(log.read(&entry).unwrap())
+}
+
+#[test]
+fn test_replay() {
+    let hlc = HLC::default();
+    let dir = temp_dir();
+
+    let put_a_1 = put("a", hlc.new_timestamp(), "1");
+    let put_b_1 = put("b", hlc.new_timestamp(), "1");
+    let put_a_2 = put("a", hlc.new_timestamp(), "2");
+    let delete_b = delete("b", hlc.new_timestamp());
+    let put_none = Record::Put {
+        key: None,
+        timestamp: hlc.new_timestamp(),
+        encoding: String::new(),
+        payload: Vec::new(),
+    };
+
+    let mut log = Log::open(&dir, false).unwrap();
+    for record in [&put_a_1, &put_b_1, &put_a_2, &delete_b, &put_none] {
+        log.append(record).unwrap();
+    }
+    drop(log);
+
+    let mut log = Log::open(&dir, true).unwrap();
+    assert_eq!(log.index().len(), 3);
+    assert_eq!(latest(&mut log, "a"), Some(put_a_2));
+    assert_eq!(latest(&mut log, "b"), Some(delete_b));
+    assert!(log.index()[&Some(OwnedKeyExpr::from_str("b").unwrap())].deleted);
+    let entry = log.index()[&None];
+    assert_eq!(log.read(&entry).unwrap(), put_none);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_recovery() {
+    let hlc = HLC::default();
+    let dir = temp_dir();
+
+    let put_a = put("a", hlc.new_timestamp(), "1");
+    let put_b = put("b", hlc.new_timestamp(), "1");
+
+    let mut log = Log::open(&dir, false).unwrap();
+    log.append(&put_a).unwrap();
+    log.append(&put_b).unwrap();
+    drop(log);
+    let valid_len = fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len();
+
+    // A crash in the middle of a write leaves an incomplete record
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(dir.join(LOG_FILE_NAME))
+        .unwrap();
+    file.write_all(&[42, 0, 0, 0, 1, 2, 3]).unwrap();
+    drop(file);
+
+    let mut log = Log::open(&dir, false).unwrap();
+    assert_eq!(
+        fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len(),
+        valid_len
+    );
+    assert_eq!(latest(&mut log, "a"), Some(put_a.clone()));
+    assert_eq!(latest(&mut log, "b"), Some(put_b.clone()));
+
+    // Records appended after a recovery are readable
+    let put_c = put("c", hlc.new_timestamp(), "1");
+    log.append(&put_c).unwrap();
+    drop(log);
+
+    let content = fs::read(dir.join(LOG_FILE_NAME)).unwrap();
+
+    // A corrupted record followed by valid ones fails the opening and leaves the log untouched
+    let mut corrupted = content.clone();
+    corrupted[valid_len as usize - 1] = !corrupted[valid_len as usize - 1];
+    fs::write(dir.join(LOG_FILE_NAME), &corrupted).unwrap();
+    assert!(Log::open(&dir, false).is_err());
+    assert_eq!(fs::read(dir.join(LOG_FILE_NAME)).unwrap(), corrupted);
+
+    // A corrupted last record is discarded
+    let mut corrupted = content;
+    let last = corrupted.len() - 1;
+    corrupted[last] = !corrupted[last];
+    fs::write(dir.join(LOG_FILE_NAME), &corrupted).unwrap();
+
+    let mut log = Log::open(&dir, false).unwrap();
+    assert_eq!(
+        fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len(),
+        valid_len
+    );
+    assert_eq!(log.index().len(), 2);
+    assert_eq!(latest(&mut log, "a"), Some(put_a));
+    assert_eq!(latest(&mut log, "b"), Some(put_b));
+    assert_eq!(latest(&mut log, "c"), None);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_compaction() {
+    let hlc = HLC::default();
+    let dir = temp_dir();
+
+    let mut log = Log::open(&dir, false).unwrap();
+    for i in 0..10 {
+        log.append(&put("a", hlc.new_timestamp(), &i.to_string()))
+            .unwrap();
+    }
+    let put_b = put("b", hlc.new_timestamp(), "1");
+    log.append(&put_b).unwrap();
+    let old_delete = delete("c", Timestamp::new(NTP64(1), *hlc.get_id()));
+    log.append(&old_delete).unwrap();
+    let recent_delete = delete("d", hlc.new_timestamp());
+    log.append(&recent_delete).unwrap();
+    // An outdated record does not supersede the latest one
+    log.append(&put("b", Timestamp::new(NTP64(1), *hlc.get_id()), "0"))
+        .unwrap();
+    let put_a = put("a", hlc.new_timestamp(), "10");
+    log.append(&put_a).unwrap();
+
+    assert!(log.needs_compaction(0));
+    assert!(!log.needs_compaction(1024 * 1024));
+    let len = fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len();
+    log.compact(Duration::from_secs(3600)).unwrap();
+    assert!(fs::metadata(dir.join(LOG_FILE_NAME)).unwrap().len() < len);
+    assert!(!log.needs_compaction(0));
+
+    assert_eq!(log.index().len(), 3);
+    assert_eq!(latest(&mut log, "a"), Some(put_a.clone()));
+    assert_eq!(latest(&mut log, "b"), Some(put_b.clone()));
+    assert_eq!(latest(&mut log, "c"), None);
+    assert_eq!(latest(&mut log, "d"), Some(recent_delete.clone()));
+
+    let put_e = put("e", hlc.new_timestamp(), "1");
+    log.append(&put_e).unwrap();
+    drop(log);
+
+    let mut log = Log::open(&dir, false).unwrap();
+    assert_eq!(log.index().len(), 4);
+    assert_eq!(latest(&mut log, "a"), Some(put_a));
+    assert_eq!(latest(&mut log, "b"), Some(put_b));
+    assert_eq!(latest(&mut log, "d"), Some(recent_delete));
+    assert_eq!(latest(&mut log, "e"), Some(put_e));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -26,6 +26,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use file_backend::FileBackend;
 use memory_backend::MemoryBackend;
 use storages_mgt::StorageMessage;
 use tokio::sync::broadcast::Sender;
@@ -48,6 +49,7 @@ use zenoh_plugin_trait::{
     plugin_long_version, plugin_version, Plugin, PluginControl, PluginReport, PluginStatusRec,
 };
 
+mod file_backend;
 mod memory_backend;
 mod replication;
 mod storages_mgt;
@@ -125,6 +127,7 @@ impl StorageRuntimeInner {
 
         let mut plugins_manager = PluginsManager::dynamic(lib_loader.clone(), BACKEND_LIB_PREFIX);
         plugins_manager.declare_static_plugin::<MemoryBackend, &str>(MEMORY_BACKEND_NAME, true);
+        plugins_manager.declare_static_plugin::<FileBackend, &str>(FILE_BACKEND_NAME, true);
 
         let session = Arc::new(zenoh::session::init(runtime.clone()).wait()?);
 
@@ -392,6 +395,7 @@ impl RunningPluginTrait for StorageRuntime {
 
 const BACKEND_LIB_PREFIX: &str = "zenoh_backend_";
 const MEMORY_BACKEND_NAME: &str = "memory";
+const FILE_BACKEND_NAME: &str = "file";
 
 fn with_extended_string<R, F: FnMut(&mut String) -> R>(
     prefix: &mut String,
@@ -11,13 +11,21 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde_json::Value;
 use tokio::sync::RwLock;
 use zenoh::{
     bytes::{Encoding, ZBytes},
-    key_expr::OwnedKeyExpr,
+    internal::bail,
+    key_expr::{keyexpr, OwnedKeyExpr},
+    query::{Parameters, TimeRange, ZenohParameters},
     time::Timestamp,
     Result as ZResult,
 };
@@ -58,9 +66,19 @@ impl Volume for MemoryBackend {
         Capability {
             persistence: Persistence::Volatile,
             history: History::Latest,
+            scan: true,
         }
     }
 
+    fn get_storage_capability(&self, props: &StorageConfig) -> Capability {
+        let mut capability = self.get_capability();
+        // An invalid configuration is reported by `create_storage`
+        if let Ok(retention) = Retention::from_config(props) {
+            capability.history = retention.history;
+        }
+        capability
+    }
+
     async fn create_storage(&self, properties: StorageConfig) -> ZResult<Box<dyn Storage>> {
         tracing::debug!("Create Memory Storage with configuration: {:?}", properties);
         Ok(Box::new(MemoryStorage::new(properties).await?))
@@ -74,18 +92,128 @@ impl Drop for MemoryBackend {
     }
 }
 
+/// The values kept by a memory storage for each key, read from the fields of its `volume` object:
+/// ```json5
+/// volume: {
+///   id: "memory",
+///   // "latest" (default) keeps only the latest value of each key, "all" keeps its history
+///   history: "all",
+///   // maximum number of values kept per key
+///   max_samples: 1000,
+///   // maximum age of the kept values, in seconds
+///   max_age: 3600,
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+struct Retention {
+    history: History,
+    max_samples: Option<usize>,
+    max_age: Option<Duration>,
+}
+
+impl Retention {
+    fn from_config(config: &StorageConfig) -> ZResult<Retention> {
+        let mut retention = Retention {
+            history: History::Latest,
+            max_samples: None,
+            max_age: None,
+        };
+        let Value::Object(volume_cfg) = &config.volume_cfg else {
+            return Ok(retention);
+        };
+        if let Some(history) = volume_cfg.get("history") {
+            retention.history = match history.as_str() {
+                Some("latest") => History::Latest,
+                Some("all") => History::All,
+                _ => bail!(
+                    "Invalid value for field `history` of the memory storage `{}`. Expecting \
+                     \"latest\" or \"all\".",
+                    config.name
+                ),
+            }
+        }
+        if let Some(max_samples) = volume_cfg.get("max_samples") {
+            match max_samples.as_u64() {
+                Some(max_samples) if max_samples > 0 => {
+                    retention.max_samples = Some(max_samples as usize)
+                }
+                _ => bail!(
+                    "Invalid value for field `max_samples` of the memory storage `{}`. Only \
+                     strictly positive integer values are accepted.",
+                    config.name
+                ),
+            }
+        }
+        if let Some(max_age) = volume_cfg.get("max_age") {
+            match max_age
+                .as_f64()
+                .and_then(|max_age| Duration::try_from_secs_f64(max_age).ok())
+            {
+                Some(max_age) => retention.max_age = Some(max_age),
+                None => bail!(
+                    "Invalid value for field `max_age` of the memory storage `{}`. Expecting a \
+                     positive number of seconds.",
+                    config.name
+                ),
+            }
+        }
+        if retention.history == History::Latest
+            && (retention.max_samples.is_some() || retention.max_age.is_some())
+        {
+            bail!(
+                "Fields `max_samples` and `max_age` of the memory storage `{}` require \
+                 `history: \"all\"`.",
+                config.name
+            )
+        }
+        Ok(retention)
+    }
+
+    /// Returns `true` if a value with the given timestamp is older than the `max_age`.
+    fn is_expired(&self, timestamp: &Timestamp, now: SystemTime) -> bool {
+        self.max_age.is_some_and(|max_age| {
+            now.duration_since(timestamp.get_time().to_system_time())
+                .is_ok_and(|age| age > max_age)
+        })
+    }
+}
+
+/// The values of a key, sorted by timestamp.
+type Values = VecDeque<StoredData>;
+
 struct MemoryStorage {
     config: StorageConfig,
-    map: Arc<RwLock<HashMap<Option<OwnedKeyExpr>, StoredData>>>,
+    retention: Retention,
+    map: Arc<RwLock<HashMap<Option<OwnedKeyExpr>, Values>>>,
 }
 
 impl MemoryStorage {
     async fn new(properties: StorageConfig) -> ZResult<MemoryStorage> {
         Ok(MemoryStorage {
+            retention: Retention::from_config(&properties)?,
             config: properties,
             map: Arc::new(RwLock::new(HashMap::new())),
         })
     }
+
+    /// Returns the values matching the `_time` range of the `parameters`, or the latest value if
+    /// there is none.
+    fn select(&self, values: &Values, parameters: &str) -> ZResult<Vec<StoredData>> {
+        let now = SystemTime::now();
+        let mut values = values
+            .iter()
+            .filter(|v| !self.retention.is_expired(&v.timestamp, now));
+        match Parameters::from(parameters).time_range() {
+            None => Ok(values.next_back().cloned().into_iter().collect()),
+            Some(time_range) => {
+                let time_range: TimeRange<SystemTime> = time_range?.resolve_at(now);
+                Ok(values
+                    .filter(|v| time_range.contains(v.timestamp.get_time().to_system_time()))
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -102,22 +230,42 @@ impl Storage for MemoryStorage {
         timestamp: Timestamp,
     ) -> ZResult<StorageInsertionResult> {
         tracing::trace!("put for {:?}", key);
+        let data = StoredData {
+            payload,
+            encoding,
+            timestamp,
+        };
         let mut map = self.map.write().await;
         match map.entry(key) {
             std::collections::hash_map::Entry::Occupied(mut e) => {
-                e.insert(StoredData {
-                    payload,
-                    encoding,
-                    timestamp,
-                });
-                return Ok(StorageInsertionResult::Replaced);
+                let values = e.get_mut();
+                if self.retention.history == History::Latest {
+                    values.clear();
+                    values.push_back(data);
+                    return Ok(StorageInsertionResult::Replaced);
+                }
+                let now = SystemTime::now();
+                if self.retention.is_expired(&timestamp, now) {
+                    return Ok(StorageInsertionResult::Outdated);
+                }
+                match values.binary_search_by(|v| v.timestamp.cmp(&timestamp)) {
+                    Ok(_) => return Ok(StorageInsertionResult::Outdated),
+                    Err(index) => values.insert(index, data),
+                }
+                while values
+                    .front()
+                    .is_some_and(|v| self.retention.is_expired(&v.timestamp, now))
+                {
+                    values.pop_front();
+                }
+                if let Some(max_samples) = self.retention.max_samples {
+                    let excess = values.len().saturating_sub(max_samples);
+                    values.drain(..excess);
+                }
+                return Ok(StorageInsertionResult::Inserted);
             }
             std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(StoredData {
-                    payload,
-                    encoding,
-                    timestamp,
-                });
+                e.insert(VecDeque::from([data]));
                 return Ok(StorageInsertionResult::Inserted);
             }
         }
@@ -126,23 +274,31 @@ impl Storage for MemoryStorage {
     async fn delete(
         &mut self,
         key: Option<OwnedKeyExpr>,
-        _timestamp: Timestamp,
+        timestamp: Timestamp,
     ) -> ZResult<StorageInsertionResult> {
         tracing::trace!("delete for {:?}", key);
-        self.map.write().await.remove_entry(&key);
+        let mut map = self.map.write().await;
+        if self.retention.history == History::Latest {
+            map.remove_entry(&key);
+        } else if let Some(values) = map.get_mut(&key) {
+            // Only the values preceding the deletion are removed from the history
+            values.retain(|v| v.timestamp > timestamp);
+            if values.is_empty() {
+                map.remove_entry(&key);
+            }
+        }
         return Ok(StorageInsertionResult::Deleted);
     }
 
     async fn get(
         &mut self,
         key: Option<OwnedKeyExpr>,
-        _parameters: &str,
+        parameters: &str,
     ) -> ZResult<Vec<StoredData>> {
         tracing::trace!("get for {:?}", key);
-        // @TODO: use parameters???
         match self.map.read().await.get(&key) {
-            Some(v) => Ok(vec![v.clone()]),
-            None => Err(format!("Key {:?} is not present", key).into()),
+            Some(values) => self.select(values, parameters),
+            None => Err(KeyNotFound(key).into()),
         }
     }
 
@@ -150,10 +306,55 @@ impl Storage for MemoryStorage {
         let map = self.map.read().await;
         let mut result = Vec::with_capacity(map.len());
         for (k, v) in map.iter() {
-            result.push((k.clone(), v.timestamp));
+            if let Some(latest) = v.back() {
+                result.push((k.clone(), latest.timestamp));
+            }
         }
         Ok(result)
     }
+
+    async fn scan(
+        &mut self,
+        key_expr: &keyexpr,
+        parameters: &str,
+    ) -> ZResult<Vec<(OwnedKeyExpr, StoredData)>> {
+        self.scan_stream(key_expr, parameters)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    async fn scan_stream<'a>(
+        &'a mut self,
+        key_expr: &'a keyexpr,
+        parameters: &'a str,
+    ) -> ZResult<StorageStream<'a, (OwnedKeyExpr, StoredData)>> {
+        tracing::trace!("scan for {}", key_expr);
+        let keys: Vec<OwnedKeyExpr> = self
+            .map
+            .read()
+            .await
+            .keys()
+            .filter_map(|k| k.as_ref().filter(|k| key_expr.intersects(k)).cloned())
+            .collect();
+        // The values of each key are only copied when the stream reaches it
+        let storage = &*self;
+        Ok(stream::iter(keys)
+            .then(move |key| async move {
+                let selected = match storage.map.read().await.get(&Some(key.clone())) {
+                    Some(values) => storage.select(values, parameters),
+                    None => Ok(Vec::new()),
+                };
+                (key, selected)
+            })
+            .flat_map(|(key, selected)| match selected {
+                Ok(values) => stream::iter(values)
+                    .map(move |data| Ok((key.clone(), data)))
+                    .left_stream(),
+                Err(e) => stream::once(async { Err(e) }).right_stream(),
+            })
+            .boxed())
+    }
 }
 
 impl Drop for MemoryStorage {
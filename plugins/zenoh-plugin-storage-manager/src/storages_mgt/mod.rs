@@ -20,6 +20,7 @@ use zenoh_backend_traits::{config::StorageConfig, History, VolumeInstance};
 
 use crate::replication::{Action, Event, LogLatest, LogLatestKey, ReplicationService};
 
+mod pagination;
 pub(crate) mod service;
 pub(crate) use service::StorageService;
 
@@ -56,7 +57,7 @@ pub(crate) async fn create_and_start_storage(
     zenoh_session: Arc<Session>,
 ) -> ZResult<Sender<StorageMessage>> {
     tracing::trace!("Create storage '{}'", &admin_key);
-    let capability = backend.get_capability();
+    let capability = backend.get_storage_capability(&config);
     let storage = backend.create_storage(config.clone()).await?;
 
     // Ex: @/390CEC11A1E34977A1C609A35BC015E6/router/status/plugins/storage_manager/storages/demo1
@@ -0,0 +1,184 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{cmp::Ordering, collections::BinaryHeap, num::IntErrorKind};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use zenoh::{
+    internal::{bail, zerror},
+    key_expr::OwnedKeyExpr,
+    query::Parameters,
+    time::Timestamp,
+    Result as ZResult,
+};
+use zenoh_backend_traits::StoredData;
+
+/// Selector parameter setting the maximum number of replies of a query.
+pub(crate) const LIMIT_PARAM: &str = "_limit";
+/// Selector parameter setting the number of replies to skip.
+pub(crate) const OFFSET_PARAM: &str = "_offset";
+/// Selector parameter resuming a query after the last reply of a previous page.
+pub(crate) const CONTINUATION_PARAM: &str = "_continue";
+/// Maximum number of replies of a page, also used when the [LIMIT_PARAM] is not given.
+pub(crate) const MAX_LIMIT: usize = 1000;
+/// Maximum number of replies skipped by the [OFFSET_PARAM], larger ones must use the
+/// [CONTINUATION_PARAM] instead.
+pub(crate) const MAX_OFFSET: usize = 10_000;
+
+/// A page of the replies to a query, requested with the [LIMIT_PARAM], [OFFSET_PARAM] and
+/// [CONTINUATION_PARAM] selector parameters.
+///
+/// The replies of a paginated query are sorted by key expression and timestamp. If the page does
+/// not contain all the remaining replies, its last reply carries, as attachment, the continuation
+/// token to pass to the [CONTINUATION_PARAM] of the query retrieving the next page.
+///
+/// Only the `offset + limit` first entries are kept while the page is filled, so that the memory
+/// used does not depend on the size of the storage. For this reason, the limit is capped to
+/// [MAX_LIMIT], which is also the limit of the queries only giving an [OFFSET_PARAM] or a
+/// [CONTINUATION_PARAM], and the offset is bounded by [MAX_OFFSET].
+pub(crate) struct Page {
+    limit: usize,
+    offset: usize,
+    after: Option<(OwnedKeyExpr, Timestamp)>,
+    entries: BinaryHeap<PageEntry>,
+}
+
+struct PageEntry {
+    key: OwnedKeyExpr,
+    data: StoredData,
+}
+
+impl PageEntry {
+    fn cmp_key(&self) -> (&str, &Timestamp) {
+        (self.key.as_str(), &self.data.timestamp)
+    }
+}
+
+impl PartialEq for PageEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp_key() == other.cmp_key()
+    }
+}
+
+impl Eq for PageEntry {}
+
+impl PartialOrd for PageEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PageEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_key().cmp(&other.cmp_key())
+    }
+}
+
+impl Page {
+    /// Returns the page requested by the selector parameters, or `None` if they do not request
+    /// any pagination.
+    pub(crate) fn from_parameters(parameters: &Parameters) -> ZResult<Option<Page>> {
+        let limit = match parameters.get(LIMIT_PARAM) {
+            Some(limit) => match limit.parse::<usize>() {
+                Ok(limit) if limit > 0 => limit.min(MAX_LIMIT),
+                // A limit too large to be parsed is capped as well
+                Err(e) if *e.kind() == IntErrorKind::PosOverflow => MAX_LIMIT,
+                _ => bail!("Invalid `{LIMIT_PARAM}` parameter: expecting a positive integer"),
+            },
+            None if parameters.contains_key(OFFSET_PARAM)
+                || parameters.contains_key(CONTINUATION_PARAM) =>
+            {
+                MAX_LIMIT
+            }
+            None => return Ok(None),
+        };
+        let offset = match parameters.get(OFFSET_PARAM) {
+            Some(offset) => match offset.parse::<usize>() {
+                Ok(offset) if offset <= MAX_OFFSET => offset,
+                _ => bail!(
+                    "Invalid `{OFFSET_PARAM}` parameter: expecting an integer up to {MAX_OFFSET}, \
+                     use `{CONTINUATION_PARAM}` to skip more replies"
+                ),
+            },
+            None => 0,
+        };
+        let after = match parameters.get(CONTINUATION_PARAM) {
+            Some(token) => Some(decode_token(token)?),
+            None => None,
+        };
+        Ok(Some(Page {
+            limit,
+            offset,
+            after,
+            entries: BinaryHeap::new(),
+        }))
+    }
+
+    /// Adds an entry to the page, if it belongs to it.
+    pub(crate) fn push(&mut self, key: OwnedKeyExpr, data: StoredData) {
+        let entry = PageEntry { key, data };
+        if let Some((key, timestamp)) = &self.after {
+            if entry.cmp_key() <= (key.as_str(), timestamp) {
+                return;
+            }
+        }
+        self.entries.push(entry);
+        // One more entry than the page is kept to know if there is a next page
+        if self.entries.len() > self.offset.saturating_add(self.limit).saturating_add(1) {
+            self.entries.pop();
+        }
+    }
+
+    /// Returns the sorted entries of the page, and the continuation token to retrieve the next
+    /// page if there is one.
+    pub(crate) fn finish(self) -> (Vec<(OwnedKeyExpr, StoredData)>, Option<String>) {
+        let mut entries: Vec<_> = self
+            .entries
+            .into_sorted_vec()
+            .into_iter()
+            .skip(self.offset)
+            .map(|entry| (entry.key, entry.data))
+            .collect();
+        let mut token = None;
+        if entries.len() > self.limit {
+            entries.truncate(self.limit);
+            token = entries
+                .last()
+                .map(|(key, data)| encode_token(key, &data.timestamp));
+        }
+        (entries, token)
+    }
+}
+
+fn encode_token(key: &OwnedKeyExpr, timestamp: &Timestamp) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{timestamp}/{key}"))
+}
+
+fn decode_token(token: &str) -> ZResult<(OwnedKeyExpr, Timestamp)> {
+    let invalid = || zerror!("Invalid `{CONTINUATION_PARAM}` parameter: {token}");
+    let decoded = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+    let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+    // A timestamp is made of its time and its id separated by a '/', none of them contains another
+    let mut split = decoded.splitn(3, '/');
+    let (Some(time), Some(id), Some(key)) = (split.next(), split.next(), split.next()) else {
+        return Err(invalid().into());
+    };
+    let timestamp = format!("{time}/{id}").parse().map_err(|_| invalid())?;
+    let key = OwnedKeyExpr::new(key).map_err(|_| invalid())?;
+    Ok((key, timestamp))
+}
+
+#[cfg(test)]
+#[path = "tests/pagination.test.rs"]
+mod tests;
@@ -14,12 +14,15 @@
 
 use std::{
     collections::HashSet,
+    fmt::Display,
     str::{self},
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Serialize;
 use tokio::sync::{broadcast::Receiver, Mutex, RwLock, RwLockWriteGuard};
 use zenoh::{
     bytes::{Encoding, ZBytes},
@@ -31,6 +34,7 @@ use zenoh::{
         },
         OwnedKeyExpr,
     },
+    query::{Parameters, Query, TimeRange, ZenohParameters},
     sample::{Sample, SampleBuilder, SampleFields, SampleKind},
     session::Session,
     time::{Timestamp, NTP64},
@@ -38,10 +42,13 @@ use zenoh::{
 };
 use zenoh_backend_traits::{
     config::{GarbageCollectionConfig, StorageConfig},
-    Capability, History, StorageInsertionResult, StoredData,
+    Capability, History, KeyNotFound, StorageInsertionResult, StorageStream, StoredData,
 };
 
-use super::LatestUpdates;
+use super::{
+    pagination::{Page, CONTINUATION_PARAM, LIMIT_PARAM, OFFSET_PARAM},
+    LatestUpdates,
+};
 use crate::{
     replication::{Action, Event},
     storages_mgt::{CacheLatest, StorageMessage},
@@ -255,7 +262,7 @@ impl StorageService {
         }
 
         let matching_keys = if key_expr.is_wild() {
-            self.get_matching_keys(&key_expr).await
+            self.get_matching_keys(&key_expr).await?
         } else {
             vec![key_expr.clone().into()]
         };
@@ -553,109 +560,358 @@ impl StorageService {
         };
         tracing::trace!("[STORAGE] Processing query on key_expr: {}", q.key_expr());
 
+        let mut replier = Replier {
+            storage_name: &self.configuration.name,
+            query: &q,
+            time_range: None,
+            page: None,
+            pending: Vec::new(),
+        };
+        if let Err(e) = check_reserved_parameters(q.parameters()) {
+            replier.reply_err(QueryErrorKind::Parameters, e).await;
+            return;
+        }
+        let parameters = match resolve_time_range(q.parameters()) {
+            Ok((parameters, time_range)) => {
+                replier.time_range = time_range;
+                parameters
+            }
+            Err(e) => {
+                replier.reply_err(QueryErrorKind::Parameters, e).await;
+                return;
+            }
+        };
+        match Page::from_parameters(q.parameters()) {
+            Ok(page) => replier.page = page,
+            Err(e) => {
+                replier.reply_err(QueryErrorKind::Parameters, e).await;
+                return;
+            }
+        };
         let prefix = self.configuration.strip_prefix.as_ref();
 
         if q.key_expr().is_wild() {
+            if self.capability.scan {
+                self.reply_scan(&q, parameters.as_str(), replier).await;
+                return;
+            }
             // resolve key expr into individual keys
-            let matching_keys = self.get_matching_keys(q.key_expr()).await;
-            let mut storage = self.storage.lock().await;
+            let matching_keys = match self.get_matching_keys(q.key_expr()).await {
+                Ok(matching_keys) => matching_keys,
+                Err(e) => {
+                    replier.reply_err(QueryErrorKind::Backend, e).await;
+                    return;
+                }
+            };
             for key in matching_keys {
                 let stripped_key = match crate::strip_prefix(prefix, &key.clone().into()) {
                     Ok(k) => k,
                     Err(e) => {
-                        tracing::error!("{}", e);
-                        // @TODO: return error when it is supported
-                        return;
-                    }
-                };
-                match storage.get(stripped_key, q.parameters().as_str()).await {
-                    Ok(stored_data) => {
-                        for entry in stored_data {
-                            if let Err(e) = q
-                                .reply(key.clone(), entry.payload.clone())
-                                .encoding(entry.encoding.clone())
-                                .timestamp(entry.timestamp)
-                                .await
-                            {
-                                tracing::warn!(
-                                    "Storage '{}' raised an error replying a query: {}",
-                                    self.name,
-                                    e
-                                )
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Storage'{}' raised an error on query: {}", self.name, e)
+                        replier.push_err(QueryErrorKind::KeyExpr, e);
+                        break;
                     }
                 };
+                self.reply_key(&key, stripped_key, parameters.as_str(), &mut replier)
+                    .await;
             }
-            drop(storage);
         } else {
             let stripped_key = match crate::strip_prefix(prefix, q.key_expr()) {
                 Ok(k) => k,
                 Err(e) => {
-                    tracing::error!("{}", e);
-                    // @TODO: return error when it is supported
+                    replier.reply_err(QueryErrorKind::KeyExpr, e).await;
                     return;
                 }
             };
-            let mut storage = self.storage.lock().await;
-            match storage.get(stripped_key, q.parameters().as_str()).await {
-                Ok(stored_data) => {
-                    for entry in stored_data {
-                        if let Err(e) = q
-                            .reply(q.key_expr().clone(), entry.payload.clone())
-                            .encoding(entry.encoding.clone())
-                            .timestamp(entry.timestamp)
-                            .await
-                        {
-                            tracing::warn!(
-                                "Storage '{}' raised an error replying a query: {}",
-                                self.name,
-                                e
-                            )
-                        }
-                    }
-                }
+            let key = OwnedKeyExpr::from(q.key_expr().as_keyexpr());
+            self.reply_key(&key, stripped_key, parameters.as_str(), &mut replier)
+                .await;
+        }
+        replier.finish().await;
+    }
+
+    /// Replies the entries of a single key, retrieved with [`Storage::get_stream`](zenoh_backend_traits::Storage::get_stream).
+    ///
+    /// The Storage is only locked while retrieving the entries of the key, which are sent once
+    /// it is released.
+    async fn reply_key(
+        &self,
+        key: &OwnedKeyExpr,
+        stripped_key: Option<OwnedKeyExpr>,
+        parameters: &str,
+        replier: &mut Replier<'_>,
+    ) {
+        let mut storage = self.storage.lock().await;
+        match storage.get_stream(stripped_key, parameters).await {
+            Ok(stored_data) => replier.push_stream(key, stored_data).await,
+            Err(e) if e.is::<KeyNotFound>() => {}
+            Err(e) => replier.push_err(QueryErrorKind::Backend, e),
+        };
+        drop(storage);
+        replier.flush().await;
+    }
+
+    /// Replies to a wildcard query with the keys found by one [`Storage::scan_stream`](zenoh_backend_traits::Storage::scan_stream)
+    /// per key expression obtained by stripping the storage prefix from the query's.
+    ///
+    /// Only the keys are collected while scanning, their entries are then retrieved and sent one
+    /// key at a time, so that neither the replies nor a slow querier hold the Storage lock.
+    async fn reply_scan(&self, q: &Query, parameters: &str, mut replier: Replier<'_>) {
+        let prefix = self.configuration.strip_prefix.as_ref();
+        let stripped_key_exprs = match prefix {
+            Some(prefix) => q.key_expr().strip_prefix(prefix),
+            None => vec![&**q.key_expr()],
+        };
+
+        if let Some(prefix) = prefix.filter(|prefix| q.key_expr().intersects(prefix)) {
+            self.reply_key(prefix, None, parameters, &mut replier).await;
+        }
+        // The same key is scanned once per entry in its history, and possibly for several
+        // stripped key expressions
+        let mut keys = HashSet::new();
+        let mut storage = self.storage.lock().await;
+        for key_expr in stripped_key_exprs {
+            let mut stored_data = match storage.scan_stream(key_expr, parameters).await {
+                Ok(stored_data) => stored_data,
                 Err(e) => {
-                    tracing::warn!("Storage '{}' raised an error on query: {e}", self.name);
+                    replier.push_err(QueryErrorKind::Backend, e);
+                    continue;
                 }
             };
+            while let Some(entry) = stored_data.next().await {
+                match entry {
+                    Ok((key, _)) => {
+                        keys.insert(key);
+                    }
+                    Err(e) => replier.push_err(QueryErrorKind::Backend, e),
+                }
+            }
+        }
+        drop(storage);
+        replier.flush().await;
+
+        for key in keys {
+            let Ok(full_key) = crate::prefix(prefix, Some(&key)) else {
+                tracing::error!("Internal error: empty key with no `strip_prefix` configured");
+                continue;
+            };
+            self.reply_key(&full_key, Some(key), parameters, &mut replier)
+                .await;
         }
+        replier.finish().await;
     }
 
-    async fn get_matching_keys(&self, key_expr: &keyexpr) -> Vec<OwnedKeyExpr> {
+    async fn get_matching_keys(&self, key_expr: &keyexpr) -> ZResult<Vec<OwnedKeyExpr>> {
         let mut result = Vec::new();
         // @TODO: if cache exists, use that to get the list
         let storage = self.storage.lock().await;
 
         let prefix = self.configuration.strip_prefix.as_ref();
 
-        match storage.get_all_entries().await {
-            Ok(entries) => {
-                for (k, _ts) in entries {
-                    // @TODO: optimize adding back the prefix (possible inspiration from https://github.com/eclipse-zenoh/zenoh/blob/0.5.0-beta.9/backends/traits/src/utils.rs#L79)
-                    let Ok(full_key) = crate::prefix(prefix, k.as_ref()) else {
-                        tracing::error!(
-                            "Internal error: empty key with no `strip_prefix` configured"
-                        );
-                        continue;
-                    };
+        for (k, _ts) in storage.get_all_entries().await? {
+            // @TODO: optimize adding back the prefix (possible inspiration from https://github.com/eclipse-zenoh/zenoh/blob/0.5.0-beta.9/backends/traits/src/utils.rs#L79)
+            let Ok(full_key) = crate::prefix(prefix, k.as_ref()) else {
+                tracing::error!("Internal error: empty key with no `strip_prefix` configured");
+                continue;
+            };
 
-                    if key_expr.intersects(&full_key.clone()) {
-                        result.push(full_key);
-                    }
-                }
+            if key_expr.intersects(&full_key.clone()) {
+                result.push(full_key);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Replies to a query with the entries retrieved from its Storage, filtered by the time range of the
+/// query and, if requested, gathered in a [Page].
+///
+/// The entries and errors pushed while the Storage is locked are only sent by [Replier::flush],
+/// once the lock is released, so that a slow querier does not block the updates of the Storage.
+/// The Storage is locked for the entries of a single key at a time, which bounds the pending
+/// replies, while the entries of a page are only sent by [Replier::finish].
+struct Replier<'a> {
+    storage_name: &'a str,
+    query: &'a Query,
+    time_range: Option<TimeRange<SystemTime>>,
+    page: Option<Page>,
+    pending: Vec<PendingReply>,
+}
+
+enum PendingReply {
+    Entry(OwnedKeyExpr, StoredData),
+    Err(QueryErrorKind, String),
+}
+
+impl Replier<'_> {
+    fn push(&mut self, key: OwnedKeyExpr, entry: StoredData) {
+        if !is_in_time_range(self.time_range.as_ref(), &entry.timestamp) {
+            return;
+        }
+        match &mut self.page {
+            Some(page) => page.push(key, entry),
+            None => self.pending.push(PendingReply::Entry(key, entry)),
+        }
+    }
+
+    fn push_err(&mut self, kind: QueryErrorKind, error: impl Display) {
+        self.pending
+            .push(PendingReply::Err(kind, error.to_string()));
+    }
+
+    /// Pushes the entries of a key retrieved with [`Storage::get_stream`](zenoh_backend_traits::Storage::get_stream).
+    async fn push_stream(
+        &mut self,
+        key: &OwnedKeyExpr,
+        mut stored_data: StorageStream<'_, StoredData>,
+    ) {
+        while let Some(entry) = stored_data.next().await {
+            match entry {
+                Ok(entry) => self.push(key.clone(), entry),
+                Err(e) => self.push_err(QueryErrorKind::Backend, e),
+            }
+        }
+    }
+
+    /// Sends the pending replies. Must be called once the Storage is unlocked.
+    async fn flush(&mut self) {
+        for reply in std::mem::take(&mut self.pending) {
+            match reply {
+                PendingReply::Entry(key, entry) => self.reply(key, entry, None).await,
+                PendingReply::Err(kind, error) => self.reply_err(kind, error).await,
+            }
+        }
+    }
+
+    /// Sends the pending replies, then the page if any. Must be called once the Storage is unlocked.
+    async fn finish(mut self) {
+        self.flush().await;
+        let Some(page) = self.page.take() else {
+            return;
+        };
+        let (entries, token) = page.finish();
+        let last = entries.len().saturating_sub(1);
+        for (i, (key, entry)) in entries.into_iter().enumerate() {
+            let attachment = if i == last { token.clone() } else { None };
+            self.reply(key, entry, attachment).await;
+        }
+    }
+
+    async fn reply(&self, key: OwnedKeyExpr, entry: StoredData, attachment: Option<String>) {
+        if let Err(e) = self
+            .query
+            .reply(key, entry.payload)
+            .encoding(entry.encoding)
+            .timestamp(entry.timestamp)
+            .attachment(attachment)
+            .await
+        {
+            tracing::warn!(
+                "Storage '{}' raised an error replying a query: {}",
+                self.storage_name,
+                e
+            )
+        }
+    }
+
+    /// Replies a [QueryError] to the query, encoded in JSON.
+    async fn reply_err(&self, kind: QueryErrorKind, error: impl Display) {
+        tracing::warn!(
+            "Storage '{}' failed to serve query on {}: {error}",
+            self.storage_name,
+            self.query.key_expr()
+        );
+        let error = QueryError {
+            storage: self.storage_name,
+            kind,
+            message: error.to_string(),
+        };
+        let payload = match serde_json::to_vec(&error) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!("Failed to serialize {error:?}: {e}");
+                return;
             }
-            Err(e) => tracing::warn!(
-                "Storage '{}' raised an error while retrieving keys: {}",
-                self.name,
+        };
+        if let Err(e) = self
+            .query
+            .reply_err(payload)
+            .encoding(Encoding::APPLICATION_JSON)
+            .await
+        {
+            tracing::warn!(
+                "Storage '{}' raised an error replying a query: {}",
+                self.storage_name,
                 e
-            ),
+            )
+        }
+    }
+}
+
+/// The error replied by a storage to a query it could not serve, e.g.:
+/// ```json
+/// { "storage": "demo", "kind": "backend", "message": "Corrupted record at offset 0" }
+/// ```
+/// A storage still replies the entries it could retrieve, so that the querier may receive both
+/// samples and errors.
+#[derive(Debug, Serialize)]
+struct QueryError<'a> {
+    storage: &'a str,
+    kind: QueryErrorKind,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum QueryErrorKind {
+    /// The selector parameters of the query are invalid or not supported.
+    Parameters,
+    /// The key expression of the query could not be mapped to the keys of the storage.
+    KeyExpr,
+    /// The backend failed to retrieve the entries.
+    Backend,
+}
+
+/// The reserved selector parameters, i.e. starting with `_`, supported by storages.
+const RESERVED_PARAMS: [&str; 5] = [
+    Parameters::TIME_RANGE_KEY,
+    Parameters::REPLY_KEY_EXPR_ANY_SEL_PARAM,
+    LIMIT_PARAM,
+    OFFSET_PARAM,
+    CONTINUATION_PARAM,
+];
+
+/// Rejects the reserved selector parameters that storages do not support. The other parameters are
+/// passed as is to the backend.
+fn check_reserved_parameters(parameters: &Parameters) -> ZResult<()> {
+    for (key, _) in parameters.iter() {
+        if key.starts_with('_') && !RESERVED_PARAMS.contains(&key) {
+            bail!("Unsupported `{key}` parameter");
         }
-        result
     }
+    Ok(())
+}
+
+/// Resolves the `_time` argument of the query parameters, if any, so that the bounds relative to
+/// `now()` are the same for the Storage and for the filtering of its replies.
+fn resolve_time_range(
+    parameters: &Parameters,
+) -> ZResult<(Parameters<'static>, Option<TimeRange<SystemTime>>)> {
+    let mut parameters = parameters.clone().into_owned();
+    let time_range = match parameters.time_range() {
+        Some(time_range) => {
+            let time_range = time_range?.resolve();
+            parameters.set_time_range(TimeRange::from(time_range));
+            Some(time_range)
+        }
+        None => None,
+    };
+    Ok((parameters, time_range))
+}
+
+fn is_in_time_range(time_range: Option<&TimeRange<SystemTime>>, timestamp: &Timestamp) -> bool {
+    time_range.map_or(true, |time_range| {
+        time_range.contains(timestamp.get_time().to_system_time())
+    })
 }
 
 // Periodic event cleaning-up data info for old metadata
@@ -0,0 +1,167 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::str::FromStr;
+
+use uhlc::{Timestamp, HLC};
+use zenoh::{bytes::Encoding, key_expr::OwnedKeyExpr, query::Parameters};
+use zenoh_backend_traits::StoredData;
+
+use super::{decode_token, encode_token, Page, MAX_LIMIT, MAX_OFFSET};
+
+fn entry(key: &str, timestamp: Timestamp) -> (OwnedKeyExpr, StoredData) {
+    (
+        OwnedKeyExpr::from_str(key).unwrap(),
+        StoredData {
+            payload: key.into(),
+            encoding: Encoding::default(),
+            timestamp,
+        },
+    )
+}
+
+fn page(parameters: &str) -> Option<Page> {
+    Page::from_parameters(&Parameters::from(parameters)).unwrap()
+}
+
+fn keys(entries: &[(OwnedKeyExpr, StoredData)]) -> Vec<&str> {
+    entries.iter().map(|(key, _)| key.as_str()).collect()
+}
+
+#[test]
+fn test_parameters() {
+    assert!(page("").is_none());
+    assert!(page("_time=[..]").is_none());
+    assert!(page("_limit=2").is_some());
+    assert!(page("_limit=2;_offset=2").is_some());
+
+    let token = encode_token(
+        &OwnedKeyExpr::from_str("a").unwrap(),
+        &HLC::default().new_timestamp(),
+    );
+    let continuation = format!("_continue={token}");
+    assert!(page(&format!("_limit=2;{continuation}")).is_some());
+
+    // The offset and continuation alone use the maximum limit
+    assert_eq!(page("_offset=2").unwrap().limit, MAX_LIMIT);
+    assert_eq!(page(&continuation).unwrap().limit, MAX_LIMIT);
+
+    // The limit is capped, the offset bounded
+    assert_eq!(page("_limit=1000000").unwrap().limit, MAX_LIMIT);
+    assert_eq!(
+        page("_limit=100000000000000000000000000000").unwrap().limit,
+        MAX_LIMIT
+    );
+    assert_eq!(
+        page(&format!("_limit=2;_offset={MAX_OFFSET}"))
+            .unwrap()
+            .offset,
+        MAX_OFFSET
+    );
+
+    for invalid in [
+        "_limit=0",
+        "_limit=-1",
+        "_limit=a",
+        "_limit=2;_offset=a",
+        "_limit=2;_continue=a",
+        &format!("_limit=2;_offset={}", MAX_OFFSET + 1),
+        &format!("_offset={}", usize::MAX),
+        "_offset=100000000000000000000000000000",
+    ] {
+        assert!(
+            Page::from_parameters(&Parameters::from(invalid)).is_err(),
+            "{invalid}"
+        );
+    }
+}
+
+#[test]
+fn test_token() {
+    let hlc = HLC::default();
+    let (key, data) = entry("a/b/c", hlc.new_timestamp());
+
+    let token = encode_token(&key, &data.timestamp);
+    assert!(token
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(decode_token(&token).unwrap(), (key, data.timestamp));
+}
+
+#[test]
+fn test_pages() {
+    let hlc = HLC::default();
+    let ts_1 = hlc.new_timestamp();
+    let ts_2 = hlc.new_timestamp();
+    let entries = [
+        entry("d", ts_1),
+        entry("b", ts_1),
+        entry("a", ts_2),
+        entry("c", ts_1),
+        entry("a", ts_1),
+    ];
+
+    // All the entries are sorted by key and timestamp
+    let mut all = page("_limit=10").unwrap();
+    for (key, data) in entries.clone() {
+        all.push(key, data);
+    }
+    let (all, token) = all.finish();
+    assert_eq!(keys(&all), ["a", "a", "b", "c", "d"]);
+    assert_eq!(all[0].1.timestamp, ts_1);
+    assert!(token.is_none());
+
+    let mut page_1 = page("_limit=2").unwrap();
+    for (key, data) in entries.clone() {
+        page_1.push(key, data);
+    }
+    let (page_1, token) = page_1.finish();
+    assert_eq!(keys(&page_1), ["a", "a"]);
+    assert_eq!(page_1[1].1.timestamp, ts_2);
+
+    let mut page_2 = page(&format!("_limit=2;_continue={}", token.unwrap())).unwrap();
+    for (key, data) in entries.clone() {
+        page_2.push(key, data);
+    }
+    let (page_2, token) = page_2.finish();
+    assert_eq!(keys(&page_2), ["b", "c"]);
+
+    let mut page_3 = page(&format!("_limit=2;_continue={}", token.unwrap())).unwrap();
+    for (key, data) in entries.clone() {
+        page_3.push(key, data);
+    }
+    let (page_3, token) = page_3.finish();
+    assert_eq!(keys(&page_3), ["d"]);
+    assert!(token.is_none());
+
+    let mut page_offset = page("_limit=2;_offset=3").unwrap();
+    for (key, data) in entries.clone() {
+        page_offset.push(key, data);
+    }
+    let (page_offset, token) = page_offset.finish();
+    assert_eq!(keys(&page_offset), ["c", "d"]);
+    assert!(token.is_none());
+
+    // The bounds of the page do not overflow
+    let mut unbounded = Page {
+        limit: usize::MAX,
+        offset: usize::MAX,
+        after: None,
+        entries: Default::default(),
+    };
+    for (key, data) in entries {
+        unbounded.push(key, data);
+    }
+    assert!(unbounded.finish().0.is_empty());
+}
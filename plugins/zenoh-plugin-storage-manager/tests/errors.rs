@@ -0,0 +1,185 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the errors replied by the storages -
+// 1. queries with invalid or unsupported selector parameters are replied an error
+// 2. the failures of a backend are replied as errors, along with the entries it could retrieve
+// 3. querying a missing key is not an error
+
+use std::{fs::OpenOptions, thread::sleep};
+
+use serde_json::Value;
+use tokio::runtime::Runtime;
+use zenoh::{
+    bytes::Encoding,
+    internal::zasync_executor_init,
+    query::{Reply, ReplyError},
+    sample::Sample,
+    Config, Session,
+};
+use zenoh_plugin_trait::Plugin;
+
+async fn get_replies(session: &Session, selector: &str) -> (Vec<Sample>, Vec<ReplyError>) {
+    let replies: Vec<Reply> = session.get(selector).await.unwrap().into_iter().collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    let mut errors = Vec::new();
+    for reply in replies {
+        match reply.into_result() {
+            Ok(sample) => samples.push(sample),
+            Err(error) => errors.push(error),
+        }
+    }
+    (samples, errors)
+}
+
+fn assert_error(error: &ReplyError, storage: &str, kind: &str) {
+    assert_eq!(error.encoding(), &Encoding::APPLICATION_JSON);
+    let error: Value = serde_json::from_slice(&error.payload().to_bytes()).unwrap();
+    assert_eq!(error["storage"], storage);
+    assert_eq!(error["kind"], kind);
+    assert!(error["message"].is_string());
+}
+
+async fn test_errors() {
+    async {
+        zasync_executor_init!();
+    }
+    .await;
+    let root = std::env::temp_dir().join(format!("zenoh-errors-test-{}", std::process::id()));
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    volumes: {{
+                        file: {{
+                            root: "{}"
+                        }}
+                    }},
+                    storages: {{
+                        errors_memory: {{
+                            key_expr: "errors/memory/**",
+                            strip_prefix: "errors/memory",
+                            volume: {{
+                                id: "memory"
+                            }}
+                        }},
+                        errors_file: {{
+                            key_expr: "errors/file/**",
+                            strip_prefix: "errors/file",
+                            volume: {{
+                                id: "file"
+                            }}
+                        }}
+                    }}
+                }}"#,
+                root.display()
+            ),
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{
+                    enabled: {
+                        router: true,
+                        peer: true,
+                        client: true
+                    }
+                }"#,
+        )
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+
+    let runtime = zenoh::internal::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::session::init(runtime).await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    session.put("errors/memory/a", "a").await.unwrap();
+    session.put("errors/file/a", "a").await.unwrap();
+    session.put("errors/file/b", "b").await.unwrap();
+    sleep(std::time::Duration::from_millis(100));
+
+    let (samples, errors) = get_replies(&session, "errors/memory/a").await;
+    assert_eq!(samples.len(), 1);
+    assert!(errors.is_empty());
+
+    // expects no reply at all for a missing key
+    for selector in ["errors/memory/missing", "errors/file/missing"] {
+        let (samples, errors) = get_replies(&session, selector).await;
+        assert!(samples.is_empty());
+        assert!(errors.is_empty());
+    }
+
+    // expects the parameters that are not reserved to be passed to the backend
+    let (samples, errors) = get_replies(&session, "errors/memory/a?custom=1").await;
+    assert_eq!(samples.len(), 1);
+    assert!(errors.is_empty());
+
+    // expects an error for invalid or unsupported selector parameters
+    for selector in [
+        "errors/memory/a?_limit=0",
+        "errors/memory/a?_time=invalid",
+        "errors/memory/a?_unknown",
+    ] {
+        let (samples, errors) = get_replies(&session, selector).await;
+        assert!(samples.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_error(&errors[0], "errors_memory", "parameters");
+    }
+
+    // Truncating the log of the file storage makes it fail to read the entries it indexed
+    OpenOptions::new()
+        .write(true)
+        .open(root.join("errors_file/log"))
+        .unwrap()
+        .set_len(0)
+        .unwrap();
+
+    let (samples, errors) = get_replies(&session, "errors/file/a").await;
+    assert!(samples.is_empty());
+    assert_eq!(errors.len(), 1);
+    assert_error(&errors[0], "errors_file", "backend");
+
+    // expects the entries that can still be read along with the errors
+    session.put("errors/file/c", "c").await.unwrap();
+    sleep(std::time::Duration::from_millis(100));
+    let (samples, errors) = get_replies(&session, "errors/file/**").await;
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].key_expr().as_str(), "errors/file/c");
+    assert_eq!(errors.len(), 2);
+    for error in &errors {
+        assert_error(error, "errors_file", "backend");
+    }
+
+    session.close().await.unwrap();
+    drop(storage);
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn errors_test() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { test_errors().await });
+}
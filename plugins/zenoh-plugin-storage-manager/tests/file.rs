@@ -0,0 +1,158 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the file backend -
+// 1. the content of a storage is persisted across restarts
+// 2. the log of the storage is compacted
+
+use std::{path::Path, thread::sleep};
+
+use tokio::runtime::Runtime;
+use zenoh::{
+    internal::{plugins::RunningPlugin, zasync_executor_init},
+    query::Reply,
+    sample::Sample,
+    Config, Session,
+};
+use zenoh_plugin_trait::Plugin;
+
+async fn start_storage(root: &Path) -> (Session, RunningPlugin) {
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            &format!(
+                r#"{{
+                    volumes: {{
+                        file: {{
+                            root: "{}"
+                        }}
+                    }},
+                    storages: {{
+                        file_test: {{
+                            key_expr: "file/test/**",
+                            strip_prefix: "file/test",
+                            volume: {{
+                                id: "file",
+                                compaction_threshold: 0
+                            }}
+                        }}
+                    }}
+                }}"#,
+                root.display()
+            ),
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{
+                    enabled: {
+                        router: true,
+                        peer: true,
+                        client: true
+                    }
+                }"#,
+        )
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+
+    let runtime = zenoh::internal::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::session::init(runtime).await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    (session, storage)
+}
+
+async fn get_data(session: &Session, key_expr: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session.get(key_expr).await.unwrap().into_iter().collect();
+    println!("Getting replies on '{key_expr}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.into_result() {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by(|a, b| a.key_expr().as_str().cmp(b.key_expr().as_str()));
+    println!("Getting Data on '{key_expr}': '{samples:?}'...");
+    samples
+}
+
+fn payloads(samples: &[Sample]) -> Vec<String> {
+    samples
+        .iter()
+        .map(|s| s.payload().try_to_string().unwrap().into_owned())
+        .collect()
+}
+
+async fn test_file_storage() {
+    async {
+        zasync_executor_init!();
+    }
+    .await;
+    let root = std::env::temp_dir().join(format!("zenoh-file-test-{}", std::process::id()));
+
+    let (session, storage) = start_storage(&root).await;
+    for i in 0..10 {
+        session
+            .put("file/test/a", i.to_string())
+            .encoding("text/plain")
+            .await
+            .unwrap();
+    }
+    session.put("file/test", "prefix").await.unwrap();
+    session.put("file/test/b/c", "c").await.unwrap();
+    session.put("file/test/d", "d").await.unwrap();
+    session.delete("file/test/d").await.unwrap();
+    sleep(std::time::Duration::from_millis(100));
+
+    let data = get_data(&session, "file/test/**").await;
+    assert_eq!(payloads(&data), ["prefix", "9", "c"]);
+    session.close().await.unwrap();
+    drop(storage);
+
+    // The log only contains the latest values once compacted
+    let log_len = std::fs::metadata(root.join("file_test/log")).unwrap().len();
+    assert!(log_len < 1024, "log of {log_len} bytes");
+
+    let (session, storage) = start_storage(&root).await;
+
+    // expects the values stored before the restart
+    let data = get_data(&session, "file/test/a").await;
+    assert_eq!(payloads(&data), ["9"]);
+    assert_eq!(data[0].encoding().to_string(), "text/plain");
+
+    let data = get_data(&session, "file/test/**").await;
+    assert_eq!(payloads(&data), ["prefix", "9", "c"]);
+
+    let data = get_data(&session, "file/test/d").await;
+    assert_eq!(data.len(), 0);
+
+    session.close().await.unwrap();
+    drop(storage);
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn file_storage_test() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { test_file_storage().await });
+}
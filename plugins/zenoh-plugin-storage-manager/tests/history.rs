@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the history mode of the memory backend -
+// 1. only the latest value is returned if the query has no time range
+// 2. the values matching the time range are returned, within the configured retention
+
+use std::{thread::sleep, time::SystemTime};
+
+use tokio::runtime::Runtime;
+use zenoh::{
+    internal::zasync_executor_init,
+    query::{Reply, TimeBound, TimeRange},
+    sample::Sample,
+    Config, Session,
+};
+use zenoh_plugin_trait::Plugin;
+
+async fn put_data(session: &Session, key_expr: &str, value: &str) {
+    println!("Putting Data ('{key_expr}': '{value}')...");
+    session.put(key_expr, value).await.unwrap();
+    sleep(std::time::Duration::from_millis(10));
+}
+
+async fn get_data(session: &Session, selector: &str) -> Vec<Sample> {
+    let replies: Vec<Reply> = session.get(selector).await.unwrap().into_iter().collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    for reply in replies {
+        if let Ok(sample) = reply.into_result() {
+            samples.push(sample);
+        }
+    }
+    samples.sort_by_key(|s| *s.timestamp().unwrap());
+    println!("Getting Data on '{selector}': '{samples:?}'...");
+    samples
+}
+
+fn payloads(samples: &[Sample]) -> Vec<String> {
+    samples
+        .iter()
+        .map(|s| s.payload().try_to_string().unwrap().into_owned())
+        .collect()
+}
+
+async fn test_history() {
+    async {
+        zasync_executor_init!();
+    }
+    .await;
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        history_test: {
+                            key_expr: "history/test/**",
+                            volume: {
+                                id: "memory",
+                                history: "all",
+                                max_samples: 3,
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{
+                    enabled: {
+                        router: true,
+                        peer: true,
+                        client: true
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::internal::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::session::init(runtime).await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    put_data(&session, "history/test/a", "1").await;
+    put_data(&session, "history/test/a", "2").await;
+    let after_2 = SystemTime::now();
+    put_data(&session, "history/test/a", "3").await;
+    put_data(&session, "history/test/a", "4").await;
+    put_data(&session, "history/test/b", "5").await;
+
+    // expects only the latest sample
+    let data = get_data(&session, "history/test/a").await;
+    assert_eq!(payloads(&data), ["4"]);
+
+    // expects the samples kept by the retention
+    let data = get_data(&session, "history/test/a?_time=[..]").await;
+    assert_eq!(payloads(&data), ["2", "3", "4"]);
+
+    let data = get_data(&session, "history/test/a?_time=[now(-1h)..now()]").await;
+    assert_eq!(payloads(&data), ["2", "3", "4"]);
+
+    let time_range = TimeRange::<SystemTime> {
+        start: TimeBound::Inclusive(after_2),
+        end: TimeBound::Unbounded,
+    };
+    let data = get_data(&session, &format!("history/test/a?_time={time_range}")).await;
+    assert_eq!(payloads(&data), ["3", "4"]);
+
+    let data = get_data(&session, "history/test/a?_time=[..now(-1h)]").await;
+    assert_eq!(data.len(), 0);
+
+    // expects the samples of all the matching keys
+    let data = get_data(&session, "history/test/*?_time=[..]").await;
+    assert_eq!(payloads(&data), ["2", "3", "4", "5"]);
+
+    let data = get_data(&session, "history/test/*").await;
+    assert_eq!(payloads(&data), ["4", "5"]);
+
+    // expects zero sample for an invalid time range
+    let data = get_data(&session, "history/test/a?_time=[3..2]").await;
+    assert_eq!(data.len(), 0);
+
+    session.delete("history/test/a").await.unwrap();
+    sleep(std::time::Duration::from_millis(10));
+
+    // expects zero sample
+    let data = get_data(&session, "history/test/a?_time=[..]").await;
+    assert_eq!(data.len(), 0);
+
+    put_data(&session, "history/test/a", "6").await;
+    let data = get_data(&session, "history/test/a?_time=[..]").await;
+    assert_eq!(payloads(&data), ["6"]);
+
+    drop(storage);
+}
+
+#[test]
+fn history_test() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { test_history().await });
+}
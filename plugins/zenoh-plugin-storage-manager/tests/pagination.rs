@@ -0,0 +1,178 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+// Test the pagination of the replies of a storage -
+// 1. all the entries can be retrieved page by page with continuation tokens
+// 2. pages can be selected with an offset, including in the history of a key
+
+use std::thread::sleep;
+
+use tokio::runtime::Runtime;
+use zenoh::{internal::zasync_executor_init, query::Reply, sample::Sample, Config, Session};
+use zenoh_plugin_trait::Plugin;
+
+/// Returns the samples of a page, sorted by key and timestamp, and its continuation token.
+async fn get_page(session: &Session, selector: &str) -> (Vec<Sample>, Option<String>) {
+    let replies: Vec<Reply> = session.get(selector).await.unwrap().into_iter().collect();
+    println!("Getting replies on '{selector}': '{replies:?}'...");
+    let mut samples = Vec::new();
+    let mut token = None;
+    for reply in replies {
+        if let Ok(sample) = reply.into_result() {
+            if let Some(attachment) = sample.attachment() {
+                assert!(token.is_none());
+                token = Some(attachment.try_to_string().unwrap().into_owned());
+            }
+            samples.push(sample);
+        }
+    }
+    samples.sort_by(|a, b| {
+        (a.key_expr().as_str(), a.timestamp()).cmp(&(b.key_expr().as_str(), b.timestamp()))
+    });
+    println!("Getting Data on '{selector}': '{samples:?}'...");
+    (samples, token)
+}
+
+fn payloads(samples: &[Sample]) -> Vec<String> {
+    samples
+        .iter()
+        .map(|s| s.payload().try_to_string().unwrap().into_owned())
+        .collect()
+}
+
+async fn test_pagination() {
+    async {
+        zasync_executor_init!();
+    }
+    .await;
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        pagination_test: {
+                            key_expr: "pagination/test/**",
+                            strip_prefix: "pagination/test",
+                            volume: {
+                                id: "memory",
+                                history: "all",
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{
+                    enabled: {
+                        router: true,
+                        peer: true,
+                        client: true
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::internal::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::session::init(runtime).await.unwrap();
+
+    sleep(std::time::Duration::from_secs(1));
+
+    for i in 0..10 {
+        session
+            .put(format!("pagination/test/k{i}"), i.to_string())
+            .await
+            .unwrap();
+    }
+    for i in 0..5 {
+        session
+            .put("pagination/test/history", format!("h{i}"))
+            .await
+            .unwrap();
+        sleep(std::time::Duration::from_millis(10));
+    }
+    sleep(std::time::Duration::from_millis(100));
+
+    // expects all the entries, page by page
+    let mut pages = Vec::new();
+    let (mut page, mut token) = get_page(&session, "pagination/test/k$*?_limit=3").await;
+    while let Some(continuation) = token {
+        assert_eq!(page.len(), 3);
+        pages.push(payloads(&page));
+        (page, token) = get_page(
+            &session,
+            &format!("pagination/test/k$*?_limit=3;_continue={continuation}"),
+        )
+        .await;
+    }
+    pages.push(payloads(&page));
+    assert_eq!(
+        pages,
+        [
+            vec!["0", "1", "2"],
+            vec!["3", "4", "5"],
+            vec!["6", "7", "8"],
+            vec!["9"]
+        ]
+    );
+
+    // expects the entries following the offset
+    let (page, token) = get_page(&session, "pagination/test/k$*?_limit=2;_offset=8").await;
+    assert_eq!(payloads(&page), ["8", "9"]);
+    assert!(token.is_none());
+
+    // expects the history of a key, page by page
+    let (page, token) = get_page(&session, "pagination/test/history?_time=[..];_limit=2").await;
+    assert_eq!(payloads(&page), ["h0", "h1"]);
+    let (page, token) = get_page(
+        &session,
+        &format!(
+            "pagination/test/history?_time=[..];_limit=2;_continue={}",
+            token.unwrap()
+        ),
+    )
+    .await;
+    assert_eq!(payloads(&page), ["h2", "h3"]);
+    assert!(token.is_some());
+
+    // expects zero sample for invalid pagination parameters
+    let (page, _) = get_page(&session, "pagination/test/k$*?_limit=0").await;
+    assert_eq!(page.len(), 0);
+    let (page, _) = get_page(&session, "pagination/test/k$*?_continue=invalid").await;
+    assert_eq!(page.len(), 0);
+    let (page, _) = get_page(&session, "pagination/test/k$*?_offset=100000").await;
+    assert_eq!(page.len(), 0);
+
+    // expects the entries following the offset, up to the maximum limit
+    let (page, token) = get_page(&session, "pagination/test/k$*?_offset=7").await;
+    assert_eq!(payloads(&page), ["7", "8", "9"]);
+    assert!(token.is_none());
+
+    drop(storage);
+}
+
+#[test]
+fn pagination_test() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { test_pagination().await });
+}
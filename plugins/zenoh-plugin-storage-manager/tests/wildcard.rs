@@ -174,8 +174,91 @@ async fn test_wild_card_in_order() {
     drop(storage);
 }
 
+async fn test_wild_card_query_strip_prefix() {
+    zasync_executor_init!();
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "plugins/storage-manager",
+            r#"{
+                    storages: {
+                        scan_test: {
+                            key_expr: "scan/test/**",
+                            strip_prefix: "scan/test",
+                            volume: {
+                                id: "memory"
+                            }
+                        }
+                    }
+                }"#,
+        )
+        .unwrap();
+    config
+        .insert_json5(
+            "timestamping",
+            r#"{
+                    enabled: {
+                        router: true,
+                        peer: true,
+                        client: true
+                    }
+                }"#,
+        )
+        .unwrap();
+
+    let runtime = zenoh::internal::runtime::RuntimeBuilder::new(config)
+        .build()
+        .await
+        .unwrap();
+    let storage =
+        zenoh_plugin_storage_manager::StoragesPlugin::start("storage-manager", &runtime).unwrap();
+
+    let session = zenoh::session::init(runtime).await.unwrap();
+    sleep(std::time::Duration::from_secs(1));
+
+    for (i, key_expr) in ["scan/test", "scan/test/a", "scan/test/a/b", "scan/test/c"]
+        .into_iter()
+        .enumerate()
+    {
+        put_data(
+            &session,
+            key_expr,
+            &i.to_string(),
+            Timestamp::from_str("7054123566570568799/BC779A06D7E049BD88C3FF3DB0C17FCC").unwrap(),
+        )
+        .await;
+    }
+
+    sleep(std::time::Duration::from_millis(10));
+
+    // the wildcard queries are resolved relatively to the stripped prefix
+    for (key_expr, expected) in [
+        ("scan/test/*", vec!["scan/test/a", "scan/test/c"]),
+        (
+            "scan/**",
+            vec!["scan/test", "scan/test/a", "scan/test/a/b", "scan/test/c"],
+        ),
+        ("scan/*/a", vec!["scan/test/a"]),
+        ("**/b", vec!["scan/test/a/b"]),
+        ("scan/other/*", vec![]),
+    ] {
+        let data = get_data(&session, key_expr).await;
+        let mut keys: Vec<&str> = data.iter().map(|s| s.key_expr().as_str()).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, expected);
+    }
+
+    drop(storage);
+}
+
 #[test]
 fn wildcard_test() {
     let rt = Runtime::new().unwrap();
     rt.block_on(async { test_wild_card_in_order().await });
 }
+
+#[test]
+fn wildcard_query_strip_prefix_test() {
+    let rt = Runtime::new().unwrap();
+    rt.block_on(async { test_wild_card_query_strip_prefix().await });
+}
@@ -26,9 +26,7 @@ async fn main() {
 
     let z = Arc::new(zenoh::open(config).await.unwrap());
     let member_id = id.unwrap_or_else(|| z.zid().to_string());
-    let member = Member::new(member_id.as_str())
-        .unwrap()
-        .lease(Duration::from_secs(3));
+    let member = Member::new(member_id.as_str()).unwrap();
 
     let group = Group::join(z.clone(), group_name.as_str(), member)
         .await
@@ -16,6 +16,7 @@ use std::{
     future::{IntoFuture, Ready},
     ops::{Bound, RangeBounds},
     sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
 };
 
 use zenoh::{
@@ -31,6 +32,8 @@ use zenoh::{
     Resolvable, Result as ZResult, Session, Wait, KE_ADV_PREFIX, KE_AT, KE_STARSTAR,
 };
 
+use crate::cache_store::{CacheStore, CacheStoreWriter};
+
 pub(crate) static KE_UHLC: &keyexpr = ke!("uhlc");
 #[zenoh_macros::unstable]
 kedefine!(
@@ -87,6 +90,8 @@ impl QoSBuilderTrait for RepliesConfig {
 #[zenoh_macros::unstable]
 pub struct CacheConfig {
     max_samples: usize,
+    max_age: Option<Duration>,
+    store: Option<Arc<dyn CacheStore>>,
     replies_config: RepliesConfig,
 }
 
@@ -95,6 +100,8 @@ impl Default for CacheConfig {
     fn default() -> Self {
         Self {
             max_samples: 1,
+            max_age: None,
+            store: None,
             replies_config: RepliesConfig::default(),
         }
     }
@@ -109,6 +116,23 @@ impl CacheConfig {
         self
     }
 
+    /// Specify how long to keep samples, in addition to `max_samples`.
+    #[zenoh_macros::unstable]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Persist the cached samples in the given [`CacheStore`].
+    ///
+    /// The samples found in the store are loaded when the cache is created, so that late joiners
+    /// can retrieve the history published before a restart of the publisher.
+    #[zenoh_macros::unstable]
+    pub fn store<S: CacheStore + 'static>(mut self, store: S) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
     /// The QoS to apply to replies.
     #[zenoh_macros::unstable]
     pub fn replies_config(mut self, qos: RepliesConfig) -> Self {
@@ -206,11 +230,52 @@ fn decode_range(range: &str) -> (Bound<u32>, Bound<u32>) {
     (start, end)
 }
 
+/// The samples of an [`AdvancedCache`], along with the time they were cached at.
+#[zenoh_macros::unstable]
+struct CacheState {
+    samples: VecDeque<(SystemTime, Sample)>,
+    max_samples: usize,
+    max_age: Option<Duration>,
+    store: Option<CacheStoreWriter>,
+}
+
+#[zenoh_macros::unstable]
+impl CacheState {
+    fn push(&mut self, sample: Sample) {
+        let time = SystemTime::now();
+        if let Some(store) = &self.store {
+            store.push(time, sample.clone());
+        }
+        self.samples.push_back((time, sample));
+        self.evict();
+    }
+
+    /// Evict the samples in excess of `max_samples` or older than `max_age`.
+    fn evict(&mut self) {
+        let mut count = self.samples.len().saturating_sub(self.max_samples);
+        if let Some(max_age) = self.max_age {
+            let now = SystemTime::now();
+            count += self
+                .samples
+                .iter()
+                .skip(count)
+                .take_while(|(time, _)| now.duration_since(*time).is_ok_and(|age| age > max_age))
+                .count();
+        }
+        if count == 0 {
+            return;
+        }
+        self.samples.drain(..count);
+        if let Some(store) = &self.store {
+            store.evict(count);
+        }
+    }
+}
+
 /// [`AdvancedCache`].
 #[zenoh_macros::unstable]
 pub struct AdvancedCache {
-    cache: Arc<RwLock<VecDeque<Sample>>>,
-    max_samples: usize,
+    cache: Arc<RwLock<CacheState>>,
     _queryable: Queryable<()>,
     _token: Option<LivelinessToken>,
 }
@@ -231,7 +296,18 @@ impl AdvancedCache {
             &key_expr,
             conf.history,
         );
-        let cache = Arc::new(RwLock::new(VecDeque::<Sample>::new()));
+        let mut state = CacheState {
+            samples: VecDeque::new(),
+            max_samples: conf.history.max_samples,
+            max_age: conf.history.max_age,
+            store: None,
+        };
+        if let Some(store) = &conf.history.store {
+            state.samples.extend(store.load()?);
+            state.store = Some(CacheStoreWriter::new(store.clone())?);
+            state.evict();
+        }
+        let cache = Arc::new(RwLock::new(state));
 
         // declare the queryable that will answer to queries on cache
         let queryable = conf
@@ -250,10 +326,16 @@ impl AdvancedCache {
                         .parameters()
                         .get("_max")
                         .and_then(|s| s.parse::<u32>().ok());
-                    if let Ok(queue) = cache.read() {
+                    if conf.history.max_age.is_some() {
+                        if let Ok(mut state) = cache.write() {
+                            state.evict();
+                        }
+                    }
+                    if let Ok(state) = cache.read() {
+                        let queue = state.samples.iter().map(|(_, sample)| sample);
                         if let Some(max) = max {
                             let mut samples = VecDeque::new();
-                            for sample in queue.iter() {
+                            for sample in queue {
                                 if range == (Bound::Unbounded, Bound::Unbounded)
                                     || sample
                                         .source_info()
@@ -290,7 +372,7 @@ impl AdvancedCache {
                                 }
                             }
                         } else {
-                            for sample in queue.iter() {
+                            for sample in queue {
                                 if range == (Bound::Unbounded, Bound::Unbounded)
                                     || sample
                                         .source_info()
@@ -343,7 +425,6 @@ impl AdvancedCache {
 
         Ok(AdvancedCache {
             cache,
-            max_samples: conf.history.max_samples,
             _queryable: queryable,
             _token: token,
         })
@@ -351,13 +432,23 @@ impl AdvancedCache {
 
     #[zenoh_macros::unstable]
     pub(crate) fn cache_sample(&self, sample: Sample) {
-        if let Ok(mut queue) = self.cache.write() {
-            if queue.len() >= self.max_samples {
-                queue.pop_front();
-            }
-            queue.push_back(sample);
+        if let Ok(mut state) = self.cache.write() {
+            state.push(sample);
         } else {
             tracing::error!("Unable to take AdvancedPublisher cache write lock");
         }
     }
 }
+
+#[zenoh_macros::unstable]
+impl Drop for AdvancedCache {
+    fn drop(&mut self) {
+        // Wait for the store to be up to date, outside of the lock the queryable may still take
+        let store = self
+            .cache
+            .write()
+            .ok()
+            .and_then(|mut state| state.store.take());
+        drop(store);
+    }
+}
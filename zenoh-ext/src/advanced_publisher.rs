@@ -13,13 +13,19 @@
 //
 use std::{
     future::{IntoFuture, Ready},
-    sync::atomic::{AtomicU32, Ordering},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
+use async_trait::async_trait;
 use zenoh::{
     bytes::{Encoding, OptionZBytes, ZBytes},
     internal::{
         bail,
+        runtime::ZRuntime,
         traits::{
             EncodingBuilderTrait, QoSBuilderTrait, SampleBuilderTrait, TimestampBuilderTrait,
         },
@@ -36,8 +42,12 @@ use zenoh::{
     Resolvable, Resolve, Result as ZResult, Session, Wait, KE_ADV_PREFIX, KE_AT, KE_EMPTY,
 };
 use zenoh_macros::ke;
+use zenoh_util::{Timed, TimedEvent, Timer};
 
-use crate::advanced_cache::{AdvancedCache, AdvancedCacheBuilder, CacheConfig, KE_UHLC};
+use crate::{
+    advanced_cache::{AdvancedCache, AdvancedCacheBuilder, CacheConfig, KE_UHLC},
+    z_serialize,
+};
 
 pub(crate) static KE_PUB: &keyexpr = ke!("pub");
 
@@ -66,6 +76,7 @@ pub struct AdvancedPublisherBuilder<'a, 'b, 'c> {
     liveliness: bool,
     cache: bool,
     history: CacheConfig,
+    heartbeat: Option<Duration>,
 }
 
 #[zenoh_macros::unstable]
@@ -86,6 +97,7 @@ impl<'a, 'b, 'c> AdvancedPublisherBuilder<'a, 'b, 'c> {
             liveliness: false,
             cache: false,
             history: CacheConfig::default(),
+            heartbeat: None,
         }
     }
 
@@ -123,6 +135,19 @@ impl<'a, 'b, 'c> AdvancedPublisherBuilder<'a, 'b, 'c> {
         self
     }
 
+    /// Periodically announce the sequence number of the last published sample.
+    ///
+    /// This allows matching [`AdvancedSubscribers`](crate::AdvancedSubscriber) that enable
+    /// [`heartbeat`](crate::RecoveryConfig::heartbeat) recovery to detect the loss of the
+    /// last published samples without waiting for a new publication.
+    /// This implies [`sample_miss_detection`](crate::AdvancedPublisherBuilder::sample_miss_detection).
+    #[zenoh_macros::unstable]
+    pub fn heartbeat(mut self, period: Duration) -> Self {
+        self.sequencing = Sequencing::SequenceNumber;
+        self.heartbeat = Some(period);
+        self
+    }
+
     /// Attach a cache to this [`AdvancedPublisher`].
     ///
     /// The cache can be used for history and/or recovery.
@@ -230,9 +255,10 @@ impl IntoFuture for AdvancedPublisherBuilder<'_, '_, '_> {
 #[zenoh_macros::unstable]
 pub struct AdvancedPublisher<'a> {
     publisher: Publisher<'a>,
-    seqnum: Option<AtomicU32>,
+    seqnum: Option<Arc<AtomicU32>>,
     cache: Option<AdvancedCache>,
     _token: Option<LivelinessToken>,
+    _heartbeat: Option<Timer>,
 }
 
 #[zenoh_macros::unstable]
@@ -270,7 +296,7 @@ impl<'a> AdvancedPublisher<'a> {
         };
 
         let seqnum = match conf.sequencing {
-            Sequencing::SequenceNumber => Some(AtomicU32::new(0)),
+            Sequencing::SequenceNumber => Some(Arc::new(AtomicU32::new(0))),
             Sequencing::Timestamp => {
                 if conf.session.hlc().is_none() {
                     bail!(
@@ -299,18 +325,41 @@ impl<'a> AdvancedPublisher<'a> {
             Some(
                 conf.session
                     .liveliness()
-                    .declare_token(prefix / &key_expr)
+                    .declare_token(&prefix / &key_expr)
                     .wait()?,
             )
         } else {
             None
         };
 
+        let heartbeat = match (conf.heartbeat, &seqnum) {
+            (Some(period), Some(seqnum)) => {
+                let publisher = conf
+                    .session
+                    .declare_publisher(&prefix / &key_expr)
+                    .reliability(conf.reliability)
+                    .priority(conf.priority)
+                    .wait()?;
+                let _rt = ZRuntime::Application.enter();
+                let timer = Timer::new(false);
+                timer.add(TimedEvent::periodic(
+                    period,
+                    Heartbeat {
+                        seqnum: seqnum.clone(),
+                        publisher,
+                    },
+                ));
+                Some(timer)
+            }
+            _ => None,
+        };
+
         Ok(AdvancedPublisher {
             publisher,
             seqnum,
             cache,
             _token: token,
+            _heartbeat: heartbeat,
         })
     }
 
@@ -488,6 +537,31 @@ impl<'a> AdvancedPublisher<'a> {
     }
 }
 
+/// Periodically publishes the sequence number of the last sample published by an [`AdvancedPublisher`].
+#[zenoh_macros::unstable]
+struct Heartbeat {
+    seqnum: Arc<AtomicU32>,
+    publisher: Publisher<'static>,
+}
+
+#[zenoh_macros::unstable]
+#[async_trait]
+impl Timed for Heartbeat {
+    async fn run(&mut self) {
+        let next_seqnum = self.seqnum.load(Ordering::Relaxed);
+        if next_seqnum == 0 {
+            return;
+        }
+        if let Err(e) = self
+            .publisher
+            .put(z_serialize(&next_seqnum.wrapping_sub(1)))
+            .await
+        {
+            tracing::warn!("Error sending heartbeat: {}", e);
+        }
+    }
+}
+
 #[zenoh_macros::unstable]
 pub type AdvancedPublisherPutBuilder<'a> = AdvancedPublicationBuilder<'a, PublicationBuilderPut>;
 #[zenoh_macros::unstable]
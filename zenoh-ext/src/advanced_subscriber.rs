@@ -35,8 +35,8 @@ use {
     std::collections::HashMap,
     std::convert::TryFrom,
     std::future::Ready,
-    std::sync::{Arc, Mutex},
-    std::time::Duration,
+    std::sync::{Arc, Mutex, Weak},
+    std::time::{Duration, Instant},
     uhlc::ID,
     zenoh::handlers::{locked, DefaultHandler},
     zenoh::internal::{runtime::ZRuntime, zlock},
@@ -46,7 +46,10 @@ use {
     zenoh::Result as ZResult,
 };
 
-use crate::advanced_cache::{ke_liveliness, KE_UHLC};
+use crate::{
+    advanced_cache::{ke_liveliness, KE_UHLC},
+    z_deserialize,
+};
 
 #[derive(Debug, Default, Clone)]
 /// Configure query for historical data.
@@ -90,12 +93,16 @@ impl HistoryConfig {
 #[zenoh_macros::unstable]
 pub struct RecoveryConfig {
     periodic_queries: Option<Duration>,
+    heartbeat: bool,
+    ordered: Option<Duration>,
 }
 
 impl std::fmt::Debug for RecoveryConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = f.debug_struct("RetransmissionConf");
         s.field("periodic_queries", &self.periodic_queries);
+        s.field("heartbeat", &self.heartbeat);
+        s.field("ordered", &self.ordered);
         s.finish()
     }
 }
@@ -116,6 +123,33 @@ impl RecoveryConfig {
         self.periodic_queries = period;
         self
     }
+
+    /// Enable recovery of the Samples announced by the heartbeats of matching publishers.
+    ///
+    /// This allows to retrieve the last Sample(s) if the last Sample(s) is/are lost
+    /// as soon as the next heartbeat is received. The Samples that can't be retrieved
+    /// are reported to the [`SampleMissListeners`](crate::SampleMissListener).
+    /// Heartbeats are only sent by [`AdvancedPublishers`](crate::AdvancedPublisher)
+    /// that enable [`heartbeat`](crate::AdvancedPublisherBuilder::heartbeat).
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn heartbeat(mut self) -> Self {
+        self.heartbeat = true;
+        self
+    }
+
+    /// Deliver Samples in strict source sequence order.
+    ///
+    /// When a gap is detected in the Samples of a source, the following Samples are held back
+    /// until the missing Samples are retrieved or `deadline` expires. The Samples still missing
+    /// when `deadline` expires are reported to the [`SampleMissListeners`](crate::SampleMissListener)
+    /// and the held back Samples are delivered.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn ordered(mut self, deadline: Duration) -> Self {
+        self.ordered = Some(deadline);
+        self
+    }
 }
 
 /// The builder of an [`AdvancedSubscriber`], allowing to configure it.
@@ -360,6 +394,9 @@ impl Wait for AdvancedSubscriberBuilder<'_, '_, '_, Callback<Sample>, true> {
         if let Some(mut liveliness_sub) = sub.liveliness_subscriber.take() {
             liveliness_sub.set_background(true);
         }
+        if let Some(mut heartbeat_sub) = sub.heartbeat_subscriber.take() {
+            heartbeat_sub.set_background(true);
+        }
         Ok(())
     }
 }
@@ -381,6 +418,12 @@ struct Period {
     period: Duration,
 }
 
+#[zenoh_macros::unstable]
+struct Deadline {
+    timer: Timer,
+    deadline: Duration,
+}
+
 #[zenoh_macros::unstable]
 struct State {
     next_id: usize,
@@ -391,6 +434,8 @@ struct State {
     key_expr: KeyExpr<'static>,
     retransmission: bool,
     period: Option<Period>,
+    deadline: Option<Deadline>,
+    statesref: Weak<Mutex<State>>,
     query_target: QueryTarget,
     query_timeout: Duration,
     callback: Callback<Sample>,
@@ -432,6 +477,8 @@ struct SourceState<T> {
     last_delivered: Option<T>,
     pending_queries: u64,
     pending_samples: BTreeMap<T, Sample>,
+    // When the pending samples started to be held back because of a gap (ordered mode only)
+    held_since: Option<Instant>,
 }
 
 /// [`AdvancedSubscriber`].
@@ -441,6 +488,7 @@ pub struct AdvancedSubscriber<Receiver> {
     subscriber: Subscriber<()>,
     receiver: Receiver,
     liveliness_subscriber: Option<Subscriber<()>>,
+    heartbeat_subscriber: Option<Subscriber<()>>,
 }
 
 #[zenoh_macros::unstable]
@@ -470,13 +518,27 @@ fn handle_sample(states: &mut State, sample: Sample) -> bool {
             last_delivered: None,
             pending_queries: 0,
             pending_samples: BTreeMap::new(),
+            held_since: None,
         });
         if states.global_pending_queries != 0 {
             state.pending_samples.insert(source_sn, sample);
         } else if state.last_delivered.is_some() && source_sn != state.last_delivered.unwrap() + 1 {
             if source_sn > state.last_delivered.unwrap() {
                 if states.retransmission {
+                    let source_id = *source_id;
                     state.pending_samples.insert(source_sn, sample);
+                    if let Some(deadline) = &states.deadline {
+                        if state.held_since.is_none() {
+                            state.held_since = Some(Instant::now());
+                            deadline.timer.add(TimedEvent::once(
+                                Instant::now() + deadline.deadline,
+                                GapDeadline {
+                                    source_id,
+                                    statesref: states.statesref.clone(),
+                                },
+                            ));
+                        }
+                    }
                 } else {
                     tracing::info!(
                         "Sample missed: missed {} samples from {:?}.",
@@ -502,6 +564,9 @@ fn handle_sample(states: &mut State, sample: Sample) -> bool {
                 last_seq_num += 1;
                 state.last_delivered = Some(last_seq_num);
             }
+            if state.pending_samples.is_empty() {
+                state.held_since = None;
+            }
         }
         new
     } else if let Some(timestamp) = sample.timestamp() {
@@ -510,6 +575,7 @@ fn handle_sample(states: &mut State, sample: Sample) -> bool {
             last_delivered: None,
             pending_queries: 0,
             pending_samples: BTreeMap::new(),
+            held_since: None,
         });
         if state.last_delivered.map(|t| t < *timestamp).unwrap_or(true) {
             if states.global_pending_queries == 0 && state.pending_queries == 0 {
@@ -568,6 +634,7 @@ impl Timed for PeriodicQuery {
             let handler = SequencedRepliesHandler {
                 source_id: self.source_id,
                 statesref: self.statesref.clone(),
+                expected: None,
             };
             let _ = session
                 .get(Selector::from((query_expr, seq_num_range)))
@@ -590,6 +657,43 @@ impl Timed for PeriodicQuery {
     }
 }
 
+/// Delivers the samples of a source held back because of a gap once the deadline expired.
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+struct GapDeadline {
+    source_id: EntityGlobalId,
+    statesref: Weak<Mutex<State>>,
+}
+
+#[zenoh_macros::unstable]
+#[async_trait]
+impl Timed for GapDeadline {
+    async fn run(&mut self) {
+        let Some(statesref) = self.statesref.upgrade() else {
+            return;
+        };
+        let mut lock = zlock!(statesref);
+        let states = &mut *lock;
+        let Some(deadline) = states.deadline.as_ref().map(|d| d.deadline) else {
+            return;
+        };
+        if let Some(state) = states.sequenced_states.get_mut(&self.source_id) {
+            // A newer deadline is scheduled if the samples were held back since then
+            if state
+                .held_since
+                .is_some_and(|held_since| held_since.elapsed() >= deadline)
+            {
+                deliver_pending_samples(
+                    state,
+                    &states.callback,
+                    &self.source_id,
+                    &states.miss_handlers,
+                );
+            }
+        }
+    }
+}
+
 #[zenoh_macros::unstable]
 impl<Handler> AdvancedSubscriber<Handler> {
     fn new<H>(conf: AdvancedSubscriberBuilder<'_, '_, '_, H>) -> ZResult<Self>
@@ -603,30 +707,41 @@ impl<Handler> AdvancedSubscriber<Handler> {
             None => None,
         };
         let retransmission = conf.retransmission;
+        let heartbeat = retransmission.as_ref().is_some_and(|r| r.heartbeat);
         let query_target = conf.query_target;
         let query_timeout = conf.query_timeout;
         let session = conf.session.clone();
-        let statesref = Arc::new(Mutex::new(State {
-            next_id: 0,
-            sequenced_states: HashMap::new(),
-            timestamped_states: HashMap::new(),
-            global_pending_queries: if conf.history.is_some() { 1 } else { 0 },
-            session,
-            period: retransmission.as_ref().and_then(|r| {
-                let _rt = ZRuntime::Application.enter();
-                r.periodic_queries.map(|p| Period {
-                    timer: Timer::new(false),
-                    period: p,
-                })
-            }),
-            key_expr: key_expr.clone().into_owned(),
-            retransmission: retransmission.is_some(),
-            query_target: conf.query_target,
-            query_timeout: conf.query_timeout,
-            callback: callback.clone(),
-            miss_handlers: HashMap::new(),
-            token: None,
-        }));
+        let statesref = Arc::new_cyclic(|statesref| {
+            Mutex::new(State {
+                next_id: 0,
+                sequenced_states: HashMap::new(),
+                timestamped_states: HashMap::new(),
+                global_pending_queries: if conf.history.is_some() { 1 } else { 0 },
+                session,
+                period: retransmission.as_ref().and_then(|r| {
+                    let _rt = ZRuntime::Application.enter();
+                    r.periodic_queries.map(|p| Period {
+                        timer: Timer::new(false),
+                        period: p,
+                    })
+                }),
+                deadline: retransmission.as_ref().and_then(|r| {
+                    let _rt = ZRuntime::Application.enter();
+                    r.ordered.map(|d| Deadline {
+                        timer: Timer::new(false),
+                        deadline: d,
+                    })
+                }),
+                statesref: statesref.clone(),
+                key_expr: key_expr.clone().into_owned(),
+                retransmission: retransmission.is_some(),
+                query_target: conf.query_target,
+                query_timeout: conf.query_timeout,
+                callback: callback.clone(),
+                miss_handlers: HashMap::new(),
+                token: None,
+            })
+        });
 
         let sub_callback = {
             let statesref = statesref.clone();
@@ -663,6 +778,7 @@ impl<Handler> AdvancedSubscriber<Handler> {
                             let handler = SequencedRepliesHandler {
                                 source_id,
                                 statesref: statesref.clone(),
+                                expected: None,
                             };
                             let _ = session
                                 .get(Selector::from((query_expr, seq_num_range)))
@@ -753,6 +869,7 @@ impl<Handler> AdvancedSubscriber<Handler> {
                                             last_delivered: None,
                                             pending_queries: 0,
                                             pending_samples: BTreeMap::new(),
+                                            held_since: None,
                                         });
                                         state.pending_queries += 1;
                                         drop(lock);
@@ -805,6 +922,7 @@ impl<Handler> AdvancedSubscriber<Handler> {
                                             last_delivered: None,
                                             pending_queries: 0,
                                             pending_samples: BTreeMap::new(),
+                                            held_since: None,
                                         });
                                         state.pending_queries += 1;
                                         drop(lock);
@@ -812,6 +930,7 @@ impl<Handler> AdvancedSubscriber<Handler> {
                                         let handler = SequencedRepliesHandler {
                                             source_id,
                                             statesref: statesref.clone(),
+                                            expected: None,
                                         };
                                         let mut params = Parameters::empty();
                                         if let Some(max) = historyconf.sample_depth {
@@ -921,6 +1040,95 @@ impl<Handler> AdvancedSubscriber<Handler> {
             None
         };
 
+        let heartbeat_subscriber = if heartbeat {
+            let heartbeat_callback = {
+                let session = conf.session.clone();
+                let statesref = statesref.clone();
+                let key_expr = key_expr.clone().into_owned();
+                move |s: Sample| {
+                    if s.kind() != SampleKind::Put {
+                        return;
+                    }
+                    let Some(source_id) = ke_liveliness::parse(s.key_expr().as_keyexpr())
+                        .ok()
+                        .and_then(|parsed| {
+                            Some(EntityGlobalId::new(
+                                ZenohId::from_str(parsed.zid().as_str()).ok()?,
+                                EntityId::from_str(parsed.eid().as_str()).ok()?,
+                            ))
+                        })
+                    else {
+                        tracing::warn!(
+                            "Received malformed heartbeat key expression: {}",
+                            s.key_expr()
+                        );
+                        return;
+                    };
+                    let Ok(heartbeat_sn) = z_deserialize::<u32>(s.payload()) else {
+                        tracing::warn!("Received malformed heartbeat from {:?}", source_id);
+                        return;
+                    };
+                    let mut lock = zlock!(statesref);
+                    let states = &mut *lock;
+                    if states.global_pending_queries != 0 {
+                        return;
+                    }
+                    // Only query sources from which samples were already delivered
+                    let Some(state) = states.sequenced_states.get_mut(&source_id) else {
+                        return;
+                    };
+                    let Some(last_delivered) = state.last_delivered else {
+                        return;
+                    };
+                    if heartbeat_sn <= last_delivered || state.pending_queries != 0 {
+                        return;
+                    }
+                    state.pending_queries += 1;
+                    let query_expr = KE_ADV_PREFIX
+                        / KE_STAR
+                        / &source_id.zid().into_keyexpr()
+                        / &KeyExpr::try_from(source_id.eid().to_string()).unwrap()
+                        / KE_STARSTAR
+                        / KE_AT
+                        / &key_expr;
+                    let seq_num_range = seq_num_range(Some(last_delivered + 1), Some(heartbeat_sn));
+                    drop(lock);
+                    let handler = SequencedRepliesHandler {
+                        source_id,
+                        statesref: statesref.clone(),
+                        expected: Some(heartbeat_sn),
+                    };
+                    let _ = session
+                        .get(Selector::from((query_expr, seq_num_range)))
+                        .callback({
+                            let key_expr = key_expr.clone().into_owned();
+                            move |r: Reply| {
+                                if let Ok(s) = r.into_result() {
+                                    if key_expr.intersects(s.key_expr()) {
+                                        let states = &mut *zlock!(handler.statesref);
+                                        handle_sample(states, s);
+                                    }
+                                }
+                            }
+                        })
+                        .consolidation(ConsolidationMode::None)
+                        .accept_replies(ReplyKeyExpr::Any)
+                        .target(query_target)
+                        .timeout(query_timeout)
+                        .wait();
+                }
+            };
+            Some(
+                conf.session
+                    .declare_subscriber(KE_ADV_PREFIX / KE_PUB / KE_STARSTAR / KE_AT / &key_expr)
+                    .callback(heartbeat_callback)
+                    .allowed_origin(conf.origin)
+                    .wait()?,
+            )
+        } else {
+            None
+        };
+
         if conf.liveliness {
             let prefix = KE_ADV_PREFIX
                 / KE_SUB
@@ -944,6 +1152,7 @@ impl<Handler> AdvancedSubscriber<Handler> {
             subscriber,
             receiver,
             liveliness_subscriber,
+            heartbeat_subscriber,
         };
 
         Ok(reliable_subscriber)
@@ -1017,36 +1226,48 @@ fn flush_sequenced_source(
     miss_handlers: &HashMap<usize, Callback<Miss>>,
 ) {
     if state.pending_queries == 0 && !state.pending_samples.is_empty() {
-        let mut pending_samples = BTreeMap::new();
-        std::mem::swap(&mut state.pending_samples, &mut pending_samples);
-        for (seq_num, sample) in pending_samples {
-            match state.last_delivered {
-                None => {
-                    state.last_delivered = Some(seq_num);
-                    callback.call(sample);
-                }
-                Some(last) if seq_num == last + 1 => {
-                    state.last_delivered = Some(seq_num);
-                    callback.call(sample);
-                }
-                Some(last) if seq_num > last + 1 => {
-                    tracing::warn!(
-                        "Sample missed: missed {} samples from {:?}.",
-                        seq_num - last - 1,
-                        source_id,
-                    );
-                    for miss_callback in miss_handlers.values() {
-                        miss_callback.call(Miss {
-                            source: *source_id,
-                            nb: seq_num - last - 1,
-                        })
-                    }
-                    state.last_delivered = Some(seq_num);
-                    callback.call(sample);
-                }
-                _ => {
-                    // duplicate
+        deliver_pending_samples(state, callback, source_id, miss_handlers);
+    }
+}
+
+/// Delivers the pending samples of a source in order, reporting the gaps between them as missed.
+#[zenoh_macros::unstable]
+fn deliver_pending_samples(
+    state: &mut SourceState<u32>,
+    callback: &Callback<Sample>,
+    source_id: &EntityGlobalId,
+    miss_handlers: &HashMap<usize, Callback<Miss>>,
+) {
+    state.held_since = None;
+    let mut pending_samples = BTreeMap::new();
+    std::mem::swap(&mut state.pending_samples, &mut pending_samples);
+    for (seq_num, sample) in pending_samples {
+        match state.last_delivered {
+            None => {
+                state.last_delivered = Some(seq_num);
+                callback.call(sample);
+            }
+            Some(last) if seq_num == last + 1 => {
+                state.last_delivered = Some(seq_num);
+                callback.call(sample);
+            }
+            Some(last) if seq_num > last + 1 => {
+                tracing::warn!(
+                    "Sample missed: missed {} samples from {:?}.",
+                    seq_num - last - 1,
+                    source_id,
+                );
+                for miss_callback in miss_handlers.values() {
+                    miss_callback.call(Miss {
+                        source: *source_id,
+                        nb: seq_num - last - 1,
+                    })
                 }
+                state.last_delivered = Some(seq_num);
+                callback.call(sample);
+            }
+            _ => {
+                // duplicate
             }
         }
     }
@@ -1100,6 +1321,8 @@ impl Drop for InitialRepliesHandler {
 struct SequencedRepliesHandler {
     source_id: EntityGlobalId,
     statesref: Arc<Mutex<State>>,
+    // The last sequence number announced by a heartbeat
+    expected: Option<u32>,
 }
 
 #[zenoh_macros::unstable]
@@ -1108,15 +1331,70 @@ impl Drop for SequencedRepliesHandler {
         let states = &mut *zlock!(self.statesref);
         if let Some(state) = states.sequenced_states.get_mut(&self.source_id) {
             state.pending_queries = state.pending_queries.saturating_sub(1);
-            if states.global_pending_queries == 0 {
+            if states.global_pending_queries != 0 {
+                return;
+            }
+            if state.held_since.is_none() {
                 flush_sequenced_source(
                     state,
                     &states.callback,
                     &self.source_id,
                     &states.miss_handlers,
-                )
+                );
+                if let Some(expected) = self.expected.filter(|_| state.pending_queries == 0) {
+                    skip_to_expected(state, expected, &self.source_id, &states.miss_handlers);
+                }
+            } else if let Some(expected) = self.expected.filter(|_| state.pending_queries == 0) {
+                // In ordered mode, the held samples up to the sequence number announced by the
+                // heartbeat can't be recovered anymore: deliver them before skipping the gap.
+                // The later ones keep waiting for the deadline.
+                let held_since = state.held_since;
+                let later = state.pending_samples.split_off(&(expected + 1));
+                deliver_pending_samples(
+                    state,
+                    &states.callback,
+                    &self.source_id,
+                    &states.miss_handlers,
+                );
+                skip_to_expected(state, expected, &self.source_id, &states.miss_handlers);
+                state.pending_samples = later;
+                if let Some(mut last) = state.last_delivered {
+                    while let Some(s) = state.pending_samples.remove(&(last + 1)) {
+                        states.callback.call(s);
+                        last += 1;
+                    }
+                    state.last_delivered = Some(last);
+                }
+                if !state.pending_samples.is_empty() {
+                    state.held_since = held_since;
+                }
             }
+            // Otherwise, samples held back because of a gap wait for the deadline
+        }
+    }
+}
+
+/// Reports the samples up to the sequence number announced by a heartbeat as missed.
+#[zenoh_macros::unstable]
+fn skip_to_expected(
+    state: &mut SourceState<u32>,
+    expected: u32,
+    source_id: &EntityGlobalId,
+    miss_handlers: &HashMap<usize, Callback<Miss>>,
+) {
+    if let Some(last) = state.last_delivered.filter(|last| expected > *last) {
+        tracing::warn!(
+            "Sample missed: missed {} samples from {:?}.",
+            expected - last,
+            source_id,
+        );
+        for miss_callback in miss_handlers.values() {
+            miss_callback.call(Miss {
+                source: *source_id,
+                nb: expected - last,
+            })
         }
+        state.last_delivered = Some(expected);
     }
 }
 
@@ -0,0 +1,344 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{mpsc, Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use zenoh::{
+    bytes::{Encoding, ZBytes},
+    internal::{zerror, zlock},
+    key_expr::KeyExpr,
+    sample::{Sample, SampleBuilder, SampleKind, SourceInfo},
+    session::{EntityGlobalId, ZenohId},
+    time::Timestamp,
+    Result as ZResult,
+};
+
+use crate::{ZDeserializer, ZSerializer};
+
+/// Minimum number of evicted records before a [`FileCacheStore`] compacts its file.
+const MIN_COMPACTION_RECORDS: usize = 1024;
+
+/// A persistent store for the samples of an [`AdvancedPublisher`](crate::AdvancedPublisher)
+/// cache, allowing late joiners to retrieve its history after the publisher restarted.
+///
+/// The cache loads the stored samples when it is created, then pushes each sample it caches to
+/// the store and evicts them from the store in the same order it evicts them from memory.
+/// These calls are made from a dedicated thread, so that the store I/O neither delays the
+/// publications nor the replies to the queries on the cache.
+///
+/// [`FileCacheStore`] stores the samples in a local file. No store backed by a
+/// `zenoh_backend_traits::Storage` is provided, as this crate does not depend on the storage
+/// plugin traits: such a store can be plugged by implementing this trait.
+#[zenoh_macros::unstable]
+pub trait CacheStore: Send + Sync {
+    /// Returns the stored samples, oldest first, along with the time they were cached at.
+    fn load(&self) -> ZResult<Vec<(SystemTime, Sample)>>;
+
+    /// Stores a sample cached at `time`.
+    fn push(&self, time: SystemTime, sample: &Sample) -> ZResult<()>;
+
+    /// Removes the `count` oldest stored samples.
+    fn evict(&self, count: usize) -> ZResult<()>;
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for dyn CacheStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CacheStore")
+    }
+}
+
+/// A [`CacheStore`] appending the cached samples to a log file.
+///
+/// Evicted samples are only removed from the file when it gets compacted, i.e. when they
+/// outnumber the samples still in cache.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh_ext::{AdvancedPublisherBuilderExt, CacheConfig, FileCacheStore};
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let publisher = session
+///     .declare_publisher("key/expression")
+///     .cache(
+///         CacheConfig::default()
+///             .max_samples(100)
+///             .store(FileCacheStore::new("/var/lib/my_app/cache.log").unwrap()),
+///     )
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct FileCacheStore {
+    path: PathBuf,
+    state: Mutex<FileState>,
+}
+
+struct FileState {
+    file: File,
+    records: usize,
+    evicted: usize,
+}
+
+#[zenoh_macros::unstable]
+impl FileCacheStore {
+    /// Opens the log file at `path`, creating it if it does not exist.
+    #[zenoh_macros::unstable]
+    pub fn new<P: AsRef<Path>>(path: P) -> ZResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_log(&path)?;
+        let records = split_records(&fs::read(&path)?).0.len();
+        Ok(Self {
+            path,
+            state: Mutex::new(FileState {
+                file,
+                records,
+                evicted: 0,
+            }),
+        })
+    }
+
+    /// Rewrites the file without the evicted records.
+    fn compact(&self, state: &mut FileState) -> ZResult<()> {
+        let buf = fs::read(&self.path)?;
+        let (records, _) = split_records(&buf);
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut tmp = File::create(&tmp_path)?;
+        for record in records.iter().skip(state.evicted) {
+            write_record(&mut tmp, record)?;
+        }
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+        state.file = open_log(&self.path)?;
+        state.records = records.len().saturating_sub(state.evicted);
+        state.evicted = 0;
+        Ok(())
+    }
+}
+
+#[zenoh_macros::unstable]
+impl CacheStore for FileCacheStore {
+    fn load(&self) -> ZResult<Vec<(SystemTime, Sample)>> {
+        let mut state = zlock!(self.state);
+        let buf = fs::read(&self.path)?;
+        let (records, len) = split_records(&buf);
+        if len < buf.len() {
+            tracing::warn!(
+                "Truncate {} incomplete bytes at the end of cache file {}",
+                buf.len() - len,
+                self.path.display()
+            );
+            state.file.set_len(len as u64)?;
+        }
+        state.records = records.len();
+        state.evicted = 0;
+        records.into_iter().map(decode_sample).collect()
+    }
+
+    fn push(&self, time: SystemTime, sample: &Sample) -> ZResult<()> {
+        let mut state = zlock!(self.state);
+        write_record(&mut state.file, &encode_sample(time, sample))?;
+        state.records += 1;
+        Ok(())
+    }
+
+    fn evict(&self, count: usize) -> ZResult<()> {
+        let mut state = zlock!(self.state);
+        state.evicted = (state.evicted + count).min(state.records);
+        if state.evicted >= MIN_COMPACTION_RECORDS.max(state.records - state.evicted) {
+            self.compact(&mut state)?;
+        }
+        Ok(())
+    }
+}
+
+/// An operation of a cache on its [`CacheStore`].
+// Pushes are the most frequent operations, boxing their sample would only add an allocation
+#[allow(clippy::large_enum_variant)]
+enum StoreOp {
+    Push(SystemTime, Sample),
+    Evict(usize),
+}
+
+/// Applies the operations of a cache on its [`CacheStore`] in order, from a dedicated thread.
+///
+/// Dropping the writer waits for the pending operations to be applied.
+pub(crate) struct CacheStoreWriter {
+    sender: Option<mpsc::Sender<StoreOp>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CacheStoreWriter {
+    pub(crate) fn new(store: Arc<dyn CacheStore>) -> ZResult<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("adv-cache-store".to_string())
+            .spawn(move || {
+                for op in receiver {
+                    match op {
+                        StoreOp::Push(time, sample) => {
+                            if let Err(e) = store.push(time, &sample) {
+                                tracing::warn!(
+                                    "Unable to store sample in AdvancedPublisher cache: {}",
+                                    e
+                                );
+                            }
+                        }
+                        StoreOp::Evict(count) => {
+                            if let Err(e) = store.evict(count) {
+                                tracing::warn!(
+                                    "Unable to evict samples from AdvancedPublisher cache: {}",
+                                    e
+                                );
+                            }
+                        }
+                    }
+                }
+            })?;
+        Ok(Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    pub(crate) fn push(&self, time: SystemTime, sample: Sample) {
+        self.send(StoreOp::Push(time, sample));
+    }
+
+    pub(crate) fn evict(&self, count: usize) {
+        self.send(StoreOp::Evict(count));
+    }
+
+    fn send(&self, op: StoreOp) {
+        if let Some(sender) = &self.sender {
+            if sender.send(op).is_err() {
+                tracing::error!("AdvancedPublisher cache store thread has stopped");
+            }
+        }
+    }
+}
+
+impl Drop for CacheStoreWriter {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                tracing::error!("AdvancedPublisher cache store thread panicked");
+            }
+        }
+    }
+}
+
+fn open_log(path: &Path) -> ZResult<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| zerror!("Unable to open cache file {}: {}", path.display(), e).into())
+}
+
+/// Each record is prefixed by its length as a little endian u32.
+fn write_record(file: &mut File, record: &[u8]) -> ZResult<()> {
+    let mut buf = Vec::with_capacity(4 + record.len());
+    buf.extend_from_slice(&(record.len() as u32).to_le_bytes());
+    buf.extend_from_slice(record);
+    file.write_all(&buf)?;
+    Ok(())
+}
+
+/// Returns the complete records of `buf` and the length they span, which is smaller than
+/// the length of `buf` if the last record was partially written.
+fn split_records(buf: &[u8]) -> (Vec<&[u8]>, usize) {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while let Some(len) = buf
+        .get(offset..offset + 4)
+        .map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize)
+    {
+        match buf.get(offset + 4..offset + 4 + len) {
+            Some(record) => records.push(record),
+            None => break,
+        }
+        offset += 4 + len;
+    }
+    (records, offset)
+}
+
+fn encode_sample(time: SystemTime, sample: &Sample) -> Vec<u8> {
+    let mut serializer = ZSerializer::new();
+    serializer.serialize(
+        time.duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64,
+    );
+    serializer.serialize(sample.key_expr().as_str());
+    serializer.serialize(sample.kind() == SampleKind::Delete);
+    serializer.serialize(sample.payload());
+    serializer.serialize(sample.encoding().to_string());
+    serializer.serialize(sample.timestamp().map(|ts| ts.to_string()));
+    serializer.serialize(
+        sample
+            .source_info()
+            .source_id()
+            .map(|id| (id.zid().to_string(), id.eid())),
+    );
+    serializer.serialize(sample.source_info().source_sn());
+    serializer.serialize(sample.attachment());
+    serializer.finish().to_bytes().into_owned()
+}
+
+fn decode_sample(record: &[u8]) -> ZResult<(SystemTime, Sample)> {
+    let zbytes = ZBytes::from(record);
+    let mut deserializer = ZDeserializer::new(&zbytes);
+    let time = UNIX_EPOCH + Duration::from_nanos(deserializer.deserialize()?);
+    let key_expr = KeyExpr::try_from(deserializer.deserialize::<String>()?)?;
+    let is_delete: bool = deserializer.deserialize()?;
+    let payload: Vec<u8> = deserializer.deserialize()?;
+    let encoding = Encoding::from(deserializer.deserialize::<String>()?);
+    let timestamp = deserializer
+        .deserialize::<Option<String>>()?
+        .map(|ts| Timestamp::from_str(&ts).map_err(|e| zerror!("Invalid timestamp: {:?}", e)))
+        .transpose()?;
+    let source_id = deserializer
+        .deserialize::<Option<(String, u32)>>()?
+        .map(|(zid, eid)| ZResult::Ok(EntityGlobalId::new(ZenohId::from_str(&zid)?, eid)))
+        .transpose()?;
+    let source_sn: Option<u32> = deserializer.deserialize()?;
+    let attachment: Option<Vec<u8>> = deserializer.deserialize()?;
+    let sample: Sample = if is_delete {
+        SampleBuilder::delete(key_expr).into()
+    } else {
+        SampleBuilder::put(key_expr, payload)
+            .encoding(encoding)
+            .into()
+    };
+    let sample = SampleBuilder::from(sample)
+        .timestamp(timestamp)
+        .source_info(SourceInfo::new(source_id, source_sn))
+        .attachment(attachment)
+        .into();
+    Ok((time, sample))
+}
@@ -13,12 +13,20 @@
 //
 
 //! To manage groups and group memberships
+//!
+//! Each member of a group declares a liveliness token, so that members leave the group
+//! either when they drop their [`Group`] or when their session is lost.
+//!
+//! Members elect a leader among them. As long as the leader is in the group, it remains the
+//! leader. When there is no leader, the member with the smallest identifier claims the
+//! leadership for a new epoch, greater than all the epochs known to the members. The epoch of a
+//! leader can be used as a fencing token: after a failover, the requests issued by a former
+//! leader carry a smaller epoch than those of the new leader and can be rejected.
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     convert::TryInto,
-    ops::Add,
     sync::Arc,
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use flume::{Receiver, Sender};
@@ -27,17 +35,22 @@ use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use zenoh::{
     bytes::ZBytesReader,
+    handlers::FifoChannelHandler,
     internal::{bail, Condition, TaskController},
     key_expr::{keyexpr, KeyExpr, OwnedKeyExpr},
-    pubsub::Publisher,
+    liveliness::LivelinessToken,
+    pubsub::Subscriber,
     qos::Priority,
-    Error as ZError, Result as ZResult, Session,
+    query::Queryable,
+    sample::{Sample, SampleKind},
+    Error as ZError, Result as ZResult, Session, Wait,
 };
 
 const GROUP_PREFIX: &str = "zenoh/ext/net/group";
-const EVENT_POSTFIX: &str = "evt";
-const VIEW_REFRESH_LEASE_RATIO: f32 = 0.75f32;
-const DEFAULT_LEASE: Duration = Duration::from_secs(18);
+const MEMBER_INFIX: &str = "member";
+const LEADER_INFIX: &str = "leader";
+const INFO_INFIX: &str = "info";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
 const DEFAULT_PRIORITY: Priority = Priority::DataHigh;
 
 #[zenoh_macros::unstable]
@@ -46,12 +59,6 @@ pub struct JoinEvent {
     pub member: Member,
 }
 
-#[zenoh_macros::unstable]
-#[derive(Serialize, Deserialize, Debug)]
-pub struct LeaseExpiredEvent {
-    pub mid: OwnedKeyExpr,
-}
-
 #[zenoh_macros::unstable]
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct LeaveEvent {
@@ -59,33 +66,21 @@ pub struct LeaveEvent {
 }
 
 #[zenoh_macros::unstable]
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NewLeaderEvent {
-    pub mid: OwnedKeyExpr,
-}
-
-#[zenoh_macros::unstable]
-#[derive(Serialize, Deserialize, Debug)]
-struct KeepAliveEvent {
-    pub mid: OwnedKeyExpr,
-}
-
-#[zenoh_macros::unstable]
-#[derive(Serialize, Deserialize, Debug)]
-enum GroupNetEvent {
-    Join(JoinEvent),
-    Leave(LeaveEvent),
-    KeepAlive(KeepAliveEvent),
+    pub leader: Leader,
 }
 
 /// Events exposed to the user to be informed for relevant
 /// changes in the group.
+///
+/// A member leaving the group, either explicitly or because its session was lost,
+/// is notified by a [`GroupEvent::Leave`].
 #[zenoh_macros::unstable]
 #[derive(Serialize, Deserialize, Debug)]
 pub enum GroupEvent {
     Join(JoinEvent),
     Leave(LeaveEvent),
-    LeaseExpired(LeaseExpiredEvent),
     NewLeader(NewLeaderEvent),
 }
 
@@ -101,9 +96,6 @@ pub enum MemberLiveliness {
 pub struct Member {
     mid: OwnedKeyExpr,
     info: Option<String>,
-    liveliness: MemberLiveliness,
-    lease: Duration,
-    refresh_ratio: f32,
     #[serde(skip)]
     priority: Priority,
 }
@@ -121,9 +113,6 @@ impl Member {
         Ok(Member {
             mid,
             info: None,
-            liveliness: MemberLiveliness::Auto,
-            lease: DEFAULT_LEASE,
-            refresh_ratio: VIEW_REFRESH_LEASE_RATIO,
             priority: DEFAULT_PRIORITY,
         })
     }
@@ -140,18 +129,18 @@ impl Member {
         self
     }
 
-    pub fn lease(mut self, d: Duration) -> Self {
-        self.lease = d;
+    #[deprecated = "Members liveliness is tracked by liveliness tokens, whose lease is the one of the transport."]
+    pub fn lease(self, _d: Duration) -> Self {
         self
     }
 
-    pub fn liveliness(mut self, l: MemberLiveliness) -> Self {
-        self.liveliness = l;
+    #[deprecated = "Members liveliness is tracked by liveliness tokens, which are always asserted automatically."]
+    pub fn liveliness(self, _l: MemberLiveliness) -> Self {
         self
     }
 
-    pub fn refresh_ratio(mut self, r: f32) -> Self {
-        self.refresh_ratio = r;
+    #[deprecated = "Members liveliness is tracked by liveliness tokens, which do not need to be refreshed."]
+    pub fn refresh_ratio(self, _r: f32) -> Self {
         self
     }
 
@@ -161,13 +150,76 @@ impl Member {
     }
 }
 
+/// The leader of a group, along with the epoch of its leadership.
+#[zenoh_macros::unstable]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Leader {
+    mid: OwnedKeyExpr,
+    epoch: u64,
+}
+
+impl Leader {
+    /// Returns the identifier of the leader.
+    pub fn id(&self) -> &keyexpr {
+        &self.mid
+    }
+
+    /// Returns the epoch of the leadership, which strictly increases at each failover and
+    /// can be used as a fencing token.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+/// A snapshot of the group, as seen by the local member.
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub struct GroupView {
+    version: u64,
+    members: Vec<Member>,
+    leader: Option<Leader>,
+}
+
+impl GroupView {
+    /// Returns the version of the view, incremented at each membership or leader change
+    /// observed by the local member.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the members of the group, sorted by identifier.
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Returns the leader of the group, if any is established.
+    pub fn leader(&self) -> Option<&Leader> {
+        self.leader.as_ref()
+    }
+}
+
+struct GroupInner {
+    // The other members of the group
+    members: HashMap<OwnedKeyExpr, Member>,
+    // The live leadership claims, as (epoch, member id)
+    claims: HashSet<(u64, OwnedKeyExpr)>,
+    max_epoch: u64,
+    local_claim: Option<(u64, LivelinessToken)>,
+    leader: Option<Leader>,
+    version: u64,
+    // Whether the tokens present when joining have been retrieved
+    synced: bool,
+}
+
 struct GroupState {
     gid: String,
     local_member: Member,
-    members: Mutex<HashMap<OwnedKeyExpr, (Member, Instant)>>,
-    group_publisher: Publisher<'static>,
+    inner: Mutex<GroupInner>,
     user_events_tx: Mutex<Option<Sender<GroupEvent>>>,
     cond: Condition,
+    session: Arc<Session>,
+    _token: LivelinessToken,
+    _queryable: Queryable<()>,
 }
 
 #[zenoh_macros::unstable]
@@ -183,182 +235,208 @@ impl Drop for Group {
     }
 }
 
-async fn keep_alive_task(state: Arc<GroupState>) {
-    let mid = state.local_member.mid.clone();
-    let evt = GroupNetEvent::KeepAlive(KeepAliveEvent { mid });
-    let buf = bincode::serialize(&evt).unwrap();
-    let period = state
-        .local_member
-        .lease
-        .mul_f32(state.local_member.refresh_ratio);
-    loop {
-        tokio::time::sleep(period).await;
-        tracing::trace!("Sending Keep Alive for: {}", &state.local_member.mid);
-        let _ = state.group_publisher.put(buf.clone()).await;
-    }
+/// Returns the claim with the greatest epoch, the smallest member id winning ties.
+fn best_claim(claims: &HashSet<(u64, OwnedKeyExpr)>) -> Option<&(u64, OwnedKeyExpr)> {
+    claims
+        .iter()
+        .max_by(|(e1, m1), (e2, m2)| e1.cmp(e2).then_with(|| m2.as_str().cmp(m1.as_str())))
 }
 
-async fn watchdog_task(s: Arc<GroupState>, period: Duration) {
-    loop {
-        tokio::time::sleep(period).await;
-        let now = Instant::now();
-        let mut ms = s.members.lock().await;
-        let expired_members: Vec<OwnedKeyExpr> = ms
-            .iter()
-            .filter(|e| e.1 .1 < now)
-            .map(|e| e.0.clone())
-            .collect();
-
-        for e in &expired_members {
-            tracing::debug!("Member with lease expired: {}", e);
-            ms.remove(e);
-        }
-        if !expired_members.is_empty() {
-            tracing::debug!("Other members list: {:?}", ms.keys());
-            drop(ms);
-            let u_evt = &*s.user_events_tx.lock().await;
-            for e in expired_members {
-                if let Some(tx) = u_evt {
-                    tx.send(GroupEvent::LeaseExpired(LeaseExpiredEvent { mid: e }))
-                        .unwrap()
-                }
-            }
-        }
+impl GroupState {
+    fn key_expr(&self, infix: &str) -> String {
+        format!("{GROUP_PREFIX}/{}/{infix}", self.gid)
     }
-}
 
-async fn query_handler(z: Arc<Session>, state: Arc<GroupState>) {
-    let qres: KeyExpr = format!(
-        "{}/{}/{}",
-        GROUP_PREFIX, &state.gid, &state.local_member.mid
-    )
-    .try_into()
-    .unwrap();
-    tracing::debug!("Started query handler for: {}", &qres);
-    let buf = bincode::serialize(&state.local_member).unwrap();
-    let queryable = z.declare_queryable(&qres).await.unwrap();
-
-    while let Ok(query) = queryable.recv_async().await {
-        tracing::trace!("Serving query for: {}", &qres);
-        query.reply(qres.clone(), buf.clone()).await.unwrap();
+    async fn notify(&self, evt: GroupEvent) {
+        let u_evt = &*self.user_events_tx.lock().await;
+        if let Some(tx) = u_evt {
+            // The user may have dropped the receiver
+            let _ = tx.send(evt);
+        }
     }
-}
 
-async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
-    let sub = z
-        .declare_subscriber(state.group_publisher.key_expr())
-        .await
-        .unwrap();
-    while let Ok(s) = sub.recv_async().await {
-        match bincode::deserialize_from::<ZBytesReader, GroupNetEvent>(s.payload().reader()) {
-            Ok(evt) => match evt {
-                GroupNetEvent::Join(je) => {
-                    tracing::debug!("Member join: {:?}", &je.member);
-                    let alive_till = Instant::now().add(je.member.lease);
-                    let mut ms = state.members.lock().await;
-                    ms.insert(je.member.mid.clone(), (je.member.clone(), alive_till));
-                    tracing::debug!("Other members list: {:?}", ms.keys());
-                    state.cond.notify_all();
-                    drop(ms);
-                    let u_evt = &*state.user_events_tx.lock().await;
-                    if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Join(je)).unwrap()
-                    }
-                }
-                GroupNetEvent::Leave(le) => {
-                    tracing::debug!("Member leave: {:?}", &le.mid);
-                    let mut ms = state.members.lock().await;
-                    ms.remove(&le.mid);
-                    tracing::debug!("Other members list: {:?}", ms.keys());
-                    drop(ms);
-                    let u_evt = &*state.user_events_tx.lock().await;
-                    if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Leave(le)).unwrap()
-                    }
-                }
-                GroupNetEvent::KeepAlive(kae) => {
-                    tracing::debug!(
-                        "KeepAlive from {} ({})",
-                        &kae.mid,
-                        if kae.mid.ne(&state.local_member.mid) {
-                            "other"
-                        } else {
-                            "myself"
-                        }
-                    );
-                    if kae.mid.ne(&state.local_member.mid) {
-                        let mut mm = state.members.lock().await;
-                        let v = mm.remove(&kae.mid);
-                        match v {
-                            Some((m, _)) => {
-                                tracing::trace!("Updating leasefor: {:?}", &kae.mid);
-                                let alive_till = Instant::now().add(m.lease);
-                                mm.insert(m.mid.clone(), (m, alive_till));
+    async fn member_info(&self, mid: &OwnedKeyExpr) -> Member {
+        let qres = format!("{}/{}", self.key_expr(INFO_INFIX), mid);
+        tracing::trace!("Issuing Query for {}", &qres);
+        match self
+            .session
+            .get(&qres)
+            .priority(self.local_member.priority)
+            .timeout(QUERY_TIMEOUT)
+            .await
+        {
+            Ok(receiver) => {
+                while let Ok(reply) = receiver.recv_async().await {
+                    match reply.result() {
+                        Ok(sample) => match bincode::deserialize_from::<ZBytesReader, Member>(
+                            sample.payload().reader(),
+                        ) {
+                            Ok(m) => {
+                                tracing::debug!("Received member information: {:?}", &m);
+                                return m;
                             }
-                            None => {
-                                tracing::debug!(
-                                    "Received Keep Alive from unknown member: {}",
-                                    &kae.mid
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Unable to deserialize the Member info received: {}",
+                                    e
                                 );
-                                let qres = format!("{}/{}/{}", GROUP_PREFIX, &state.gid, kae.mid);
-                                // @TODO: we could also send this member info
-                                let qc = zenoh::query::ConsolidationMode::None;
-                                tracing::trace!("Issuing Query for {}", &qres);
-                                let receiver = z.get(&qres).consolidation(qc).await.unwrap();
-
-                                while let Ok(reply) = receiver.recv_async().await {
-                                    match reply.result() {
-                                        Ok(sample) => {
-                                            match bincode::deserialize_from::<ZBytesReader, Member>(
-                                                sample.payload().reader(),
-                                            ) {
-                                                Ok(m) => {
-                                                    let mut expiry = Instant::now();
-                                                    expiry = expiry.add(m.lease);
-                                                    tracing::debug!(
-                                                        "Received member information: {:?}",
-                                                        &m
-                                                    );
-                                                    mm.insert(kae.mid.clone(), (m.clone(), expiry));
-                                                    tracing::debug!(
-                                                        "Other members list: {:?}",
-                                                        mm.keys()
-                                                    );
-                                                    // Advertise a JoinEvent
-                                                    let u_evt = &*state.user_events_tx.lock().await;
-                                                    if let Some(tx) = u_evt {
-                                                        let je = JoinEvent { member: m };
-                                                        tx.send_async(GroupEvent::Join(je))
-                                                            .await
-                                                            .unwrap()
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    tracing::warn!(
-                                                        "Unable to deserialize the Member info received: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!("Error received: {:?}", e);
-                                        }
-                                    }
-                                }
-                                state.cond.notify_all();
                             }
+                        },
+                        Err(e) => {
+                            tracing::warn!("Error received: {:?}", e);
                         }
-                    } else {
-                        tracing::trace!("KeepAlive from Local Participant -- Ignoring");
                     }
                 }
-            },
-            Err(e) => {
-                tracing::warn!("Failed decoding net-event due to: {:?}", e);
+            }
+            Err(e) => tracing::warn!("Unable to query the Member info of {}: {}", mid, e),
+        }
+        Member {
+            mid: mid.clone(),
+            info: None,
+            priority: DEFAULT_PRIORITY,
+        }
+    }
+
+    async fn handle_member_sample(&self, sample: &Sample) {
+        let prefix = format!("{}/", self.key_expr(MEMBER_INFIX));
+        let Some(mid) = sample
+            .key_expr()
+            .as_str()
+            .strip_prefix(&prefix)
+            .and_then(|mid| OwnedKeyExpr::new(mid).ok())
+        else {
+            tracing::warn!("Received invalid member token: {}", sample.key_expr());
+            return;
+        };
+        if mid == self.local_member.mid {
+            return;
+        }
+        match sample.kind() {
+            SampleKind::Put => {
+                if self.inner.lock().await.members.contains_key(&mid) {
+                    return;
+                }
+                let member = self.member_info(&mid).await;
+                tracing::debug!("Member join: {:?}", &member);
+                let mut inner = self.inner.lock().await;
+                inner.members.insert(mid, member.clone());
+                inner.version += 1;
+                tracing::debug!("Other members list: {:?}", inner.members.keys());
+                self.cond.notify_all();
+                self.notify(GroupEvent::Join(JoinEvent { member })).await;
+                self.elect(&mut inner).await;
+            }
+            SampleKind::Delete => {
+                let mut inner = self.inner.lock().await;
+                if inner.members.remove(&mid).is_some() {
+                    tracing::debug!("Member leave: {:?}", &mid);
+                    inner.version += 1;
+                    tracing::debug!("Other members list: {:?}", inner.members.keys());
+                    self.cond.notify_all();
+                    self.notify(GroupEvent::Leave(LeaveEvent { mid })).await;
+                    self.elect(&mut inner).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_leader_sample(&self, sample: &Sample) {
+        let prefix = format!("{}/", self.key_expr(LEADER_INFIX));
+        let Some((epoch, mid)) = sample
+            .key_expr()
+            .as_str()
+            .strip_prefix(&prefix)
+            .and_then(|claim| claim.split_once('/'))
+            .and_then(|(epoch, mid)| Some((epoch.parse().ok()?, OwnedKeyExpr::new(mid).ok()?)))
+        else {
+            tracing::warn!("Received invalid leader token: {}", sample.key_expr());
+            return;
+        };
+        let mut inner = self.inner.lock().await;
+        match sample.kind() {
+            SampleKind::Put => {
+                tracing::debug!("Leadership claim of {} for epoch {}", &mid, epoch);
+                inner.max_epoch = inner.max_epoch.max(epoch);
+                inner.claims.insert((epoch, mid));
+            }
+            SampleKind::Delete => {
+                tracing::debug!("Leadership release of {} for epoch {}", &mid, epoch);
+                inner.claims.remove(&(epoch, mid));
+            }
+        }
+        self.elect(&mut inner).await;
+    }
+
+    /// Updates the leader according to the live claims, the local member stepping down if
+    /// another claim wins over its own, or claiming the leadership if there is no leader and
+    /// it has the smallest identifier of the group.
+    async fn elect(&self, inner: &mut GroupInner) {
+        if !inner.synced {
+            return;
+        }
+        let local_mid = &self.local_member.mid;
+        if let Some((epoch, _)) = &inner.local_claim {
+            let local = (*epoch, local_mid.clone());
+            if best_claim(&inner.claims) != Some(&local) {
+                tracing::debug!("Stepping down from leadership of epoch {}", epoch);
+                inner.claims.remove(&local);
+                inner.local_claim = None;
+            }
+        }
+        if inner.claims.is_empty()
+            && inner
+                .members
+                .keys()
+                .all(|mid| local_mid.as_str() < mid.as_str())
+        {
+            let epoch = inner.max_epoch + 1;
+            let claim = format!("{}/{}/{}", self.key_expr(LEADER_INFIX), epoch, local_mid);
+            tracing::debug!("Claiming leadership for epoch {}", epoch);
+            match self.session.liveliness().declare_token(claim).await {
+                Ok(token) => {
+                    inner.max_epoch = epoch;
+                    inner.claims.insert((epoch, local_mid.clone()));
+                    inner.local_claim = Some((epoch, token));
+                }
+                Err(e) => tracing::warn!("Unable to claim leadership: {}", e),
+            }
+        }
+        let leader = best_claim(&inner.claims).map(|(epoch, mid)| Leader {
+            mid: mid.clone(),
+            epoch: *epoch,
+        });
+        if leader != inner.leader {
+            inner.leader = leader.clone();
+            inner.version += 1;
+            self.cond.notify_all();
+            if let Some(leader) = leader {
+                tracing::debug!("New leader: {:?}", &leader);
+                self.notify(GroupEvent::NewLeader(NewLeaderEvent { leader }))
+                    .await;
             }
         }
     }
 }
 
+async fn net_event_handler(
+    state: Arc<GroupState>,
+    member_sub: Subscriber<FifoChannelHandler<Sample>>,
+    leader_sub: Subscriber<FifoChannelHandler<Sample>>,
+) {
+    loop {
+        select! {
+            s = member_sub.recv_async().fuse() => match s {
+                Ok(s) => state.handle_member_sample(&s).await,
+                Err(_) => break,
+            },
+            s = leader_sub.recv_async().fuse() => match s {
+                Ok(s) => state.handle_leader_sample(&s).await,
+                Err(_) => break,
+            },
+        }
+    }
+}
+
 impl Group {
     pub async fn join<T>(z: Arc<Session>, group: T, with: Member) -> ZResult<Group>
     where
@@ -370,36 +448,87 @@ impl Group {
             bail!("Group ID is not allowed to contain wildcards: {}", group);
         }
 
-        let event_expr = format!("{GROUP_PREFIX}/{group}/{EVENT_POSTFIX}");
-        let publisher = z
-            .declare_publisher(event_expr)
-            .priority(with.priority)
-            .await
+        let qres: KeyExpr = format!("{GROUP_PREFIX}/{group}/{INFO_INFIX}/{}", &with.mid)
+            .try_into()
             .unwrap();
+        tracing::debug!("Started query handler for: {}", &qres);
+        let buf = bincode::serialize(&with).unwrap();
+        let priority = with.priority;
+        let queryable = z
+            .declare_queryable(qres.clone())
+            .callback(move |query| {
+                tracing::trace!("Serving query for: {}", &qres);
+                if let Err(e) = query.reply(&qres, buf.clone()).priority(priority).wait() {
+                    tracing::warn!("Unable to reply to query for {}: {}", &qres, e);
+                }
+            })
+            .await?;
+
+        // announce the member:
+        tracing::debug!("Declaring liveliness token for local member: {:?}", &with);
+        let token = z
+            .liveliness()
+            .declare_token(format!(
+                "{GROUP_PREFIX}/{group}/{MEMBER_INFIX}/{}",
+                &with.mid
+            ))
+            .await?;
+
+        // The subscribers are declared before retrieving the current tokens so that no change
+        // is missed in between.
+        let member_expr = format!("{GROUP_PREFIX}/{group}/{MEMBER_INFIX}/**");
+        let leader_expr = format!("{GROUP_PREFIX}/{group}/{LEADER_INFIX}/**");
+        let member_sub = z.liveliness().declare_subscriber(&member_expr).await?;
+        let leader_sub = z.liveliness().declare_subscriber(&leader_expr).await?;
+
         let state = Arc::new(GroupState {
             gid: String::from(group),
-            local_member: with.clone(),
-            members: Mutex::new(Default::default()),
-            group_publisher: publisher,
+            local_member: with,
+            inner: Mutex::new(GroupInner {
+                members: HashMap::new(),
+                claims: HashSet::new(),
+                max_epoch: 0,
+                local_claim: None,
+                leader: None,
+                version: 0,
+                synced: false,
+            }),
             user_events_tx: Mutex::new(Default::default()),
             cond: Condition::new(),
+            session: z.clone(),
+            _token: token,
+            _queryable: queryable,
         });
-        let is_auto_liveliness = matches!(with.liveliness, MemberLiveliness::Auto);
 
-        // announce the member:
-        tracing::debug!("Sending Join Message for local member: {:?}", &with);
-        let join_evt = GroupNetEvent::Join(JoinEvent { member: with });
-        let buf = bincode::serialize(&join_evt).unwrap();
-        let _ = state.group_publisher.put(buf).await;
+        // The current leader is retrieved before the members, so that the local member does not
+        // claim the leadership of a group that already has a leader.
+        let replies = z
+            .liveliness()
+            .get(&leader_expr)
+            .timeout(QUERY_TIMEOUT)
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.result() {
+                state.handle_leader_sample(sample).await;
+            }
+        }
+        let replies = z
+            .liveliness()
+            .get(&member_expr)
+            .timeout(QUERY_TIMEOUT)
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.result() {
+                state.handle_member_sample(sample).await;
+            }
+        }
+        let mut inner = state.inner.lock().await;
+        inner.synced = true;
+        state.elect(&mut inner).await;
+        drop(inner);
 
         let task_controller = TaskController::default();
-        // If the liveliness is manual it is the user who has to assert it.
-        if is_auto_liveliness {
-            task_controller.spawn_abortable(keep_alive_task(state.clone()));
-        }
-        task_controller.spawn_abortable(net_event_handler(z.clone(), state.clone()));
-        task_controller.spawn_abortable(query_handler(z.clone(), state.clone()));
-        task_controller.spawn_abortable(watchdog_task(state.clone(), Duration::from_secs(1)));
+        task_controller.spawn_abortable(net_event_handler(state.clone(), member_sub, leader_sub));
         Ok(Group {
             state,
             task_controller,
@@ -426,34 +555,37 @@ impl Group {
     }
 
     /// Returns the current group view, in other terms the list
-    /// of group members.
+    /// of group members, sorted by identifier.
     pub async fn view(&self) -> Vec<Member> {
-        let mut ms: Vec<Member> = self
-            .state
-            .members
-            .lock()
-            .await
-            .iter()
-            .map(|e| e.1 .0.clone())
-            .collect();
-        ms.push(self.state.local_member.clone());
-        ms
+        self.group_view().await.members
+    }
+
+    /// Returns a consistent snapshot of the group members and of its leader.
+    pub async fn group_view(&self) -> GroupView {
+        let inner = self.state.inner.lock().await;
+        let mut members: Vec<Member> = inner.members.values().cloned().collect();
+        members.push(self.state.local_member.clone());
+        members.sort_by(|m1, m2| m1.mid.as_str().cmp(m2.mid.as_str()));
+        GroupView {
+            version: inner.version,
+            members,
+            leader: inner.leader.clone(),
+        }
     }
 
     /// Wait for a view size to be established or times out. The resulting selector parameters
     /// indicates whether the desired view size has been established.
     pub async fn wait_for_view_size(&self, size: usize, timeout: Duration) -> bool {
-        if self.state.members.lock().await.len() + 1 >= size {
+        if self.state.inner.lock().await.members.len() + 1 >= size {
             true
         } else {
-            // let s = self.state.clone();
             let f = async {
                 loop {
-                    let ms = self.state.members.lock().await;
-                    if ms.len() + 1 >= size {
+                    let inner = self.state.inner.lock().await;
+                    if inner.members.len() + 1 >= size {
                         return true;
                     } else {
-                        self.state.cond.wait(ms).await;
+                        self.state.cond.wait(inner).await;
                     }
                 }
             };
@@ -467,21 +599,41 @@ impl Group {
 
     /// Returns the current group size.
     pub async fn size(&self) -> usize {
-        let ms = self.state.members.lock().await;
-        ms.len() + 1 // with +1 being the local member
+        let inner = self.state.inner.lock().await;
+        inner.members.len() + 1 // with +1 being the local member
+    }
+
+    /// Returns the current leader of the group, if any is established. Notice that a view
+    /// change may cause a change on leader. Thus it is wise to always get the leader after
+    /// a view change.
+    pub async fn leader(&self) -> Option<Leader> {
+        self.state.inner.lock().await.leader.clone()
     }
 
-    /// Returns the evental leader for this group. Notice that a view change may cause
-    /// a change on leader. Thus it is wise to always get the leader after a view change.
-    pub async fn leader(&self) -> Member {
-        use std::cmp::Ordering;
-        let group = self.view().await;
-        let mut leader = self.state.local_member.clone();
-        for m in group {
-            if leader.id().as_str().cmp(m.id().as_str()) == Ordering::Less {
-                leader = m
+    /// Wait for a leader to be established or times out.
+    pub async fn wait_for_leader(&self, timeout: Duration) -> Option<Leader> {
+        let f = async {
+            loop {
+                let inner = self.state.inner.lock().await;
+                match &inner.leader {
+                    Some(leader) => return leader.clone(),
+                    None => self.state.cond.wait(inner).await,
+                }
             }
+        };
+        select! {
+            p = f.fuse() => Some(p),
+            _ = tokio::time::sleep(timeout).fuse() => None,
         }
-        leader
+    }
+
+    /// Returns the epoch of the local member leadership if it is the leader of the group.
+    pub async fn leadership(&self) -> Option<u64> {
+        let inner = self.state.inner.lock().await;
+        inner
+            .leader
+            .as_ref()
+            .filter(|leader| leader.mid == self.state.local_member.mid)
+            .map(|leader| leader.epoch)
     }
 }
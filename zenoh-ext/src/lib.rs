@@ -18,19 +18,31 @@ mod advanced_publisher;
 #[cfg(feature = "unstable")]
 mod advanced_subscriber;
 #[cfg(feature = "unstable")]
+mod cache_store;
+#[cfg(feature = "unstable")]
 pub mod group;
 #[cfg(feature = "unstable")]
+mod lock;
+#[cfg(feature = "unstable")]
 mod publication_cache;
 #[cfg(feature = "unstable")]
 mod publisher_ext;
 #[cfg(feature = "unstable")]
 mod querying_subscriber;
+#[cfg(feature = "unstable")]
+mod rpc;
+#[cfg(feature = "unstable")]
+mod schema;
 mod serialization;
 #[cfg(feature = "unstable")]
 mod session_ext;
 #[cfg(feature = "unstable")]
 mod subscriber_ext;
 
+#[cfg(feature = "unstable")]
+pub use zenoh_macros::Schema;
+pub use zenoh_macros::{Deserialize, Serialize};
+
 #[cfg(feature = "internal")]
 pub use crate::serialization::VarInt;
 pub use crate::serialization::{
@@ -38,6 +50,13 @@ pub use crate::serialization::{
     ZReadIter, ZSerializer,
 };
 #[cfg(feature = "unstable")]
+pub use crate::serialization::{z_deserialize_borrowed, DeserializeBorrowed, DeserializeOwned};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::serialization::VarInt;
+}
+#[cfg(feature = "unstable")]
 #[allow(deprecated)]
 pub use crate::{
     advanced_cache::{CacheConfig, RepliesConfig},
@@ -46,12 +65,16 @@ pub use crate::{
         AdvancedSubscriber, AdvancedSubscriberBuilder, HistoryConfig, Miss, RecoveryConfig,
         SampleMissHandlerUndeclaration, SampleMissListener, SampleMissListenerBuilder,
     },
+    cache_store::{CacheStore, FileCacheStore},
+    lock::{DistributedLock, LockEvent, LockGuard, LockHolder},
     publication_cache::{PublicationCache, PublicationCacheBuilder},
     publisher_ext::AdvancedPublisherBuilderExt,
     querying_subscriber::{
         ExtractSample, FetchingSubscriber, FetchingSubscriberBuilder, KeySpace, LivelinessSpace,
         QueryingSubscriberBuilder, UserSpace,
     },
+    rpc::{Method, ResponseStream, RpcClient, RpcContext, RpcError, RpcServer, RpcStatus},
+    schema::{Schema, ZSchema, ZSchemaError},
     session_ext::SessionExt,
     subscriber_ext::{AdvancedSubscriberBuilderExt, SubscriberBuilderExt, SubscriberForward},
 };
@@ -0,0 +1,608 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    collections::HashSet,
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use flume::{Receiver, Sender};
+use tokio::sync::Notify;
+use zenoh::{
+    internal::{bail, zlock},
+    key_expr::{keyexpr, KeyExpr, OwnedKeyExpr},
+    liveliness::LivelinessToken,
+    pubsub::Subscriber,
+    query::{ConsolidationMode, Query, QueryTarget, Queryable},
+    sample::{Sample, SampleKind},
+    Error as ZError, Result as ZResult, Session, Wait,
+};
+
+use crate::{z_deserialize, z_serialize};
+
+const LOCK_PREFIX: &str = "zenoh/ext/net/lock";
+const PARTICIPANT_INFIX: &str = "participant";
+const HOLDER_INFIX: &str = "holder";
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The holder of a [`DistributedLock`], along with its fencing token.
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LockHolder {
+    id: OwnedKeyExpr,
+    fence: u64,
+}
+
+#[zenoh_macros::unstable]
+impl LockHolder {
+    /// Returns the identifier of the [`DistributedLock`] holding the lock.
+    #[zenoh_macros::unstable]
+    pub fn id(&self) -> &keyexpr {
+        &self.id
+    }
+
+    /// Returns the fencing token of the holder.
+    #[zenoh_macros::unstable]
+    pub fn fence(&self) -> u64 {
+        self.fence
+    }
+}
+
+/// Events notified to the observers of a [`DistributedLock`].
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub enum LockEvent {
+    /// The lock has been acquired.
+    Acquired(LockHolder),
+    /// The lock has been released, either explicitly or because the session of its holder
+    /// was lost.
+    Released(LockHolder),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Idle,
+    // Waiting for the permission of the other participants with the given ticket
+    Wanting(u64),
+    Held,
+}
+
+struct LockInner {
+    // Logical clock ordering the acquisition requests
+    lamport: u64,
+    max_fence: u64,
+    status: Status,
+    // Requests of the other participants to be granted once the lock is released
+    deferred: Vec<Request>,
+    participants: HashSet<OwnedKeyExpr>,
+    // Participants whose permission is requested by the ongoing acquisition
+    requested: HashSet<OwnedKeyExpr>,
+    holder: Option<LockHolder>,
+}
+
+struct Request {
+    query: Query,
+    ticket: u64,
+    id: OwnedKeyExpr,
+}
+
+impl Request {
+    /// Returns whether the request has priority over the acquisition with the given ticket.
+    fn precedes(&self, ticket: u64, id: &keyexpr) -> bool {
+        (self.ticket, self.id.as_str()) < (ticket, id.as_str())
+    }
+}
+
+struct LockState {
+    session: Session,
+    name: OwnedKeyExpr,
+    id: OwnedKeyExpr,
+    key_expr: KeyExpr<'static>,
+    inner: Mutex<LockInner>,
+    changed: Notify,
+    events_tx: Mutex<Option<Sender<LockEvent>>>,
+}
+
+impl LockState {
+    fn prefix(&self, infix: &str) -> String {
+        format!("{LOCK_PREFIX}/{}/{infix}", self.name)
+    }
+
+    fn notify(&self, evt: LockEvent) {
+        if let Some(tx) = &*zlock!(self.events_tx) {
+            // The user may have dropped the receiver
+            let _ = tx.send(evt);
+        }
+    }
+
+    /// Replies to a query with the local clock and fencing token.
+    fn grant(&self, query: Query, lamport: u64, max_fence: u64) {
+        if let Err(e) = query
+            .reply(self.key_expr.clone(), z_serialize(&(lamport, max_fence)))
+            .wait()
+        {
+            tracing::warn!(
+                "Unable to reply to lock request on {}: {}",
+                self.key_expr,
+                e
+            );
+        }
+    }
+
+    fn handle_query(&self, query: Query) {
+        let mut inner = zlock!(self.inner);
+        // Queries without payload only retrieve the clock and fencing token
+        let Some(payload) = query.payload() else {
+            let (lamport, max_fence) = (inner.lamport, inner.max_fence);
+            drop(inner);
+            return self.grant(query, lamport, max_fence);
+        };
+        let (ticket, id) = match z_deserialize::<(u64, String)>(payload)
+            .ok()
+            .and_then(|(ticket, id)| Some((ticket, OwnedKeyExpr::new(id).ok()?)))
+        {
+            Some(request) => request,
+            None => {
+                tracing::warn!("Received invalid lock request on {}", self.key_expr);
+                return;
+            }
+        };
+        inner.lamport = inner.lamport.max(ticket);
+        let request = Request { query, ticket, id };
+        // A participant which joined during the acquisition is only granted the lock once its
+        // permission is requested as well, otherwise both could acquire the lock.
+        let granted = match inner.status {
+            Status::Idle => true,
+            Status::Wanting(own) => {
+                inner.requested.contains(&request.id) && request.precedes(own, &self.id)
+            }
+            Status::Held => false,
+        };
+        if granted {
+            let (lamport, max_fence) = (inner.lamport, inner.max_fence);
+            drop(inner);
+            self.grant(request.query, lamport, max_fence);
+        } else {
+            tracing::debug!("Defer lock request of {} on {}", request.id, self.name);
+            inner.deferred.push(request);
+        }
+    }
+
+    fn handle_token(&self, sample: Sample) {
+        let key_expr = sample.key_expr().as_str();
+        if let Some(id) = key_expr
+            .strip_prefix(&self.prefix(PARTICIPANT_INFIX))
+            .and_then(|id| id.strip_prefix('/'))
+        {
+            let Ok(id) = OwnedKeyExpr::new(id) else {
+                return;
+            };
+            if id == self.id {
+                return;
+            }
+            let mut inner = zlock!(self.inner);
+            match sample.kind() {
+                SampleKind::Put => inner.participants.insert(id),
+                SampleKind::Delete => inner.participants.remove(&id),
+            };
+            drop(inner);
+            self.changed.notify_waiters();
+        } else if let Some(holder) = key_expr
+            .strip_prefix(&self.prefix(HOLDER_INFIX))
+            .and_then(|holder| holder.strip_prefix('/'))
+            .and_then(|holder| holder.split_once('/'))
+            .and_then(|(fence, id)| {
+                Some(LockHolder {
+                    id: OwnedKeyExpr::new(id).ok()?,
+                    fence: fence.parse().ok()?,
+                })
+            })
+        {
+            let mut inner = zlock!(self.inner);
+            match sample.kind() {
+                SampleKind::Put => {
+                    inner.max_fence = inner.max_fence.max(holder.fence);
+                    inner.holder = Some(holder.clone());
+                    drop(inner);
+                    self.notify(LockEvent::Acquired(holder));
+                }
+                SampleKind::Delete => {
+                    if inner.holder.as_ref() == Some(&holder) {
+                        inner.holder = None;
+                    }
+                    drop(inner);
+                    self.changed.notify_waiters();
+                    self.notify(LockEvent::Released(holder));
+                }
+            }
+        } else {
+            tracing::warn!("Received invalid lock token: {}", key_expr);
+        }
+    }
+
+    /// Updates the local clock and fencing token with the ones replied by another participant.
+    fn handle_reply(&self, sample: &Sample) -> bool {
+        match z_deserialize::<(u64, u64)>(sample.payload()) {
+            Ok((lamport, max_fence)) => {
+                let mut inner = zlock!(self.inner);
+                inner.lamport = inner.lamport.max(lamport);
+                inner.max_fence = inner.max_fence.max(max_fence);
+                true
+            }
+            Err(_) => {
+                tracing::warn!("Received invalid lock reply on {}", sample.key_expr());
+                false
+            }
+        }
+    }
+
+    fn has_left(&self, participant: &keyexpr) -> bool {
+        let inner = zlock!(self.inner);
+        !inner.participants.contains(participant)
+            && inner
+                .holder
+                .as_ref()
+                .map_or(true, |holder| holder.id.as_str() != participant.as_str())
+    }
+
+    /// Waits for the permission of a participant, which is implicitly given when it leaves
+    /// without holding the lock.
+    async fn request(&self, participant: &keyexpr, ticket: u64, deadline: Instant) -> ZResult<()> {
+        let key_expr = format!("{}/{}", self.prefix(PARTICIPANT_INFIX), participant);
+        loop {
+            let changed = self.changed.notified();
+            if self.has_left(participant) {
+                return Ok(());
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                bail!("Timeout while acquiring lock {}", self.name);
+            }
+            let replies = self
+                .session
+                .get(&key_expr)
+                .payload(z_serialize(&(ticket, self.id.as_str())))
+                .consolidation(ConsolidationMode::None)
+                .timeout(remaining)
+                .await?;
+            while let Ok(reply) = replies.recv_async().await {
+                if let Ok(sample) = reply.result() {
+                    if self.handle_reply(sample) {
+                        return Ok(());
+                    }
+                }
+            }
+            // The participant left or the request timed out
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let _ = tokio::time::timeout(remaining, changed).await;
+        }
+    }
+
+    /// Releases the lock, or gives up acquiring it, granting the deferred requests.
+    fn release(&self) {
+        let mut inner = zlock!(self.inner);
+        inner.status = Status::Idle;
+        inner.requested.clear();
+        let deferred = std::mem::take(&mut inner.deferred);
+        let (lamport, max_fence) = (inner.lamport, inner.max_fence);
+        drop(inner);
+        for request in deferred {
+            self.grant(request.query, lamport, max_fence);
+        }
+    }
+
+    /// Adds the participants which joined since the last call to the ones whose permission is
+    /// requested, granting their deferred requests having priority over the acquisition.
+    ///
+    /// Returns the added participants, or breaks with the fencing token of the acquisition if
+    /// there is none, in which case the lock is held.
+    fn extend_acquisition(&self, ticket: u64) -> ControlFlow<u64, Vec<OwnedKeyExpr>> {
+        let mut guard = zlock!(self.inner);
+        let inner = &mut *guard;
+        let joined: Vec<_> = inner
+            .participants
+            .difference(&inner.requested)
+            .cloned()
+            .collect();
+        if joined.is_empty() {
+            inner.max_fence += 1;
+            inner.status = Status::Held;
+            return ControlFlow::Break(inner.max_fence);
+        }
+        inner.requested.extend(joined.iter().cloned());
+        let (granted, deferred): (Vec<_>, _) = std::mem::take(&mut inner.deferred)
+            .into_iter()
+            .partition(|request| {
+                joined.contains(&request.id) && request.precedes(ticket, &self.id)
+            });
+        inner.deferred = deferred;
+        let (lamport, max_fence) = (inner.lamport, inner.max_fence);
+        drop(guard);
+        for request in granted {
+            self.grant(request.query, lamport, max_fence);
+        }
+        ControlFlow::Continue(joined)
+    }
+}
+
+/// Gives up acquiring the lock if the acquisition is cancelled or fails.
+struct Acquisition<'a> {
+    state: &'a LockState,
+    done: bool,
+}
+
+impl Drop for Acquisition<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.state.release();
+        }
+    }
+}
+
+/// A lock providing mutual exclusion between the [`DistributedLock`]s declared with the same
+/// name on any session of the zenoh network.
+///
+/// Each `DistributedLock` declares a liveliness token and a queryable. Acquiring the lock
+/// requires the permission of all the other participants, which is deferred by the holder of
+/// the lock until it releases it. The lock is released when its [`LockGuard`] is dropped, or
+/// when the session of its holder is lost.
+///
+/// Each acquisition of the lock is given a fencing token, greater than the ones of all the
+/// previous acquisitions known to the participants. The resources protected by the lock can
+/// reject the requests carrying a smaller fencing token than the last one they received, e.g.
+/// the ones sent by a holder whose session was considered lost by the others.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+///
+/// use zenoh_ext::DistributedLock;
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let lock = DistributedLock::new(&session, "my/lock").await.unwrap();
+/// let guard = lock.acquire(Duration::from_secs(10)).await.unwrap();
+/// session
+///     .put("my/shared/config", "value")
+///     .attachment(guard.fence().to_le_bytes().to_vec())
+///     .await
+///     .unwrap();
+/// drop(guard);
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct DistributedLock {
+    state: Arc<LockState>,
+    _token: LivelinessToken,
+    _queryable: Queryable<()>,
+    _subscriber: Subscriber<()>,
+}
+
+#[zenoh_macros::unstable]
+impl DistributedLock {
+    /// Declares a participant to the lock with the given name.
+    #[zenoh_macros::unstable]
+    pub async fn new<T>(session: &Session, name: T) -> ZResult<Self>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let name: OwnedKeyExpr = name.try_into().map_err(|e| e.into())?;
+        if name.is_wild() {
+            bail!("Lock name is not allowed to contain wildcards: {}", name);
+        }
+        let id = OwnedKeyExpr::try_from(format!(
+            "{}-{}",
+            session.zid(),
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        ))?;
+        let key_expr = KeyExpr::try_from(format!("{LOCK_PREFIX}/{name}/{PARTICIPANT_INFIX}/{id}"))?;
+        let state = Arc::new(LockState {
+            session: session.clone(),
+            name,
+            id,
+            key_expr,
+            inner: Mutex::new(LockInner {
+                lamport: 0,
+                max_fence: 0,
+                status: Status::Idle,
+                deferred: Vec::new(),
+                participants: HashSet::new(),
+                requested: HashSet::new(),
+                holder: None,
+            }),
+            changed: Notify::new(),
+            events_tx: Mutex::new(None),
+        });
+
+        let queryable = session
+            .declare_queryable(state.key_expr.clone())
+            .callback({
+                let state = state.clone();
+                move |query| state.handle_query(query)
+            })
+            .await?;
+        // The subscriber is declared before retrieving the current tokens so that no change
+        // is missed in between.
+        let tokens = format!("{LOCK_PREFIX}/{}/**", state.name);
+        let subscriber = session
+            .liveliness()
+            .declare_subscriber(&tokens)
+            .callback({
+                let state = state.clone();
+                move |sample| state.handle_token(sample)
+            })
+            .await?;
+        let token = session
+            .liveliness()
+            .declare_token(state.key_expr.clone())
+            .await?;
+        let replies = session
+            .liveliness()
+            .get(&tokens)
+            .timeout(QUERY_TIMEOUT)
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.into_result() {
+                state.handle_token(sample);
+            }
+        }
+        // Retrieve the clock and fencing token of the other participants
+        let replies = session
+            .get(format!("{}/*", state.prefix(PARTICIPANT_INFIX)))
+            .target(QueryTarget::All)
+            .consolidation(ConsolidationMode::None)
+            .timeout(QUERY_TIMEOUT)
+            .await?;
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.result() {
+                state.handle_reply(sample);
+            }
+        }
+
+        Ok(DistributedLock {
+            state,
+            _token: token,
+            _queryable: queryable,
+            _subscriber: subscriber,
+        })
+    }
+
+    /// Returns the name of the lock.
+    #[zenoh_macros::unstable]
+    pub fn name(&self) -> &keyexpr {
+        &self.state.name
+    }
+
+    /// Returns the identifier of this participant to the lock.
+    #[zenoh_macros::unstable]
+    pub fn id(&self) -> &keyexpr {
+        &self.state.id
+    }
+
+    /// Acquires the lock, waiting at most `timeout` for its holder to release it.
+    ///
+    /// A participant can only hold the lock once at a time: this fails if the lock is already
+    /// held or being acquired through this `DistributedLock`.
+    #[zenoh_macros::unstable]
+    pub async fn acquire(&self, timeout: Duration) -> ZResult<LockGuard> {
+        let deadline = Instant::now() + timeout;
+        let ticket = {
+            let mut inner = zlock!(self.state.inner);
+            if inner.status != Status::Idle {
+                bail!("Lock {} is already held or being acquired", self.state.name);
+            }
+            inner.lamport += 1;
+            inner.status = Status::Wanting(inner.lamport);
+            inner.lamport
+        };
+        let mut acquisition = Acquisition {
+            state: &self.state,
+            done: false,
+        };
+        // The participants which joined while waiting for the permission of the others must
+        // give their permission too.
+        let fence = loop {
+            match self.state.extend_acquisition(ticket) {
+                ControlFlow::Continue(participants) => {
+                    futures::future::try_join_all(
+                        participants
+                            .iter()
+                            .map(|participant| self.state.request(participant, ticket, deadline)),
+                    )
+                    .await?;
+                }
+                ControlFlow::Break(fence) => break fence,
+            }
+        };
+        let token = self
+            .state
+            .session
+            .liveliness()
+            .declare_token(format!(
+                "{}/{}/{}",
+                self.state.prefix(HOLDER_INFIX),
+                fence,
+                self.state.id
+            ))
+            .await?;
+        tracing::debug!("Acquired lock {} with fence {}", self.state.name, fence);
+        acquisition.done = true;
+        Ok(LockGuard {
+            state: self.state.clone(),
+            fence,
+            token: Some(token),
+        })
+    }
+
+    /// Returns the current holder of the lock, if any, as observed by this participant.
+    #[zenoh_macros::unstable]
+    pub fn holder(&self) -> Option<LockHolder> {
+        zlock!(self.state.inner).holder.clone()
+    }
+
+    /// Returns a receiver notified of the acquisitions and releases of the lock.
+    /// Notice that there can be a single subscription at the time, each call to subscribe
+    /// will cancel the previous subscription.
+    #[zenoh_macros::unstable]
+    pub fn subscribe(&self) -> Receiver<LockEvent> {
+        let (tx, rx) = flume::unbounded();
+        *zlock!(self.state.events_tx) = Some(tx);
+        rx
+    }
+}
+
+/// A [`DistributedLock`] acquisition, which releases the lock when dropped.
+#[zenoh_macros::unstable]
+pub struct LockGuard {
+    state: Arc<LockState>,
+    fence: u64,
+    token: Option<LivelinessToken>,
+}
+
+#[zenoh_macros::unstable]
+impl LockGuard {
+    /// Returns the fencing token of this acquisition.
+    #[zenoh_macros::unstable]
+    pub fn fence(&self) -> u64 {
+        self.fence
+    }
+
+    /// Releases the lock.
+    #[zenoh_macros::unstable]
+    pub fn release(self) {
+        drop(self)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        tracing::debug!(
+            "Released lock {} with fence {}",
+            self.state.name,
+            self.fence
+        );
+        // Notify the observers before granting the deferred requests
+        drop(self.token.take());
+        self.state.release();
+    }
+}
@@ -11,6 +11,8 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use std::time::Duration;
+
 use zenoh::pubsub::PublisherBuilder;
 
 use crate::{advanced_cache::CacheConfig, AdvancedPublisherBuilder};
@@ -29,6 +31,13 @@ pub trait AdvancedPublisherBuilderExt<'a, 'b, 'c> {
     #[zenoh_macros::unstable]
     fn sample_miss_detection(self) -> AdvancedPublisherBuilder<'a, 'b, 'c>;
 
+    /// Periodically announce the sequence number of the last published sample, allowing matching
+    /// [`AdvancedSubscribers`](crate::AdvancedSubscriber) to promptly detect the loss of the last samples.
+    ///
+    /// This implies [`sample_miss_detection`](crate::AdvancedPublisherBuilder::sample_miss_detection).
+    #[zenoh_macros::unstable]
+    fn heartbeat(self, period: Duration) -> AdvancedPublisherBuilder<'a, 'b, 'c>;
+
     /// Allow this publisher to be detected by [`AdvancedSubscribers`](crate::AdvancedSubscriber).
     ///
     /// This allows [`AdvancedSubscribers`](crate::AdvancedSubscriber) to retrieve the local history.
@@ -57,6 +66,15 @@ impl<'a, 'b, 'c> AdvancedPublisherBuilderExt<'a, 'b, 'c> for PublisherBuilder<'a
         AdvancedPublisherBuilder::new(self).sample_miss_detection()
     }
 
+    /// Periodically announce the sequence number of the last published sample, allowing matching
+    /// [`AdvancedSubscribers`](crate::AdvancedSubscriber) to promptly detect the loss of the last samples.
+    ///
+    /// This implies [`sample_miss_detection`](crate::AdvancedPublisherBuilder::sample_miss_detection).
+    #[zenoh_macros::unstable]
+    fn heartbeat(self, period: Duration) -> AdvancedPublisherBuilder<'a, 'b, 'c> {
+        AdvancedPublisherBuilder::new(self).heartbeat(period)
+    }
+
     /// Allow this publisher to be detected by [`AdvancedSubscribers`](crate::AdvancedSubscriber).
     ///
     /// This allows [`AdvancedSubscribers`](crate::AdvancedSubscriber) to retrieve the local history.
@@ -0,0 +1,647 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use futures::{Stream, StreamExt};
+use tokio::sync::Notify;
+use zenoh::{
+    bytes::Encoding,
+    handlers::FifoChannelHandler,
+    internal::{bail, runtime::ZRuntime, zlock},
+    key_expr::{keyexpr, KeyExpr, OwnedKeyExpr},
+    pubsub::Subscriber,
+    query::{ConsolidationMode, Querier, Query, Queryable, Reply, ReplyError},
+    Error as ZError, Result as ZResult, Session, Wait,
+};
+
+use crate::{z_deserialize, z_serialize, Deserialize, Serialize};
+
+/// The chunk of the key expression on which the cancellations of a service are published.
+const CANCEL_SUFFIX: &str = "@cancel";
+/// The selector parameter carrying the identifier of a request.
+const REQUEST_ID_PARAM: &str = "_rid";
+/// How long a cancellation received before its request is remembered.
+const EARLY_CANCEL_TTL: Duration = Duration::from_secs(10);
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A method of an RPC service.
+///
+/// A method is called by an [`RpcClient`] and served by an [`RpcServer`] on the key expression
+/// `<service>/<NAME>`, its requests and responses being serialized with [`z_serialize`].
+///
+/// # Examples
+/// ```
+/// use zenoh_ext::Method;
+///
+/// struct Add;
+///
+/// impl Method for Add {
+///     const NAME: &'static str = "add";
+///     type Request = (i64, i64);
+///     type Response = i64;
+/// }
+/// ```
+#[zenoh_macros::unstable]
+pub trait Method: Send + Sync + 'static {
+    /// The name of the method, which must be a valid key expression chunk.
+    const NAME: &'static str;
+    /// The type of the requests.
+    type Request: Serialize + Deserialize + Send + 'static;
+    /// The type of the responses.
+    type Response: Serialize + Deserialize + Send + 'static;
+}
+
+/// An error returned by an RPC handler, which is forwarded to the client.
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcStatus {
+    code: u32,
+    message: String,
+}
+
+#[zenoh_macros::unstable]
+impl RpcStatus {
+    /// The code of the status returned by the server when it fails to decode a request, or when
+    /// the request has no identifier or the identifier of a request in progress.
+    #[zenoh_macros::unstable]
+    pub const INVALID_REQUEST: u32 = u32::MAX;
+
+    /// Creates a status with an application defined code and a message.
+    #[zenoh_macros::unstable]
+    pub fn new<T: Into<String>>(code: u32, message: T) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the code of the status.
+    #[zenoh_macros::unstable]
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+
+    /// Returns the message of the status.
+    #[zenoh_macros::unstable]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Display for RpcStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "status {}: {}", self.code, self.message)
+    }
+}
+
+/// The error of an RPC call.
+#[zenoh_macros::unstable]
+#[derive(Debug)]
+pub enum RpcError {
+    /// The handler of the server returned an error.
+    Status(RpcStatus),
+    /// No response was received before the timeout of the client.
+    Timeout,
+    /// No server replied to the request.
+    NoReply,
+    /// A response could not be decoded.
+    Decode(String),
+    /// The request could not be sent.
+    Zenoh(ZError),
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::Status(status) => write!(f, "RPC failed with {status}"),
+            RpcError::Timeout => write!(f, "RPC timed out"),
+            RpcError::NoReply => write!(f, "RPC got no reply"),
+            RpcError::Decode(e) => write!(f, "RPC reply could not be decoded: {e}"),
+            RpcError::Zenoh(e) => write!(f, "RPC could not be sent: {e}"),
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl std::error::Error for RpcError {}
+
+#[zenoh_macros::unstable]
+impl From<ReplyError> for RpcError {
+    fn from(e: ReplyError) -> Self {
+        if *e.encoding() == Encoding::ZENOH_SERIALIZED {
+            match z_deserialize::<(u32, String)>(e.payload()) {
+                Ok((code, message)) => RpcError::Status(RpcStatus { code, message }),
+                Err(_) => RpcError::Decode("invalid status".into()),
+            }
+        } else {
+            match e.payload().try_to_string() {
+                Ok(s) if s == "Timeout" => RpcError::Timeout,
+                Ok(s) => RpcError::Decode(s.into_owned()),
+                Err(e) => RpcError::Decode(e.to_string()),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Cancellation {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl Cancellation {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.notify.notify_waiters();
+    }
+}
+
+/// The context of a request served by an [`RpcServer`].
+#[zenoh_macros::unstable]
+#[derive(Clone)]
+pub struct RpcContext {
+    request_id: String,
+    cancellation: Arc<Cancellation>,
+}
+
+#[zenoh_macros::unstable]
+impl RpcContext {
+    /// Returns the identifier of the request, unique among the requests of a client.
+    #[zenoh_macros::unstable]
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// Returns `true` if the client cancelled the request.
+    #[zenoh_macros::unstable]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the client to cancel the request.
+    #[zenoh_macros::unstable]
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.cancellation.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[zenoh_macros::unstable]
+impl fmt::Debug for RpcContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RpcContext")
+            .field("request_id", &self.request_id)
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+#[derive(Default)]
+struct InflightRequests {
+    requests: HashMap<String, Arc<Cancellation>>,
+    // The cancellations received before their request, which may still be on its way
+    cancelled: HashMap<String, Instant>,
+}
+
+type Inflight = Arc<Mutex<InflightRequests>>;
+
+/// The server of an RPC service, dispatching the requests of each [`Method`] to its handler.
+///
+/// Each request is handled in its own task, which is aborted if the client cancels it.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh_ext::{Method, RpcServer, RpcStatus};
+///
+/// struct Div;
+///
+/// impl Method for Div {
+///     const NAME: &'static str = "div";
+///     type Request = (i64, i64);
+///     type Response = i64;
+/// }
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let mut server = RpcServer::new(&session, "calculator").await.unwrap();
+/// server
+///     .unary::<Div, _, _>(|(a, b), _ctx| async move {
+///         a.checked_div(b)
+///             .ok_or_else(|| RpcStatus::new(1, "division by zero"))
+///     })
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct RpcServer {
+    session: Session,
+    service: OwnedKeyExpr,
+    inflight: Inflight,
+    queryables: Vec<Queryable<()>>,
+    _cancel_subscriber: Subscriber<()>,
+}
+
+#[zenoh_macros::unstable]
+impl RpcServer {
+    /// Creates the server of the service with the given name.
+    #[zenoh_macros::unstable]
+    pub async fn new<T>(session: &Session, service: T) -> ZResult<Self>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let service = service_key_expr(service)?;
+        let inflight: Inflight = Default::default();
+        let cancel_subscriber = session
+            .declare_subscriber(format!("{service}/{CANCEL_SUFFIX}"))
+            .callback({
+                let inflight = inflight.clone();
+                move |sample| {
+                    let Ok(request_id) = z_deserialize::<String>(sample.payload()) else {
+                        tracing::warn!("Received invalid cancellation on {}", sample.key_expr());
+                        return;
+                    };
+                    let mut inflight = zlock!(inflight);
+                    if let Some(cancellation) = inflight.requests.get(&request_id) {
+                        tracing::debug!("Cancel request {}", request_id);
+                        cancellation.cancel();
+                    } else {
+                        inflight
+                            .cancelled
+                            .retain(|_, received| received.elapsed() < EARLY_CANCEL_TTL);
+                        inflight.cancelled.insert(request_id, Instant::now());
+                    }
+                }
+            })
+            .await?;
+        Ok(RpcServer {
+            session: session.clone(),
+            service,
+            inflight,
+            queryables: Vec::new(),
+            _cancel_subscriber: cancel_subscriber,
+        })
+    }
+
+    /// Returns the name of the service.
+    #[zenoh_macros::unstable]
+    pub fn service(&self) -> &keyexpr {
+        &self.service
+    }
+
+    /// Serves a method replying a single response to each request.
+    #[zenoh_macros::unstable]
+    pub async fn unary<M, F, Fut>(&mut self, handler: F) -> ZResult<()>
+    where
+        M: Method,
+        F: Fn(M::Request, RpcContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<M::Response, RpcStatus>> + Send + 'static,
+    {
+        self.serve::<M, _, _>(move |request, ctx| futures::stream::once(handler(request, ctx)))
+            .await
+    }
+
+    /// Serves a method replying a stream of responses to each request.
+    ///
+    /// The stream ends after the first error it returns.
+    #[zenoh_macros::unstable]
+    pub async fn streaming<M, F, S>(&mut self, handler: F) -> ZResult<()>
+    where
+        M: Method,
+        F: Fn(M::Request, RpcContext) -> S + Send + Sync + 'static,
+        S: Stream<Item = Result<M::Response, RpcStatus>> + Send + 'static,
+    {
+        self.serve::<M, _, _>(handler).await
+    }
+
+    async fn serve<M, F, S>(&mut self, handler: F) -> ZResult<()>
+    where
+        M: Method,
+        F: Fn(M::Request, RpcContext) -> S + Send + Sync + 'static,
+        S: Stream<Item = Result<M::Response, RpcStatus>> + Send + 'static,
+    {
+        let key_expr = method_key_expr::<M>(&self.service)?;
+        let inflight = self.inflight.clone();
+        let queryable = self
+            .session
+            .declare_queryable(key_expr.clone())
+            .complete(true)
+            .callback(move |query| {
+                let Some(request_id) = query
+                    .parameters()
+                    .get(REQUEST_ID_PARAM)
+                    .filter(|request_id| !request_id.is_empty())
+                    .map(str::to_string)
+                else {
+                    tracing::warn!("Received request without id on {}", key_expr);
+                    let status = RpcStatus::new(RpcStatus::INVALID_REQUEST, "missing request id");
+                    reply_status(&query, status);
+                    return;
+                };
+                let request = match query.payload().map(z_deserialize::<M::Request>) {
+                    Some(Ok(request)) => request,
+                    _ => {
+                        tracing::warn!("Received invalid request {} on {}", request_id, key_expr);
+                        let status =
+                            RpcStatus::new(RpcStatus::INVALID_REQUEST, "invalid request payload");
+                        reply_status(&query, status);
+                        return;
+                    }
+                };
+                let ctx = RpcContext {
+                    request_id: request_id.clone(),
+                    cancellation: Default::default(),
+                };
+                {
+                    let mut inflight = zlock!(inflight);
+                    if inflight.cancelled.remove(&request_id).is_some() {
+                        tracing::debug!("Request {} on {} cancelled", request_id, key_expr);
+                        return;
+                    }
+                    match inflight.requests.entry(request_id.clone()) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(ctx.cancellation.clone());
+                        }
+                        Entry::Occupied(_) => {
+                            drop(inflight);
+                            tracing::warn!(
+                                "Received duplicate request {} on {}",
+                                request_id,
+                                key_expr
+                            );
+                            let status =
+                                RpcStatus::new(RpcStatus::INVALID_REQUEST, "duplicate request id");
+                            reply_status(&query, status);
+                            return;
+                        }
+                    }
+                }
+                let responses = handler(request, ctx.clone());
+                let key_expr = key_expr.clone();
+                let inflight = inflight.clone();
+                ZRuntime::Application.spawn(async move {
+                    tokio::select! {
+                        _ = reply_responses(&query, &key_expr, responses) => {}
+                        _ = ctx.cancelled() => {
+                            tracing::debug!("Request {} on {} cancelled", request_id, key_expr);
+                        }
+                    }
+                    zlock!(inflight).requests.remove(&request_id);
+                });
+            })
+            .await?;
+        self.queryables.push(queryable);
+        Ok(())
+    }
+}
+
+async fn reply_responses<T, S>(query: &Query, key_expr: &KeyExpr<'static>, responses: S)
+where
+    T: Serialize,
+    S: Stream<Item = Result<T, RpcStatus>>,
+{
+    futures::pin_mut!(responses);
+    while let Some(response) = responses.next().await {
+        match response {
+            Ok(response) => {
+                if let Err(e) = query.reply(key_expr.clone(), z_serialize(&response)).await {
+                    tracing::warn!("Unable to reply to request on {}: {}", key_expr, e);
+                    return;
+                }
+            }
+            Err(status) => {
+                reply_status(query, status);
+                return;
+            }
+        }
+    }
+}
+
+fn reply_status(query: &Query, status: RpcStatus) {
+    if let Err(e) = query
+        .reply_err(z_serialize(&(status.code, status.message)))
+        .encoding(Encoding::ZENOH_SERIALIZED)
+        .wait()
+    {
+        tracing::warn!("Unable to reply error to request: {}", e);
+    }
+}
+
+fn service_key_expr<T>(service: T) -> ZResult<OwnedKeyExpr>
+where
+    T: TryInto<OwnedKeyExpr>,
+    <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+{
+    let service: OwnedKeyExpr = service.try_into().map_err(|e| e.into())?;
+    if service.is_wild() {
+        bail!(
+            "Service name is not allowed to contain wildcards: {}",
+            service
+        );
+    }
+    Ok(service)
+}
+
+fn method_key_expr<M: Method>(service: &keyexpr) -> ZResult<KeyExpr<'static>> {
+    let method = keyexpr::new(M::NAME)?;
+    if method.is_wild() || method.as_str().contains('/') {
+        bail!(
+            "Method name must be a single chunk without wildcards: {}",
+            method
+        );
+    }
+    Ok((service / method).into())
+}
+
+/// A client stub calling a [`Method`] of an RPC service.
+///
+/// # Examples
+/// ```no_run
+/// # #[tokio::main]
+/// # async fn main() {
+/// use std::time::Duration;
+///
+/// use zenoh_ext::{Method, RpcClient};
+///
+/// struct Div;
+///
+/// impl Method for Div {
+///     const NAME: &'static str = "div";
+///     type Request = (i64, i64);
+///     type Response = i64;
+/// }
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let client = RpcClient::<Div>::new(&session, "calculator", Duration::from_secs(5))
+///     .await
+///     .unwrap();
+/// match client.call(&(7, 2)).await {
+///     Ok(quotient) => println!("7 / 2 = {quotient}"),
+///     Err(e) => println!("{e}"),
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+pub struct RpcClient<M: Method> {
+    session: Session,
+    querier: Querier<'static>,
+    cancel_key_expr: KeyExpr<'static>,
+    _method: PhantomData<M>,
+}
+
+#[zenoh_macros::unstable]
+impl<M: Method> RpcClient<M> {
+    /// Creates a client of the method of the service with the given name, whose calls time out
+    /// after `timeout`.
+    #[zenoh_macros::unstable]
+    pub async fn new<T>(session: &Session, service: T, timeout: Duration) -> ZResult<Self>
+    where
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let service = service_key_expr(service)?;
+        let querier = session
+            .declare_querier(method_key_expr::<M>(&service)?)
+            .consolidation(ConsolidationMode::None)
+            .timeout(timeout)
+            .await?;
+        Ok(RpcClient {
+            session: session.clone(),
+            querier,
+            cancel_key_expr: KeyExpr::try_from(format!("{service}/{CANCEL_SUFFIX}"))?,
+            _method: PhantomData,
+        })
+    }
+
+    /// Calls the method, returning the first response of the server.
+    #[zenoh_macros::unstable]
+    pub async fn call(&self, request: &M::Request) -> Result<M::Response, RpcError> {
+        let mut responses = self.call_streaming(request).await?;
+        let response = responses.next().await.unwrap_or(Err(RpcError::NoReply));
+        // The remaining responses of a unary method are not worth a cancellation
+        responses.finished = true;
+        response
+    }
+
+    /// Calls the method, returning the stream of responses of the server.
+    ///
+    /// The call is cancelled if the stream is dropped before its end.
+    #[zenoh_macros::unstable]
+    pub async fn call_streaming(
+        &self,
+        request: &M::Request,
+    ) -> Result<ResponseStream<M>, RpcError> {
+        let request_id = format!(
+            "{}-{}",
+            self.session.zid(),
+            NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let replies = self
+            .querier
+            .get()
+            .parameters(format!("{REQUEST_ID_PARAM}={request_id}"))
+            .payload(z_serialize(request))
+            .await
+            .map_err(RpcError::Zenoh)?;
+        Ok(ResponseStream {
+            session: self.session.clone(),
+            cancel_key_expr: self.cancel_key_expr.clone(),
+            request_id,
+            replies,
+            finished: false,
+            _method: PhantomData,
+        })
+    }
+}
+
+/// The stream of responses of an RPC call.
+#[zenoh_macros::unstable]
+pub struct ResponseStream<M: Method> {
+    session: Session,
+    cancel_key_expr: KeyExpr<'static>,
+    request_id: String,
+    replies: FifoChannelHandler<Reply>,
+    finished: bool,
+    _method: PhantomData<M>,
+}
+
+#[zenoh_macros::unstable]
+impl<M: Method> ResponseStream<M> {
+    /// Returns the identifier of the request.
+    #[zenoh_macros::unstable]
+    pub fn request_id(&self) -> &str {
+        &self.request_id
+    }
+
+    /// Returns the next response, or `None` at the end of the stream.
+    #[zenoh_macros::unstable]
+    pub async fn next(&mut self) -> Option<Result<M::Response, RpcError>> {
+        if self.finished {
+            return None;
+        }
+        match self.replies.recv_async().await {
+            Ok(reply) => Some(match reply.into_result() {
+                Ok(sample) => z_deserialize::<M::Response>(sample.payload())
+                    .map_err(|e| RpcError::Decode(e.to_string())),
+                Err(e) => Err(e.into()),
+            }),
+            Err(_) => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+
+    /// Cancels the call, aborting its handler on the server.
+    #[zenoh_macros::unstable]
+    pub fn cancel(self) {
+        drop(self)
+    }
+}
+
+#[zenoh_macros::unstable]
+impl<M: Method> Drop for ResponseStream<M> {
+    fn drop(&mut self) {
+        if !self.finished {
+            tracing::debug!("Cancel request {}", self.request_id);
+            if let Err(e) = self
+                .session
+                .put(&self.cancel_key_expr, z_serialize(&self.request_id))
+                .wait()
+            {
+                tracing::warn!("Unable to cancel request {}: {}", self.request_id, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,729 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use zenoh::bytes::{Encoding, ZBytes};
+
+use crate::serialization::{VarInt, ZDeserializer};
+
+/// Description of the wire format of a type serialized according to the
+/// [Zenoh serialization format][1].
+///
+/// A schema is usually obtained with [`Schema::schema`]. Its textual representation, given by
+/// its [`Display`](fmt::Display) implementation and parsed by its [`FromStr`] implementation,
+/// can be published alongside the data as the schema of a [`Encoding::ZENOH_SERIALIZED`]
+/// encoding, see [`ZSchema::encoding`] and [`ZSchema::from_encoding`]. Subscribers can then
+/// check a payload with [`ZSchema::validate`] before deserializing it.
+///
+/// # Examples
+///
+/// ```rust
+/// use zenoh_ext::*;
+///
+/// #[derive(Serialize, Deserialize, Schema)]
+/// struct Position {
+///     x: f64,
+///     y: f64,
+///     label: Option<String>,
+/// }
+///
+/// let schema = Position::schema();
+/// assert_eq!(schema.to_string(), "Position{x:f64,y:f64,label:option<str>}");
+/// assert_eq!(schema.to_string().parse::<ZSchema>().unwrap(), schema);
+///
+/// let zbytes = z_serialize(&Position { x: 1.0, y: 2.0, label: None });
+/// assert!(schema.validate(&zbytes).is_ok());
+/// assert!(schema.validate(&z_serialize(&(1.0f64, "label"))).is_err());
+/// ```
+///
+/// [1]: https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ZSchema {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    /// A LEB128 variable length integer.
+    VarInt,
+    /// A UTF-8 string.
+    Str,
+    /// A sequence of bytes, e.g. a [`ZBytes`].
+    Bytes,
+    /// A boolean followed by the value if it is `true`.
+    Option(Box<ZSchema>),
+    /// A sequence of values, e.g. a slice, an array or a set.
+    Seq(Box<ZSchema>),
+    /// A sequence of key-value pairs.
+    Map(Box<ZSchema>, Box<ZSchema>),
+    /// A tuple of values.
+    Tuple(Vec<ZSchema>),
+    /// A named tuple of named values. Fields of tuple structs are named by their index.
+    Struct {
+        name: String,
+        fields: Vec<(String, ZSchema)>,
+    },
+    /// The index of the variant followed by its fields.
+    Enum {
+        name: String,
+        variants: Vec<(String, Vec<(String, ZSchema)>)>,
+    },
+}
+
+/// Error occurring when a payload doesn't match a [`ZSchema`] or when parsing a [`ZSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZSchemaError(String);
+
+impl fmt::Display for ZSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ZSchemaError {}
+
+impl ZSchema {
+    /// Returns a [`Encoding::ZENOH_SERIALIZED`] encoding whose schema is this schema.
+    pub fn encoding(&self) -> Encoding {
+        Encoding::ZENOH_SERIALIZED.with_schema(self.to_string())
+    }
+
+    /// Returns the schema of a [`Encoding::ZENOH_SERIALIZED`] encoding,
+    /// `None` if the encoding is not serialized data or has no schema.
+    pub fn from_encoding(encoding: &Encoding) -> Option<Result<Self, ZSchemaError>> {
+        let encoding = encoding.to_string();
+        let schema = encoding
+            .strip_prefix(&Encoding::ZENOH_SERIALIZED.to_string())?
+            .strip_prefix(';')?;
+        Some(schema.parse())
+    }
+
+    /// Checks that `zbytes` is a value serialized according to this schema.
+    ///
+    /// The error indicates the path of the value which doesn't match, e.g. `Position.label`.
+    pub fn validate(&self, zbytes: &ZBytes) -> Result<(), ZSchemaError> {
+        let mut deserializer = ZDeserializer::new(zbytes);
+        let mut path = vec![];
+        self.validate_value(&mut deserializer, &mut path)?;
+        if !deserializer.done() {
+            return Err(ZSchemaError("unexpected trailing bytes".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the values of this schema are serialized as no bytes at all.
+    fn is_zero_sized(&self) -> bool {
+        match self {
+            ZSchema::Tuple(schemas) => schemas.iter().all(ZSchema::is_zero_sized),
+            ZSchema::Struct { fields, .. } => fields.iter().all(|(_, s)| s.is_zero_sized()),
+            _ => false,
+        }
+    }
+
+    fn validate_value<'a>(
+        &'a self,
+        deserializer: &mut ZDeserializer,
+        path: &mut Vec<Cow<'a, str>>,
+    ) -> Result<(), ZSchemaError> {
+        macro_rules! check {
+            ($ty:ty) => {
+                deserializer
+                    .deserialize::<$ty>()
+                    .map(drop)
+                    .map_err(|_| error(path, concat!("invalid ", stringify!($ty))))
+            };
+        }
+        let len = |deserializer: &mut ZDeserializer, path: &[Cow<str>]| {
+            deserializer
+                .deserialize::<VarInt<usize>>()
+                .map(|l| l.0)
+                .map_err(|_| error(path, "invalid length"))
+        };
+        // The length of a sequence whose elements are serialized as at least a byte each,
+        // `None` if they are zero-sized and thus don't need to be validated one by one.
+        let seq_len = |deserializer: &mut ZDeserializer, path: &[Cow<str>], zero_sized: bool| {
+            let len = len(deserializer, path)?;
+            if zero_sized {
+                Ok(None)
+            } else if len > deserializer.remaining() {
+                Err(error(path, "invalid length"))
+            } else {
+                Ok(Some(len))
+            }
+        };
+        // The bytes of a string are skipped without being allocated, as their length may be huge
+        let bytes = |deserializer: &mut ZDeserializer, path: &[Cow<str>], utf8: bool| {
+            let len = len(deserializer, path)?;
+            if len > deserializer.remaining() {
+                return Err(error(path, "invalid length"));
+            }
+            deserializer.skip_bytes(len, utf8).map_err(|_| {
+                error(
+                    path,
+                    if utf8 {
+                        "invalid String"
+                    } else {
+                        "invalid Vec<u8>"
+                    },
+                )
+            })
+        };
+        match self {
+            ZSchema::Bool => check!(bool),
+            ZSchema::I8 => check!(i8),
+            ZSchema::I16 => check!(i16),
+            ZSchema::I32 => check!(i32),
+            ZSchema::I64 => check!(i64),
+            ZSchema::I128 => check!(i128),
+            ZSchema::U8 => check!(u8),
+            ZSchema::U16 => check!(u16),
+            ZSchema::U32 => check!(u32),
+            ZSchema::U64 => check!(u64),
+            ZSchema::U128 => check!(u128),
+            ZSchema::F32 => check!(f32),
+            ZSchema::F64 => check!(f64),
+            ZSchema::VarInt => check!(VarInt<usize>),
+            ZSchema::Str => bytes(deserializer, path, true),
+            ZSchema::Bytes => bytes(deserializer, path, false),
+            ZSchema::Option(schema) => {
+                if deserializer
+                    .deserialize::<bool>()
+                    .map_err(|_| error(path, "invalid option flag"))?
+                {
+                    schema.validate_value(deserializer, path)?;
+                }
+                Ok(())
+            }
+            ZSchema::Seq(schema) => {
+                let len = seq_len(deserializer, path, schema.is_zero_sized())?;
+                for i in 0..len.unwrap_or(0) {
+                    path.push(Cow::Owned(format!("[{i}]")));
+                    schema.validate_value(deserializer, path)?;
+                    path.pop();
+                }
+                Ok(())
+            }
+            ZSchema::Map(key, value) => {
+                let zero_sized = key.is_zero_sized() && value.is_zero_sized();
+                let len = seq_len(deserializer, path, zero_sized)?;
+                for i in 0..len.unwrap_or(0) {
+                    path.push(Cow::Owned(format!("[{i}]")));
+                    key.validate_value(deserializer, path)?;
+                    value.validate_value(deserializer, path)?;
+                    path.pop();
+                }
+                Ok(())
+            }
+            ZSchema::Tuple(schemas) => {
+                for (i, schema) in schemas.iter().enumerate() {
+                    path.push(Cow::Owned(format!(".{i}")));
+                    schema.validate_value(deserializer, path)?;
+                    path.pop();
+                }
+                Ok(())
+            }
+            // The type name is only part of the path of the outermost value
+            ZSchema::Struct { name, fields } => {
+                path.push(Cow::Borrowed(if path.is_empty() { name } else { "" }));
+                validate_fields(fields, deserializer, path)?;
+                path.pop();
+                Ok(())
+            }
+            ZSchema::Enum { name, variants } => {
+                let index = len(deserializer, path)?;
+                let Some((variant, fields)) = variants.get(index) else {
+                    return Err(error(path, &format!("invalid {name} variant {index}")));
+                };
+                let name = if path.is_empty() { name.as_str() } else { "" };
+                path.push(Cow::Owned(format!("{name}::{variant}")));
+                validate_fields(fields, deserializer, path)?;
+                path.pop();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn validate_fields<'a>(
+    fields: &'a [(String, ZSchema)],
+    deserializer: &mut ZDeserializer,
+    path: &mut Vec<Cow<'a, str>>,
+) -> Result<(), ZSchemaError> {
+    for (name, schema) in fields {
+        path.push(Cow::Owned(format!(".{name}")));
+        schema.validate_value(deserializer, path)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn error(path: &[Cow<str>], message: &str) -> ZSchemaError {
+    if path.is_empty() {
+        ZSchemaError(message.to_string())
+    } else {
+        ZSchemaError(format!("{}: {message}", path.concat()))
+    }
+}
+
+impl fmt::Display for ZSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn fields(f: &mut fmt::Formatter<'_>, fields: &[(String, ZSchema)]) -> fmt::Result {
+            f.write_str("{")?;
+            for (i, (name, schema)) in fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{name}:{schema}")?;
+            }
+            f.write_str("}")
+        }
+        match self {
+            ZSchema::Bool => f.write_str("bool"),
+            ZSchema::I8 => f.write_str("i8"),
+            ZSchema::I16 => f.write_str("i16"),
+            ZSchema::I32 => f.write_str("i32"),
+            ZSchema::I64 => f.write_str("i64"),
+            ZSchema::I128 => f.write_str("i128"),
+            ZSchema::U8 => f.write_str("u8"),
+            ZSchema::U16 => f.write_str("u16"),
+            ZSchema::U32 => f.write_str("u32"),
+            ZSchema::U64 => f.write_str("u64"),
+            ZSchema::U128 => f.write_str("u128"),
+            ZSchema::F32 => f.write_str("f32"),
+            ZSchema::F64 => f.write_str("f64"),
+            ZSchema::VarInt => f.write_str("varint"),
+            ZSchema::Str => f.write_str("str"),
+            ZSchema::Bytes => f.write_str("bytes"),
+            ZSchema::Option(schema) => write!(f, "option<{schema}>"),
+            ZSchema::Seq(schema) => write!(f, "seq<{schema}>"),
+            ZSchema::Map(key, value) => write!(f, "map<{key},{value}>"),
+            ZSchema::Tuple(schemas) => {
+                f.write_str("(")?;
+                for (i, schema) in schemas.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{schema}")?;
+                }
+                f.write_str(")")
+            }
+            ZSchema::Struct { name, fields: f_ } => {
+                f.write_str(name)?;
+                fields(f, f_)
+            }
+            ZSchema::Enum { name, variants } => {
+                write!(f, "{name}[")?;
+                for (i, (variant, f_)) in variants.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    f.write_str(variant)?;
+                    if !f_.is_empty() {
+                        fields(f, f_)?;
+                    }
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl FromStr for ZSchema {
+    type Err = ZSchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { s, pos: 0 };
+        let schema = parser.schema()?;
+        if parser.pos != s.len() {
+            return Err(parser.error("unexpected trailing characters"));
+        }
+        Ok(schema)
+    }
+}
+
+/// Recursive descent parser of the textual representation of a [`ZSchema`].
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ZSchemaError {
+        ZSchemaError(format!("invalid schema at {}: {message}", self.pos))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.peek() == Some(c);
+        if eaten {
+            self.pos += c.len_utf8();
+        }
+        eaten
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ZSchemaError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{c}`")))
+        }
+    }
+
+    fn ident(&mut self) -> Result<&str, ZSchemaError> {
+        let start = self.pos;
+        let len = self.s[start..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.s.len() - start);
+        if len == 0 {
+            return Err(self.error("expected an identifier"));
+        }
+        self.pos += len;
+        Ok(&self.s[start..self.pos])
+    }
+
+    /// Parses `item` separated by `,` until `end`.
+    fn list<T>(
+        &mut self,
+        end: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, ZSchemaError>,
+    ) -> Result<Vec<T>, ZSchemaError> {
+        let mut items = vec![];
+        if self.eat(end) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if self.eat(end) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn fields(&mut self) -> Result<Vec<(String, ZSchema)>, ZSchemaError> {
+        self.list('}', |p| {
+            let name = p.ident()?.to_string();
+            p.expect(':')?;
+            Ok((name, p.schema()?))
+        })
+    }
+
+    fn schema(&mut self) -> Result<ZSchema, ZSchemaError> {
+        if self.eat('(') {
+            return Ok(ZSchema::Tuple(self.list(')', Self::schema)?));
+        }
+        let ident = self.ident()?;
+        let schema = match ident {
+            "bool" => ZSchema::Bool,
+            "i8" => ZSchema::I8,
+            "i16" => ZSchema::I16,
+            "i32" => ZSchema::I32,
+            "i64" => ZSchema::I64,
+            "i128" => ZSchema::I128,
+            "u8" => ZSchema::U8,
+            "u16" => ZSchema::U16,
+            "u32" => ZSchema::U32,
+            "u64" => ZSchema::U64,
+            "u128" => ZSchema::U128,
+            "f32" => ZSchema::F32,
+            "f64" => ZSchema::F64,
+            "varint" => ZSchema::VarInt,
+            "str" => ZSchema::Str,
+            "bytes" => ZSchema::Bytes,
+            "option" | "seq" | "map" => {
+                let ident = ident.to_string();
+                self.expect('<')?;
+                let schema = Box::new(self.schema()?);
+                let schema = if ident == "map" {
+                    self.expect(',')?;
+                    ZSchema::Map(schema, Box::new(self.schema()?))
+                } else if ident == "seq" {
+                    ZSchema::Seq(schema)
+                } else {
+                    ZSchema::Option(schema)
+                };
+                self.expect('>')?;
+                schema
+            }
+            name => {
+                let name = name.to_string();
+                if self.eat('{') {
+                    ZSchema::Struct {
+                        name,
+                        fields: self.fields()?,
+                    }
+                } else if self.eat('[') {
+                    let variants = self.list(']', |p| {
+                        let variant = p.ident()?.to_string();
+                        let fields = if p.eat('{') { p.fields()? } else { vec![] };
+                        Ok((variant, fields))
+                    })?;
+                    ZSchema::Enum { name, variants }
+                } else {
+                    return Err(self.error(&format!("unknown type `{name}`")));
+                }
+            }
+        };
+        Ok(schema)
+    }
+}
+
+/// Types whose [`ZSchema`] is known, i.e. whose serialization format can be described at runtime.
+///
+/// It can be derived for structs and enums with `#[derive(Schema)]`.
+pub trait Schema {
+    /// Returns the schema of the type.
+    fn schema() -> ZSchema;
+}
+
+impl<T: Schema + ?Sized> Schema for &T {
+    fn schema() -> ZSchema {
+        T::schema()
+    }
+}
+
+macro_rules! impl_schema {
+    ($($ty:ty => $schema:expr),* $(,)?) => {$(
+        impl Schema for $ty {
+            fn schema() -> ZSchema {
+                $schema
+            }
+        }
+    )*};
+}
+impl_schema!(
+    bool => ZSchema::Bool,
+    i8 => ZSchema::I8,
+    i16 => ZSchema::I16,
+    i32 => ZSchema::I32,
+    i64 => ZSchema::I64,
+    i128 => ZSchema::I128,
+    u8 => ZSchema::U8,
+    u16 => ZSchema::U16,
+    u32 => ZSchema::U32,
+    u64 => ZSchema::U64,
+    u128 => ZSchema::U128,
+    f32 => ZSchema::F32,
+    f64 => ZSchema::F64,
+    VarInt<usize> => ZSchema::VarInt,
+    str => ZSchema::Str,
+    String => ZSchema::Str,
+    Cow<'_, str> => ZSchema::Str,
+    ZBytes => ZSchema::Bytes,
+);
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> ZSchema {
+        ZSchema::Option(Box::new(T::schema()))
+    }
+}
+
+macro_rules! impl_seq_schema {
+    ($($ty:ty $(: $bound:path)?),* $(,)?) => {$(
+        impl<T: Schema $(+ $bound)?> Schema for $ty {
+            fn schema() -> ZSchema {
+                ZSchema::Seq(Box::new(T::schema()))
+            }
+        }
+    )*};
+}
+impl_seq_schema!([T], Vec<T>, Box<[T]>, HashSet<T>, BTreeSet<T>);
+
+impl<T: Schema, const N: usize> Schema for [T; N] {
+    fn schema() -> ZSchema {
+        ZSchema::Seq(Box::new(T::schema()))
+    }
+}
+
+impl<T: Schema + Clone> Schema for Cow<'_, [T]> {
+    fn schema() -> ZSchema {
+        ZSchema::Seq(Box::new(T::schema()))
+    }
+}
+
+impl<K: Schema, V: Schema> Schema for HashMap<K, V> {
+    fn schema() -> ZSchema {
+        ZSchema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+impl<K: Schema, V: Schema> Schema for BTreeMap<K, V> {
+    fn schema() -> ZSchema {
+        ZSchema::Map(Box::new(K::schema()), Box::new(V::schema()))
+    }
+}
+
+macro_rules! impl_tuple_schema {
+    ($($ty:ident),*) => {
+        impl<$($ty: Schema),*> Schema for ($($ty,)*) {
+            fn schema() -> ZSchema {
+                ZSchema::Tuple(vec![$($ty::schema()),*])
+            }
+        }
+    };
+}
+impl_tuple_schema!();
+impl_tuple_schema!(T0);
+impl_tuple_schema!(T0, T1);
+impl_tuple_schema!(T0, T1, T2);
+impl_tuple_schema!(T0, T1, T2, T3);
+impl_tuple_schema!(T0, T1, T2, T3, T4);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14);
+impl_tuple_schema!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization::z_serialize;
+
+    #[test]
+    fn schema_display_parse() {
+        for schema in [
+            <(u8, i64, f32, bool, String)>::schema(),
+            <HashMap<String, Vec<Option<u16>>>>::schema(),
+            <()>::schema(),
+            ZSchema::Struct {
+                name: "Struct".into(),
+                fields: vec![("a".into(), ZSchema::Bytes), ("0".into(), ZSchema::VarInt)],
+            },
+            ZSchema::Enum {
+                name: "Enum".into(),
+                variants: vec![
+                    ("A".into(), vec![]),
+                    ("B".into(), vec![("0".into(), ZSchema::I32)]),
+                ],
+            },
+        ] {
+            assert_eq!(schema.to_string().parse::<ZSchema>().unwrap(), schema);
+        }
+        assert_eq!(
+            <HashMap<String, Vec<Option<u16>>>>::schema().to_string(),
+            "map<str,seq<option<u16>>>"
+        );
+        assert!("seq<u8".parse::<ZSchema>().is_err());
+        assert!("unknown".parse::<ZSchema>().is_err());
+        assert!("u8,".parse::<ZSchema>().is_err());
+    }
+
+    #[test]
+    fn schema_encoding() {
+        let schema = <(u8, String)>::schema();
+        let encoding = schema.encoding();
+        assert_eq!(encoding.to_string(), "zenoh/serialized;(u8,str)");
+        assert_eq!(ZSchema::from_encoding(&encoding), Some(Ok(schema)));
+        assert_eq!(ZSchema::from_encoding(&Encoding::ZENOH_SERIALIZED), None);
+        assert_eq!(ZSchema::from_encoding(&Encoding::TEXT_PLAIN), None);
+    }
+
+    #[test]
+    fn schema_validate() {
+        let schema = <(u16, Vec<String>, Option<f64>)>::schema();
+        let valid = (42u16, vec!["a".to_string(), "b".to_string()], Some(1.0f64));
+        assert!(schema.validate(&z_serialize(&valid)).is_ok());
+        assert!(schema
+            .validate(&z_serialize(&(42u16, Vec::<String>::new(), None::<f64>)))
+            .is_ok());
+        // Missing option
+        assert_eq!(
+            schema.validate(&z_serialize(&(42u16, vec!["a"]))),
+            Err(ZSchemaError(".2: invalid option flag".into()))
+        );
+        // Invalid UTF-8 string
+        assert_eq!(
+            schema.validate(&z_serialize(&(42u16, vec![vec![0xffu8]], None::<f64>))),
+            Err(ZSchemaError(".1[0]: invalid String".into()))
+        );
+        // Trailing bytes
+        assert!(schema
+            .validate(&z_serialize(&(42u16, vec!["a"], None::<f64>, 0u8)))
+            .is_err());
+    }
+
+    #[test]
+    fn schema_validate_huge_length() {
+        let huge = z_serialize(&VarInt(usize::MAX));
+        // Zero-sized elements are valid whatever their count
+        for schema in [
+            ZSchema::Seq(Box::new(ZSchema::Tuple(vec![]))),
+            ZSchema::Map(Box::new(<()>::schema()), Box::new(<((), ())>::schema())),
+        ] {
+            assert!(schema.validate(&huge).is_ok());
+        }
+        // Other elements are bounded by the remaining bytes
+        assert_eq!(
+            <Vec<u8>>::schema().validate(&huge),
+            Err(ZSchemaError("invalid length".into()))
+        );
+        assert_eq!(
+            <Vec<(u8, ())>>::schema().validate(&z_serialize(&VarInt(2usize))),
+            Err(ZSchemaError("invalid length".into()))
+        );
+        for schema in [ZSchema::Str, ZSchema::Bytes] {
+            assert_eq!(
+                schema.validate(&huge),
+                Err(ZSchemaError("invalid length".into()))
+            );
+        }
+    }
+
+    /// Splits the bytes in slices of 7 bytes.
+    fn split(bytes: &[u8]) -> ZBytes {
+        let mut writer = ZBytes::writer();
+        for chunk in bytes.chunks(7) {
+            writer.append(ZBytes::from(chunk.to_vec()));
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn schema_validate_split_bytes() {
+        // The characters of the string are split between the slices
+        let serialized = z_serialize(&"é".repeat(1000)).to_bytes().into_owned();
+        assert!(ZSchema::Str.validate(&split(&serialized)).is_ok());
+        assert!(ZSchema::Bytes.validate(&split(&serialized)).is_ok());
+
+        let mut invalid = serialized;
+        *invalid.last_mut().unwrap() = 0xff;
+        assert_eq!(
+            ZSchema::Str.validate(&split(&invalid)),
+            Err(ZSchemaError("invalid String".into()))
+        );
+        assert!(ZSchema::Bytes.validate(&split(&invalid)).is_ok());
+    }
+}
@@ -41,8 +41,19 @@ fn default_serialize_n<T: Serialize>(slice: &[T], serializer: &mut ZSerializer)
 
 /// Serialization implementation.
 ///
+/// It can be derived for structs and enums with `#[derive(Serialize)]`.
 /// See [Zenoh serialization format RFC][1].
 ///
+/// The derived implementation encodes the variants of an enum by their index in declaration order,
+/// so enums with explicit discriminants are rejected:
+/// ```compile_fail
+/// #[derive(zenoh_ext::Serialize)]
+/// enum Flag {
+///     A = 1,
+///     B = 2,
+/// }
+/// ```
+///
 /// [1]: https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md
 pub trait Serialize {
     /// Serialize the given object into a [`ZSerializer`].
@@ -86,6 +97,7 @@ fn default_deserialize_n_uninit<'a, T: Deserialize>(
 
 /// Deserialization implementation.
 ///
+/// It can be derived for structs and enums with `#[derive(Deserialize)]`.
 /// See [Zenoh serialization format RFC][1].
 ///
 /// [1]: https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md
@@ -110,6 +122,47 @@ pub trait Deserialize: Sized {
     }
 }
 
+/// Deserialization implementation borrowing from the deserialized [`ZBytes`] when possible.
+///
+/// It is implemented for `&[u8]`, `&str`, `Cow<[u8]>` and `Cow<str>`, for the [`DeserializeOwned`]
+/// types, and for the tuples, sequences, sets, maps and options of `DeserializeBorrowed` types.
+/// `Cow<[u8]>` and `Cow<str>` borrow the bytes when they are contiguous in memory, e.g. in the
+/// same network or shared memory buffer, and allocate otherwise, while `&[u8]` and `&str` fail
+/// to deserialize non-contiguous bytes.
+///
+/// See [Zenoh serialization format RFC][1].
+///
+/// [1]: https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md
+#[cfg(feature = "unstable")]
+pub trait DeserializeBorrowed<'a>: Sized {
+    /// Deserialize the given type from a [`ZDeserializer`], borrowing from its [`ZBytes`].
+    ///
+    /// User may prefer to use [`ZDeserializer::deserialize_borrowed`] instead of this function.
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError>;
+    #[doc(hidden)]
+    fn deserialize_borrowed_vec(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Vec<Self>, ZDeserializeError> {
+        let len = <VarInt<usize>>::deserialize(deserializer)?.0;
+        // The length is not trusted to allocate, each element being serialized as a byte at least
+        let mut vec = Vec::with_capacity(len.min(deserializer.remaining()));
+        for _ in 0..len {
+            vec.push(Self::deserialize_borrowed(deserializer)?);
+        }
+        Ok(vec)
+    }
+}
+
+/// Marker of the [`Deserialize`] types that never borrow from the deserialized [`ZBytes`], e.g.
+/// numbers or `String`, implementing [`DeserializeBorrowed`] with [`Deserialize`].
+///
+/// A type deriving [`Deserialize`] can implement it to be deserialized with
+/// [`ZDeserializer::deserialize_borrowed`], e.g. in a tuple along with borrowed strings.
+#[cfg(feature = "unstable")]
+pub trait DeserializeOwned: Deserialize {}
+
 /// Serialize an object according to the [Zenoh serialization format][1].
 ///
 /// Serialization doesn't take the ownership of the data.
@@ -149,6 +202,33 @@ pub fn z_deserialize<T: Deserialize>(zbytes: &ZBytes) -> Result<T, ZDeserializeE
     Ok(t)
 }
 
+/// Deserialize an object according to the [Zenoh serialization format][1],
+/// borrowing from `zbytes` when possible.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use zenoh_ext::*;
+/// let zbytes = z_serialize("large payload");
+/// let s = z_deserialize_borrowed::<Cow<str>>(&zbytes).unwrap();
+/// assert!(matches!(s, Cow::Borrowed("large payload")));
+/// ```
+///
+/// [1]: https://github.com/eclipse-zenoh/roadmap/blob/main/rfcs/ALL/Serialization.md
+#[cfg(feature = "unstable")]
+pub fn z_deserialize_borrowed<'a, T: DeserializeBorrowed<'a>>(
+    zbytes: &'a ZBytes,
+) -> Result<T, ZDeserializeError> {
+    let mut deserializer = ZDeserializer::new(zbytes);
+    let t = T::deserialize_borrowed(&mut deserializer)?;
+    if !deserializer.done() {
+        return Err(ZDeserializeError);
+    }
+    Ok(t)
+}
+
 /// Serializer implementing the [Zenoh serialization format][1].
 ///
 /// Serializing objects one after the other is equivalent to serialize a tuple of these objects.
@@ -246,11 +326,64 @@ impl<'a> ZDeserializer<'a> {
         self.0.is_empty()
     }
 
+    /// Return the number of bytes left to deserialize.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn remaining(&self) -> usize {
+        self.0.remaining()
+    }
+
+    /// Skip the next `len` bytes without allocating them, checking that they are valid UTF-8 if
+    /// `utf8` is set.
+    #[cfg(feature = "unstable")]
+    pub(crate) fn skip_bytes(&mut self, len: usize, utf8: bool) -> Result<(), ZDeserializeError> {
+        if len > self.remaining() {
+            return Err(ZDeserializeError);
+        }
+        if let Some(bytes) = self.0.read_contiguous(len) {
+            if utf8 && std::str::from_utf8(bytes).is_err() {
+                return Err(ZDeserializeError);
+            }
+            return Ok(());
+        }
+        // The bytes are split across several slices: they are read and checked chunk by chunk,
+        // a character split between two chunks being moved to the start of the next one.
+        let mut buf = [0u8; 1024];
+        let (mut left, mut split) = (len, 0);
+        while left > 0 {
+            let read = (buf.len() - split).min(left);
+            self.0
+                .read_exact(&mut buf[split..split + read])
+                .or(Err(ZDeserializeError))?;
+            left -= read;
+            let chunk_len = split + read;
+            split = 0;
+            if !utf8 {
+                continue;
+            }
+            if let Err(e) = std::str::from_utf8(&buf[..chunk_len]) {
+                if e.error_len().is_some() || left == 0 {
+                    return Err(ZDeserializeError);
+                }
+                split = chunk_len - e.valid_up_to();
+                buf.copy_within(e.valid_up_to()..chunk_len, 0);
+            }
+        }
+        Ok(())
+    }
+
     /// Deserialize the given type from a [`ZDeserializer`].
     pub fn deserialize<T: Deserialize>(&mut self) -> Result<T, ZDeserializeError> {
         T::deserialize(self)
     }
 
+    /// Deserialize the given type from a [`ZDeserializer`], borrowing from its [`ZBytes`] when possible.
+    #[cfg(feature = "unstable")]
+    pub fn deserialize_borrowed<T: DeserializeBorrowed<'a>>(
+        &mut self,
+    ) -> Result<T, ZDeserializeError> {
+        T::deserialize_borrowed(self)
+    }
+
     /// Deserialize an iterator into a [`ZDeserializer`].
     ///
     /// Sequence deserialized with this method may have been serialized with [`ZSerializer::serialize_iter`].
@@ -500,6 +633,196 @@ impl Deserialize for String {
     }
 }
 
+impl<T: Serialize> Serialize for Option<T> {
+    fn serialize(&self, serializer: &mut ZSerializer) {
+        self.is_some().serialize(serializer);
+        if let Some(t) = self {
+            t.serialize(serializer);
+        }
+    }
+}
+impl<T: Deserialize> Deserialize for Option<T> {
+    fn deserialize(deserializer: &mut ZDeserializer) -> Result<Self, ZDeserializeError> {
+        if bool::deserialize(deserializer)? {
+            Ok(Some(T::deserialize(deserializer)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+macro_rules! impl_deserialize_owned {
+    ($($ty:ty),* $(,)?) => {$(
+        impl DeserializeOwned for $ty {}
+    )*};
+}
+#[cfg(feature = "unstable")]
+impl_deserialize_owned!(
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    f32,
+    f64,
+    bool,
+    String,
+    VarInt<usize>,
+);
+
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeOwned> DeserializeBorrowed<'a> for T {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        T::deserialize(deserializer)
+    }
+    fn deserialize_borrowed_vec(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Vec<Self>, ZDeserializeError> {
+        <Vec<T>>::deserialize(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a>> DeserializeBorrowed<'a> for Vec<T> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        T::deserialize_borrowed_vec(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a>> DeserializeBorrowed<'a> for Box<[T]> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        Ok(T::deserialize_borrowed_vec(deserializer)?.into_boxed_slice())
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a>, const N: usize> DeserializeBorrowed<'a> for [T; N] {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        T::deserialize_borrowed_vec(deserializer)?
+            .try_into()
+            .or(Err(ZDeserializeError))
+    }
+}
+#[cfg(feature = "unstable")]
+fn deserialize_borrowed_iter<'a, T: DeserializeBorrowed<'a>, C: FromIterator<T>>(
+    deserializer: &mut ZDeserializer<'a>,
+) -> Result<C, ZDeserializeError> {
+    let len = <VarInt<usize>>::deserialize(deserializer)?.0;
+    (0..len)
+        .map(|_| T::deserialize_borrowed(deserializer))
+        .collect()
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a> + Eq + Hash> DeserializeBorrowed<'a> for HashSet<T> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        deserialize_borrowed_iter(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a> + Ord> DeserializeBorrowed<'a> for BTreeSet<T> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        deserialize_borrowed_iter(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, K: DeserializeBorrowed<'a> + Eq + Hash, V: DeserializeBorrowed<'a>> DeserializeBorrowed<'a>
+    for HashMap<K, V>
+{
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        deserialize_borrowed_iter(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, K: DeserializeBorrowed<'a> + Ord, V: DeserializeBorrowed<'a>> DeserializeBorrowed<'a>
+    for BTreeMap<K, V>
+{
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        deserialize_borrowed_iter(deserializer)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a, T: DeserializeBorrowed<'a>> DeserializeBorrowed<'a> for Option<T> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        if bool::deserialize(deserializer)? {
+            Ok(Some(T::deserialize_borrowed(deserializer)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a> DeserializeBorrowed<'a> for &'a [u8] {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        let len = <VarInt<usize>>::deserialize(deserializer)?.0;
+        deserializer.0.read_contiguous(len).ok_or(ZDeserializeError)
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a> DeserializeBorrowed<'a> for &'a str {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        std::str::from_utf8(deserializer.deserialize_borrowed()?).or(Err(ZDeserializeError))
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a> DeserializeBorrowed<'a> for Cow<'a, [u8]> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        let len = <VarInt<usize>>::deserialize(deserializer)?.0;
+        if let Some(bytes) = deserializer.0.read_contiguous(len) {
+            return Ok(Cow::Borrowed(bytes));
+        }
+        if deserializer.0.remaining() < len {
+            return Err(ZDeserializeError);
+        }
+        let mut vec = vec![0; len];
+        deserializer
+            .0
+            .read_exact(&mut vec)
+            .or(Err(ZDeserializeError))?;
+        Ok(Cow::Owned(vec))
+    }
+}
+#[cfg(feature = "unstable")]
+impl<'a> DeserializeBorrowed<'a> for Cow<'a, str> {
+    fn deserialize_borrowed(
+        deserializer: &mut ZDeserializer<'a>,
+    ) -> Result<Self, ZDeserializeError> {
+        Ok(match deserializer.deserialize_borrowed()? {
+            Cow::Borrowed(bytes) => {
+                Cow::Borrowed(std::str::from_utf8(bytes).or(Err(ZDeserializeError))?)
+            }
+            Cow::Owned(vec) => Cow::Owned(String::from_utf8(vec).or(Err(ZDeserializeError))?),
+        })
+    }
+}
+
 macro_rules! impl_tuple {
     ($($ty:ident/$i:tt),* $(,)?) => {
         impl_tuple!(@;$($ty/$i),*);
@@ -524,6 +847,15 @@ macro_rules! impl_tuple {
                 Ok(($($ty::deserialize(deserializer)?,)*))
             }
         }
+        #[cfg(feature = "unstable")]
+        #[allow(unused)]
+        impl<'a, $($ty: DeserializeBorrowed<'a>),*> DeserializeBorrowed<'a> for ($($ty,)*) {
+            fn deserialize_borrowed(
+                deserializer: &mut ZDeserializer<'a>,
+            ) -> Result<Self, ZDeserializeError> {
+                Ok(($($ty::deserialize_borrowed(deserializer)?,)*))
+            }
+        }
     };
 }
 impl_tuple!(
@@ -636,6 +968,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn option_serialization() {
+        serialize_deserialize!(Option<String>, Some("42".to_string()));
+        serialize_deserialize!(Option<String>, None);
+        serialize_deserialize!(Vec<Option<i32>>, vec![Some(1), None, Some(3)]);
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn borrowed_deserialization() {
+        let payload = z_serialize(&("abc", vec![1u8, 2, 3], 42u32));
+        let mut deserializer = ZDeserializer::new(&payload);
+        assert_eq!(deserializer.deserialize_borrowed::<&str>().unwrap(), "abc");
+        assert!(matches!(
+            deserializer.deserialize_borrowed::<Cow<[u8]>>().unwrap(),
+            Cow::Borrowed([1, 2, 3])
+        ));
+        assert_eq!(deserializer.deserialize_borrowed::<u32>().unwrap(), 42);
+        assert!(deserializer.done());
+        assert_eq!(
+            z_deserialize_borrowed::<(&str, &[u8], u32)>(&payload).unwrap(),
+            ("abc", [1u8, 2, 3].as_slice(), 42)
+        );
+
+        // Containers of borrowed types
+        let payload = z_serialize(&vec!["a", "bc"]);
+        assert_eq!(
+            z_deserialize_borrowed::<Vec<&str>>(&payload).unwrap(),
+            ["a", "bc"]
+        );
+        let map = HashMap::from([("a".to_string(), vec![1u8]), ("b".to_string(), vec![])]);
+        let payload = z_serialize(&map);
+        let borrowed = z_deserialize_borrowed::<HashMap<&str, &[u8]>>(&payload).unwrap();
+        assert_eq!(borrowed.len(), 2);
+        assert_eq!(borrowed["a"], [1u8]);
+        assert_eq!(
+            z_deserialize_borrowed::<Vec<Option<(String, Cow<str>)>>>(&z_serialize(&vec![
+                Some(("a", "b")),
+                None
+            ]))
+            .unwrap(),
+            [Some(("a".to_string(), Cow::Borrowed("b"))), None]
+        );
+        // A huge length does not allocate
+        assert!(z_deserialize_borrowed::<Vec<&str>>(&z_serialize(&VarInt(usize::MAX))).is_err());
+        assert!(z_deserialize_borrowed::<&str>(&z_serialize(&[0xffu8][..])).is_err());
+
+        // Bytes split over several slices cannot be borrowed
+        let mut serializer = ZSerializer::new();
+        serializer.serialize(VarInt(6));
+        serializer.0.append(ZBytes::from("abc"));
+        serializer.0.append(ZBytes::from("def"));
+        let payload = serializer.finish();
+        assert!(z_deserialize_borrowed::<&[u8]>(&payload).is_err());
+        assert!(matches!(
+            z_deserialize_borrowed::<Cow<str>>(&payload).unwrap(),
+            Cow::Owned(s) if s == "abcdef"
+        ));
+    }
+
     #[test]
     fn hashmap_serialization() {
         let mut map = HashMap::new();
@@ -670,5 +1062,7 @@ mod tests {
         );
         let vp: Vec<(&str, i16)> = vec![("s1", 10), ("s2", -10000)];
         check_binary_format!(vp, vec![2, 2, 115, 49, 10, 0, 2, 115, 50, 240, 216]);
+        let o: (Option<u8>, Option<u8>) = (Some(42), None);
+        check_binary_format!(o, vec![1, 42, 0]);
     }
 }
@@ -15,8 +15,8 @@
 use zenoh::sample::SampleKind;
 use zenoh_config::{EndPoint, ModeDependentValue, WhatAmI};
 use zenoh_ext::{
-    AdvancedPublisherBuilderExt, AdvancedSubscriberBuilderExt, CacheConfig, HistoryConfig,
-    RecoveryConfig,
+    AdvancedPublisherBuilderExt, AdvancedSubscriberBuilderExt, CacheConfig, FileCacheStore,
+    HistoryConfig, RecoveryConfig,
 };
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
@@ -713,3 +713,838 @@ async fn test_advanced_late_joiner() {
 
     router.close().await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_history_store() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const PEER1_ENDPOINT: &str = "tcp/localhost:47456";
+
+    const ADVANCED_HISTORY_STORE_KEYEXPR: &str = "test/advanced/history_store";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let path = std::env::temp_dir().join(format!(
+        "zenoh_test_advanced_history_store_{}.log",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+
+    let peer1 = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![PEER1_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        c.timestamping
+            .set_enabled(Some(ModeDependentValue::Unique(true)))
+            .unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Peer));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Peer (1) ZID: {}", s.zid());
+        s
+    };
+
+    let publ = ztimeout!(peer1
+        .declare_publisher(ADVANCED_HISTORY_STORE_KEYEXPR)
+        .cache(
+            CacheConfig::default()
+                .max_samples(3)
+                .store(FileCacheStore::new(&path).unwrap())
+        ))
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    publ.undeclare().await.unwrap();
+
+    // Restart the publisher: the history is loaded from the store
+    let publ = ztimeout!(peer1
+        .declare_publisher(ADVANCED_HISTORY_STORE_KEYEXPR)
+        .cache(
+            CacheConfig::default()
+                .max_samples(3)
+                .store(FileCacheStore::new(&path).unwrap())
+        ))
+    .unwrap();
+    ztimeout!(publ.put("4")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let peer2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![PEER1_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Peer));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Peer (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(peer2
+        .declare_subscriber(ADVANCED_HISTORY_STORE_KEYEXPR)
+        .history(HistoryConfig::default()))
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let mut payloads = vec![];
+    while let Some(sample) = sub.try_recv().unwrap() {
+        assert_eq!(sample.kind(), SampleKind::Put);
+        payloads.push(sample.payload().try_to_string().unwrap().into_owned());
+    }
+    payloads.sort();
+    assert_eq!(payloads, ["2", "3", "4"]);
+
+    publ.undeclare().await.unwrap();
+
+    peer1.close().await.unwrap();
+    peer2.close().await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_history_slow_store() {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant, SystemTime},
+    };
+
+    use zenoh::{internal::ztimeout, sample::Sample};
+    use zenoh_ext::CacheStore;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const STORE_DELAY: Duration = Duration::from_millis(500);
+
+    const ADVANCED_HISTORY_SLOW_STORE_KEYEXPR: &str = "test/advanced/history_slow_store";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    /// A store taking [`STORE_DELAY`] to store each sample.
+    #[derive(Clone, Default)]
+    struct SlowStore(Arc<Mutex<Vec<String>>>);
+
+    impl CacheStore for SlowStore {
+        fn load(&self) -> zenoh::Result<Vec<(SystemTime, Sample)>> {
+            Ok(vec![])
+        }
+
+        fn push(&self, _time: SystemTime, sample: &Sample) -> zenoh::Result<()> {
+            std::thread::sleep(STORE_DELAY);
+            let payload = sample.payload().try_to_string().unwrap().into_owned();
+            self.0.lock().unwrap().push(payload);
+            Ok(())
+        }
+
+        fn evict(&self, _count: usize) -> zenoh::Result<()> {
+            Ok(())
+        }
+    }
+
+    let peer = {
+        let mut c = zenoh::Config::default();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        c.timestamping
+            .set_enabled(Some(ModeDependentValue::Unique(true)))
+            .unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Peer));
+        ztimeout!(zenoh::open(c)).unwrap()
+    };
+
+    let store = SlowStore::default();
+    let publ = ztimeout!(peer
+        .declare_publisher(ADVANCED_HISTORY_SLOW_STORE_KEYEXPR)
+        .cache(CacheConfig::default().max_samples(3).store(store.clone())))
+    .unwrap();
+
+    // The publications don't wait for the store
+    let start = Instant::now();
+    ztimeout!(publ.put("1")).unwrap();
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    assert!(start.elapsed() < STORE_DELAY);
+
+    // Undeclaring the publisher waits for the store to be up to date
+    publ.undeclare().await.unwrap();
+    assert_eq!(*store.0.lock().unwrap(), ["1", "2", "3"]);
+
+    peer.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_history_max_age() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const PEER1_ENDPOINT: &str = "tcp/localhost:47457";
+
+    const ADVANCED_HISTORY_MAX_AGE_KEYEXPR: &str = "test/advanced/history_max_age";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let peer1 = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![PEER1_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        c.timestamping
+            .set_enabled(Some(ModeDependentValue::Unique(true)))
+            .unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Peer));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Peer (1) ZID: {}", s.zid());
+        s
+    };
+
+    let publ = ztimeout!(peer1
+        .declare_publisher(ADVANCED_HISTORY_MAX_AGE_KEYEXPR)
+        .cache(CacheConfig::default().max_samples(10).max_age(SLEEP)))
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+    ztimeout!(publ.put("2")).unwrap();
+
+    tokio::time::sleep(2 * SLEEP).await;
+
+    ztimeout!(publ.put("3")).unwrap();
+
+    let peer2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![PEER1_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Peer));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Peer (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(peer2
+        .declare_subscriber(ADVANCED_HISTORY_MAX_AGE_KEYEXPR)
+        .history(HistoryConfig::default()))
+    .unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "3");
+
+    tokio::time::sleep(SLEEP).await;
+    assert!(sub.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    peer1.close().await.unwrap();
+    peer2.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_heartbeat() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const RECONNECT_SLEEP: Duration = Duration::from_secs(5);
+    const HEARTBEAT_PERIOD: Duration = Duration::from_millis(500);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47458";
+
+    const ADVANCED_HEARTBEAT_KEYEXPR: &str = "test/advanced/heartbeat";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let client1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(client2
+        .declare_subscriber(ADVANCED_HEARTBEAT_KEYEXPR)
+        .recovery(RecoveryConfig::default().heartbeat()))
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let publ = ztimeout!(client1
+        .declare_publisher(ADVANCED_HEARTBEAT_KEYEXPR)
+        .cache(CacheConfig::default().max_samples(10))
+        .heartbeat(HEARTBEAT_PERIOD))
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "1");
+
+    assert!(sub.try_recv().unwrap().is_none());
+
+    router.close().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    ztimeout!(publ.put("4")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    assert!(sub.try_recv().unwrap().is_none());
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+    // No new publication: the lost samples are detected thanks to the heartbeats
+    tokio::time::sleep(RECONNECT_SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "2");
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "3");
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "4");
+
+    assert!(sub.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    client1.close().await.unwrap();
+    client2.close().await.unwrap();
+
+    router.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_heartbeat_sample_miss() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const RECONNECT_SLEEP: Duration = Duration::from_secs(5);
+    const HEARTBEAT_PERIOD: Duration = Duration::from_millis(500);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47459";
+
+    const ADVANCED_HEARTBEAT_SAMPLE_MISS_KEYEXPR: &str = "test/advanced/heartbeat_sample_miss";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let client1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(client2
+        .declare_subscriber(ADVANCED_HEARTBEAT_SAMPLE_MISS_KEYEXPR)
+        .recovery(RecoveryConfig::default().heartbeat()))
+    .unwrap();
+    let miss_listener = ztimeout!(sub.sample_miss_listener()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // No cache: the lost samples can't be recovered
+    let publ = ztimeout!(client1
+        .declare_publisher(ADVANCED_HEARTBEAT_SAMPLE_MISS_KEYEXPR)
+        .heartbeat(HEARTBEAT_PERIOD))
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "1");
+
+    router.close().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+    // No new publication: the lost samples are detected thanks to the heartbeats
+    tokio::time::sleep(RECONNECT_SLEEP).await;
+
+    let miss = ztimeout!(miss_listener.recv_async()).unwrap();
+    assert_eq!(miss.source(), publ.id());
+    assert_eq!(miss.nb(), 2);
+
+    assert!(miss_listener.try_recv().unwrap().is_none());
+    assert!(sub.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    client1.close().await.unwrap();
+    client2.close().await.unwrap();
+
+    router.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_ordered_deadline() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const RECONNECT_SLEEP: Duration = Duration::from_secs(5);
+    const DEADLINE: Duration = Duration::from_secs(3);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47460";
+
+    const ADVANCED_ORDERED_DEADLINE_KEYEXPR: &str = "test/advanced/ordered_deadline";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let client1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(client2
+        .declare_subscriber(ADVANCED_ORDERED_DEADLINE_KEYEXPR)
+        .recovery(RecoveryConfig::default().ordered(DEADLINE)))
+    .unwrap();
+    let miss_listener = ztimeout!(sub.sample_miss_listener()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // No cache: the lost sample can't be recovered
+    let publ = ztimeout!(client1
+        .declare_publisher(ADVANCED_ORDERED_DEADLINE_KEYEXPR)
+        .sample_miss_detection())
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "1");
+
+    router.close().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publ.put("2")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+    tokio::time::sleep(RECONNECT_SLEEP).await;
+
+    ztimeout!(publ.put("3")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The sample following the gap is held back until the deadline expires
+    assert!(sub.try_recv().unwrap().is_none());
+    assert!(miss_listener.try_recv().unwrap().is_none());
+
+    let miss = ztimeout!(miss_listener.recv_async()).unwrap();
+    assert_eq!(miss.source(), publ.id());
+    assert_eq!(miss.nb(), 1);
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "3");
+
+    assert!(sub.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    client1.close().await.unwrap();
+    client2.close().await.unwrap();
+
+    router.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_ordered_recovery() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const RECONNECT_SLEEP: Duration = Duration::from_secs(5);
+    const DEADLINE: Duration = Duration::from_secs(30);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47490";
+
+    const ADVANCED_ORDERED_RECOVERY_KEYEXPR: &str = "test/advanced/ordered_recovery";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let client1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(client2
+        .declare_subscriber(ADVANCED_ORDERED_RECOVERY_KEYEXPR)
+        .recovery(RecoveryConfig::default().ordered(DEADLINE)))
+    .unwrap();
+    let miss_listener = ztimeout!(sub.sample_miss_listener()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let publ = ztimeout!(client1
+        .declare_publisher(ADVANCED_ORDERED_RECOVERY_KEYEXPR)
+        .cache(CacheConfig::default().max_samples(10))
+        .sample_miss_detection())
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "1");
+
+    router.close().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+    tokio::time::sleep(RECONNECT_SLEEP).await;
+
+    ztimeout!(publ.put("4")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The gap is recovered long before the deadline and the samples are delivered in order
+    for value in ["2", "3", "4"] {
+        let sample = sub.try_recv().unwrap().unwrap();
+        assert_eq!(sample.kind(), SampleKind::Put);
+        assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), value);
+    }
+    assert!(sub.try_recv().unwrap().is_none());
+    assert!(miss_listener.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    client1.close().await.unwrap();
+    client2.close().await.unwrap();
+
+    router.close().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_advanced_ordered_heartbeat() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const RECONNECT_SLEEP: Duration = Duration::from_secs(5);
+    const HEARTBEAT_PERIOD: Duration = Duration::from_millis(500);
+    const DEADLINE: Duration = Duration::from_secs(30);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47491";
+
+    const ADVANCED_ORDERED_HEARTBEAT_KEYEXPR: &str = "test/advanced/ordered_heartbeat";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let client1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let sub = ztimeout!(client2
+        .declare_subscriber(ADVANCED_ORDERED_HEARTBEAT_KEYEXPR)
+        .recovery(RecoveryConfig::default().heartbeat().ordered(DEADLINE)))
+    .unwrap();
+    let miss_listener = ztimeout!(sub.sample_miss_listener()).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // The cache only keeps the last 2 samples: "2" can't be recovered
+    let publ = ztimeout!(client1
+        .declare_publisher(ADVANCED_ORDERED_HEARTBEAT_KEYEXPR)
+        .cache(CacheConfig::default().max_samples(2))
+        .heartbeat(HEARTBEAT_PERIOD))
+    .unwrap();
+    ztimeout!(publ.put("1")).unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "1");
+
+    router.close().await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(publ.put("2")).unwrap();
+    ztimeout!(publ.put("3")).unwrap();
+    ztimeout!(publ.put("4")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+    // No new publication: the heartbeat recovery settles the gap without waiting for the deadline
+    tokio::time::sleep(RECONNECT_SLEEP).await;
+
+    let miss = miss_listener.try_recv().unwrap().unwrap();
+    assert_eq!(miss.source(), publ.id());
+    assert_eq!(miss.nb(), 1);
+    assert!(miss_listener.try_recv().unwrap().is_none());
+
+    for value in ["3", "4"] {
+        let sample = sub.try_recv().unwrap().unwrap();
+        assert_eq!(sample.kind(), SampleKind::Put);
+        assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), value);
+    }
+    assert!(sub.try_recv().unwrap().is_none());
+
+    ztimeout!(publ.put("5")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let sample = sub.try_recv().unwrap().unwrap();
+    assert_eq!(sample.payload().try_to_string().unwrap().as_ref(), "5");
+    assert!(sub.try_recv().unwrap().is_none());
+    assert!(miss_listener.try_recv().unwrap().is_none());
+
+    publ.undeclare().await.unwrap();
+
+    client1.close().await.unwrap();
+    client2.close().await.unwrap();
+
+    router.close().await.unwrap();
+}
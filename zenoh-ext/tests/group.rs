@@ -0,0 +1,223 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use std::sync::Arc;
+
+use zenoh::config::{EndPoint, WhatAmI};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_group_leader_failover() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::group::{Group, GroupEvent, Member};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47461";
+
+    const GROUP: &str = "test/group/failover";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let z_a = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let z_b = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let z_c = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (3) ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let group_a = ztimeout!(Group::join(
+        z_a.clone(),
+        GROUP,
+        Member::new("a").unwrap().info("primary")
+    ))
+    .unwrap();
+    let group_b = ztimeout!(Group::join(z_b.clone(), GROUP, Member::new("b").unwrap())).unwrap();
+    let group_c = ztimeout!(Group::join(z_c.clone(), GROUP, Member::new("c").unwrap())).unwrap();
+
+    for group in [&group_a, &group_b, &group_c] {
+        assert!(ztimeout!(group.wait_for_view_size(3, TIMEOUT)));
+        let view = group.group_view().await;
+        let ids: Vec<&str> = view.members().iter().map(|m| m.id().as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        let leader = ztimeout!(group.wait_for_leader(TIMEOUT)).unwrap();
+        assert_eq!(leader.id().as_str(), "a");
+        assert_eq!(leader.epoch(), 1);
+    }
+    assert_eq!(group_a.leadership().await, Some(1));
+    assert_eq!(group_b.leadership().await, None);
+
+    // The leader session is lost
+    let events = group_b.subscribe().await;
+    ztimeout!(z_a.close()).unwrap();
+
+    let mut left = false;
+    let leader = loop {
+        match ztimeout!(events.recv_async()).unwrap() {
+            GroupEvent::Leave(e) => {
+                assert_eq!(e.mid.as_str(), "a");
+                left = true;
+            }
+            GroupEvent::NewLeader(e) => break e.leader,
+            GroupEvent::Join(e) => panic!("Unexpected join of {}", e.member.id()),
+        }
+    };
+    assert!(left);
+    assert_eq!(leader.id().as_str(), "b");
+    assert_eq!(leader.epoch(), 2);
+    assert_eq!(group_b.leadership().await, Some(2));
+    assert_eq!(group_b.size().await, 2);
+
+    let view = ztimeout!(async {
+        loop {
+            let view = group_c.group_view().await;
+            if view.leader() == Some(&leader) {
+                break view;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+    assert_eq!(view.members().len(), 2);
+    assert_eq!(group_c.leadership().await, None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_group_leader_sticky() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::group::{Group, GroupEvent, Member};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const SLEEP: Duration = Duration::from_secs(1);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47462";
+
+    const GROUP: &str = "test/group/sticky";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let z_b = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let z_a = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        Arc::new(s)
+    };
+
+    let group_b = ztimeout!(Group::join(z_b.clone(), GROUP, Member::new("b").unwrap())).unwrap();
+    let leader = ztimeout!(group_b.wait_for_leader(TIMEOUT)).unwrap();
+    assert_eq!(leader.id().as_str(), "b");
+    assert_eq!(leader.epoch(), 1);
+
+    // A member with a smaller id joining does not take over the leadership
+    let events = group_b.subscribe().await;
+    let group_a = ztimeout!(Group::join(z_a.clone(), GROUP, Member::new("a").unwrap())).unwrap();
+    match ztimeout!(events.recv_async()).unwrap() {
+        GroupEvent::Join(e) => assert_eq!(e.member.id().as_str(), "a"),
+        e => panic!("Unexpected event {e:?}"),
+    }
+    tokio::time::sleep(SLEEP).await;
+    assert!(events.is_empty());
+    assert_eq!(ztimeout!(group_a.wait_for_leader(TIMEOUT)), Some(leader));
+    assert_eq!(group_a.leadership().await, None);
+
+    // The leader leaves the group
+    drop(group_b);
+    let leader = ztimeout!(async {
+        loop {
+            if let Some(leader) = group_a.leader().await.filter(|l| l.id().as_str() == "a") {
+                break leader;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    });
+    assert_eq!(leader.epoch(), 2);
+    assert_eq!(group_a.size().await, 1);
+}
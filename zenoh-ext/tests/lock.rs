@@ -0,0 +1,374 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use zenoh::config::{EndPoint, WhatAmI};
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_lock_acquire_release() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::{DistributedLock, LockEvent};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47463";
+
+    const LOCK: &str = "test/lock/acquire";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let z1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let z2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let z3 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (3) ZID: {}", s.zid());
+        s
+    };
+
+    let lock1 = ztimeout!(DistributedLock::new(&z1, LOCK)).unwrap();
+    let lock2 = ztimeout!(DistributedLock::new(&z2, LOCK)).unwrap();
+    let observer = ztimeout!(DistributedLock::new(&z3, LOCK)).unwrap();
+    let events = observer.subscribe();
+
+    let guard1 = ztimeout!(lock1.acquire(TIMEOUT)).unwrap();
+    assert_eq!(guard1.fence(), 1);
+    assert!(ztimeout!(lock1.acquire(TIMEOUT)).is_err());
+    assert!(ztimeout!(lock2.acquire(Duration::from_secs(1))).is_err());
+
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Acquired(holder) => {
+            assert_eq!(holder.id(), lock1.id());
+            assert_eq!(holder.fence(), 1);
+        }
+        e => panic!("Unexpected event {e:?}"),
+    }
+    assert_eq!(observer.holder().unwrap().id(), lock1.id());
+
+    // The lock is granted to the waiting participant once released
+    let acquire2 = tokio::spawn(async move {
+        let guard = lock2.acquire(TIMEOUT).await.unwrap();
+        (lock2, guard)
+    });
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(!acquire2.is_finished());
+    guard1.release();
+    let (lock2, guard2) = ztimeout!(acquire2).unwrap();
+    assert_eq!(guard2.fence(), 2);
+
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Released(holder) => {
+            assert_eq!(holder.id(), lock1.id());
+            assert_eq!(holder.fence(), 1);
+        }
+        e => panic!("Unexpected event {e:?}"),
+    }
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Acquired(holder) => {
+            assert_eq!(holder.id(), lock2.id());
+            assert_eq!(holder.fence(), 2);
+        }
+        e => panic!("Unexpected event {e:?}"),
+    }
+
+    drop(guard2);
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Released(holder) => assert_eq!(holder.id(), lock2.id()),
+        e => panic!("Unexpected event {e:?}"),
+    }
+    assert!(observer.holder().is_none());
+
+    // A late participant does not reuse a fencing token
+    let lock4 = ztimeout!(DistributedLock::new(&z3, LOCK)).unwrap();
+    let guard4 = ztimeout!(lock4.acquire(TIMEOUT)).unwrap();
+    assert_eq!(guard4.fence(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_lock_session_loss() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::{DistributedLock, LockEvent};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47464";
+
+    const LOCK: &str = "test/lock/loss";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let z1 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let z2 = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let lock1 = ztimeout!(DistributedLock::new(&z1, LOCK)).unwrap();
+    let lock2 = ztimeout!(DistributedLock::new(&z2, LOCK)).unwrap();
+    let events = lock2.subscribe();
+
+    let _guard1 = ztimeout!(lock1.acquire(TIMEOUT)).unwrap();
+    let acquire2 = tokio::spawn(async move {
+        let guard = lock2.acquire(TIMEOUT).await.unwrap();
+        (lock2, guard)
+    });
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Acquired(holder) => assert_eq!(holder.fence(), 1),
+        e => panic!("Unexpected event {e:?}"),
+    }
+
+    // The lock is released when the session of its holder is lost
+    ztimeout!(z1.close()).unwrap();
+    let (_lock2, guard2) = ztimeout!(acquire2).unwrap();
+    assert_eq!(guard2.fence(), 2);
+    match ztimeout!(events.recv_async()).unwrap() {
+        LockEvent::Released(holder) => assert_eq!(holder.fence(), 1),
+        e => panic!("Unexpected event {e:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_lock_contention() {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::DistributedLock;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47465";
+
+    const LOCK: &str = "test/lock/contention";
+    const PARTICIPANTS: usize = 3;
+    const ACQUISITIONS: usize = 5;
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let mut locks = Vec::new();
+    for _ in 0..PARTICIPANTS {
+        let z = {
+            let mut c = zenoh::Config::default();
+            c.connect
+                .endpoints
+                .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+                .unwrap();
+            c.scouting.multicast.set_enabled(Some(false)).unwrap();
+            let _ = c.set_mode(Some(WhatAmI::Client));
+            let s = ztimeout!(zenoh::open(c)).unwrap();
+            tracing::info!("Client ZID: {}", s.zid());
+            s
+        };
+        locks.push(ztimeout!(DistributedLock::new(&z, LOCK)).unwrap());
+    }
+
+    let held = Arc::new(AtomicBool::new(false));
+    let fences = Arc::new(Mutex::new(Vec::new()));
+    let tasks: Vec<_> = locks
+        .into_iter()
+        .map(|lock| {
+            let held = held.clone();
+            let fences = fences.clone();
+            tokio::spawn(async move {
+                for _ in 0..ACQUISITIONS {
+                    let guard = lock.acquire(TIMEOUT).await.unwrap();
+                    assert!(!held.swap(true, Ordering::SeqCst));
+                    fences.lock().unwrap().push(guard.fence());
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    held.store(false, Ordering::SeqCst);
+                    drop(guard);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        ztimeout!(task).unwrap();
+    }
+
+    let fences = fences.lock().unwrap();
+    let expected: Vec<u64> = (1..=(PARTICIPANTS * ACQUISITIONS) as u64).collect();
+    assert_eq!(*fences, expected);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_lock_join_during_acquisition() {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::DistributedLock;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47466";
+
+    const LOCK: &str = "test/lock/join";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let mut sessions = Vec::new();
+    for _ in 0..3 {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client ZID: {}", s.zid());
+        sessions.push(s);
+    }
+
+    let held = Arc::new(AtomicBool::new(false));
+    let hold = |lock: DistributedLock| {
+        let held = held.clone();
+        tokio::spawn(async move {
+            let guard = lock.acquire(TIMEOUT).await.unwrap();
+            assert!(!held.swap(true, Ordering::SeqCst));
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            held.store(false, Ordering::SeqCst);
+            guard.fence()
+        })
+    };
+
+    let lock1 = ztimeout!(DistributedLock::new(&sessions[0], LOCK)).unwrap();
+    let lock2 = ztimeout!(DistributedLock::new(&sessions[1], LOCK)).unwrap();
+    let guard1 = ztimeout!(lock1.acquire(TIMEOUT)).unwrap();
+    let acquire2 = hold(lock2);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // The third participant joins while the second one waits for the first one
+    let lock3 = ztimeout!(DistributedLock::new(&sessions[2], LOCK)).unwrap();
+    let acquire3 = hold(lock3);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(!acquire2.is_finished());
+    assert!(!acquire3.is_finished());
+
+    guard1.release();
+    let mut fences = vec![ztimeout!(acquire2).unwrap(), ztimeout!(acquire3).unwrap()];
+    fences.sort();
+    assert_eq!(fences, [2, 3]);
+}
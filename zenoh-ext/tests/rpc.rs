@@ -0,0 +1,361 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "unstable")]
+use zenoh::config::{EndPoint, WhatAmI};
+use zenoh_ext::{z_serialize, Method};
+
+struct Div;
+
+impl Method for Div {
+    const NAME: &'static str = "div";
+    type Request = (i64, i64);
+    type Response = i64;
+}
+
+struct Range;
+
+impl Method for Range {
+    const NAME: &'static str = "range";
+    type Request = (u32, Option<u32>);
+    type Response = u32;
+}
+
+struct Sleep;
+
+impl Method for Sleep {
+    const NAME: &'static str = "sleep";
+    type Request = u64;
+    type Response = ();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_rpc_unary() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::{RpcClient, RpcError, RpcServer, RpcStatus};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47466";
+
+    const SERVICE: &str = "test/rpc/unary";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let server_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let client = ztimeout!(RpcClient::<Div>::new(&client_session, SERVICE, TIMEOUT)).unwrap();
+    assert!(matches!(
+        ztimeout!(client.call(&(7, 2))),
+        Err(RpcError::NoReply)
+    ));
+
+    let mut server = ztimeout!(RpcServer::new(&server_session, SERVICE)).unwrap();
+    ztimeout!(server.unary::<Div, _, _>(|(a, b), _ctx| async move {
+        a.checked_div(b)
+            .ok_or_else(|| RpcStatus::new(1, "division by zero"))
+    }))
+    .unwrap();
+    ztimeout!(server.unary::<Sleep, _, _>(|ms, _ctx| async move {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        Ok(())
+    }))
+    .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    assert_eq!(ztimeout!(client.call(&(7, 2))).unwrap(), 3);
+    match ztimeout!(client.call(&(7, 0))) {
+        Err(RpcError::Status(status)) => {
+            assert_eq!(status.code(), 1);
+            assert_eq!(status.message(), "division by zero");
+        }
+        r => panic!("Unexpected result {r:?}"),
+    }
+
+    // The call fails if the handler does not reply in time
+    let client = ztimeout!(RpcClient::<Sleep>::new(
+        &client_session,
+        SERVICE,
+        Duration::from_millis(500)
+    ))
+    .unwrap();
+    ztimeout!(client.call(&0)).unwrap();
+    assert!(matches!(
+        ztimeout!(client.call(&5000)),
+        Err(RpcError::Timeout)
+    ));
+
+    // A request of another type is rejected by the server
+    struct InvalidDiv;
+
+    impl Method for InvalidDiv {
+        const NAME: &'static str = "div";
+        type Request = String;
+        type Response = i64;
+    }
+
+    let client = ztimeout!(RpcClient::<InvalidDiv>::new(
+        &client_session,
+        SERVICE,
+        TIMEOUT
+    ))
+    .unwrap();
+    match ztimeout!(client.call(&"7 / 2".to_string())) {
+        Err(RpcError::Status(status)) => assert_eq!(status.code(), RpcStatus::INVALID_REQUEST),
+        r => panic!("Unexpected result {r:?}"),
+    }
+
+    // A request without id is rejected by the server
+    let replies = ztimeout!(client_session
+        .get(format!("{SERVICE}/div"))
+        .payload(z_serialize(&(7i64, 2i64))))
+    .unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    match RpcError::from(reply.into_result().unwrap_err()) {
+        RpcError::Status(status) => assert_eq!(status.code(), RpcStatus::INVALID_REQUEST),
+        e => panic!("Unexpected error {e:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_rpc_streaming() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::{RpcClient, RpcError, RpcServer, RpcStatus};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47467";
+
+    const SERVICE: &str = "test/rpc/streaming";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let server_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    let mut server = ztimeout!(RpcServer::new(&server_session, SERVICE)).unwrap();
+    ztimeout!(server.streaming::<Range, _, _>(|(count, fail_at), _ctx| {
+        futures::stream::iter((0..count).map(move |i| match fail_at {
+            Some(fail_at) if i == fail_at => Err(RpcStatus::new(2, "failed")),
+            _ => Ok(i),
+        }))
+    }))
+    .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let client = ztimeout!(RpcClient::<Range>::new(&client_session, SERVICE, TIMEOUT)).unwrap();
+    let mut responses = ztimeout!(client.call_streaming(&(5, None))).unwrap();
+    let mut received = Vec::new();
+    while let Some(response) = ztimeout!(responses.next()) {
+        received.push(response.unwrap());
+    }
+    assert_eq!(received, [0, 1, 2, 3, 4]);
+
+    // The stream ends after an error
+    let mut responses = ztimeout!(client.call_streaming(&(5, Some(2)))).unwrap();
+    assert_eq!(ztimeout!(responses.next()).unwrap().unwrap(), 0);
+    assert_eq!(ztimeout!(responses.next()).unwrap().unwrap(), 1);
+    match ztimeout!(responses.next()) {
+        Some(Err(RpcError::Status(status))) => assert_eq!(status.code(), 2),
+        r => panic!("Unexpected result {r:?}"),
+    }
+    assert!(ztimeout!(responses.next()).is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_rpc_cancellation() {
+    use std::time::Duration;
+
+    use zenoh::internal::ztimeout;
+    use zenoh_ext::{RpcClient, RpcServer};
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+    const ROUTER_ENDPOINT: &str = "tcp/localhost:47468";
+
+    const SERVICE: &str = "test/rpc/cancellation";
+
+    zenoh_util::init_log_from_env_or("error");
+
+    let _router = {
+        let mut c = zenoh::Config::default();
+        c.listen
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Router));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Router ZID: {}", s.zid());
+        s
+    };
+
+    let server_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (1) ZID: {}", s.zid());
+        s
+    };
+
+    let client_session = {
+        let mut c = zenoh::Config::default();
+        c.connect
+            .endpoints
+            .set(vec![ROUTER_ENDPOINT.parse::<EndPoint>().unwrap()])
+            .unwrap();
+        c.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let _ = c.set_mode(Some(WhatAmI::Client));
+        let s = ztimeout!(zenoh::open(c)).unwrap();
+        tracing::info!("Client (2) ZID: {}", s.zid());
+        s
+    };
+
+    // Notifies the request id when the handler is dropped
+    struct Aborted(flume::Sender<String>, String);
+
+    impl Drop for Aborted {
+        fn drop(&mut self) {
+            let _ = self.0.send(self.1.clone());
+        }
+    }
+
+    let (aborted_tx, aborted_rx) = flume::unbounded();
+    let mut server = ztimeout!(RpcServer::new(&server_session, SERVICE)).unwrap();
+    ztimeout!(server.streaming::<Range, _, _>(move |_, ctx| {
+        let aborted = Aborted(aborted_tx.clone(), ctx.request_id().to_string());
+        futures::stream::unfold((0, aborted), |(i, aborted)| async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Some((Ok(i), (i + 1, aborted)))
+        })
+    }))
+    .unwrap();
+    ztimeout!(server.unary::<Sleep, _, _>(|ms, ctx| async move {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        assert!(!ctx.is_cancelled());
+        Ok(())
+    }))
+    .unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let client = ztimeout!(RpcClient::<Range>::new(&client_session, SERVICE, TIMEOUT)).unwrap();
+    let mut responses = ztimeout!(client.call_streaming(&(0, None))).unwrap();
+    assert_eq!(ztimeout!(responses.next()).unwrap().unwrap(), 0);
+    assert_eq!(ztimeout!(responses.next()).unwrap().unwrap(), 1);
+    let request_id = responses.request_id().to_string();
+    responses.cancel();
+    assert_eq!(ztimeout!(aborted_rx.recv_async()).unwrap(), request_id);
+
+    // Dropping an unary call cancels it
+    let client = ztimeout!(RpcClient::<Sleep>::new(&client_session, SERVICE, TIMEOUT)).unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), client.call(&60_000))
+            .await
+            .is_err()
+    );
+    // The handler of a completed call is not cancelled
+    ztimeout!(client.call(&10)).unwrap();
+
+    // A request cancelled before it reaches the server is not handled
+    ztimeout!(client_session.put(
+        format!("{SERVICE}/@cancel"),
+        z_serialize(&"early".to_string())
+    ))
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let replies = ztimeout!(client_session
+        .get(format!("{SERVICE}/range?_rid=early"))
+        .payload(z_serialize(&(0u32, None::<u32>))))
+    .unwrap();
+    assert!(ztimeout!(replies.recv_async()).is_err());
+    assert!(aborted_rx.try_recv().is_err());
+}
@@ -0,0 +1,156 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+
+use zenoh_ext::{z_deserialize, z_serialize, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Named {
+    id: u16,
+    name: String,
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unnamed(f32, String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Generic<T> {
+    values: HashMap<String, T>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Enum {
+    Unit,
+    Unnamed(i32, Option<Named>),
+    Named { unit: Unit, unnamed: Unnamed },
+}
+
+macro_rules! serialize_deserialize {
+    ($ty:ty, $expr:expr) => {
+        let expr: &$ty = &$expr;
+        let payload = z_serialize(expr);
+        let output = z_deserialize::<$ty>(&payload).unwrap();
+        assert_eq!(*expr, output);
+    };
+}
+
+#[test]
+fn derive_struct_serialization() {
+    let named = Named {
+        id: 42,
+        name: "test".into(),
+        tags: Some(vec!["a".into(), "b".into()]),
+    };
+    serialize_deserialize!(Named, named);
+    serialize_deserialize!(Unnamed, Unnamed(42.0, "test".into()));
+    serialize_deserialize!(Unit, Unit);
+    serialize_deserialize!(
+        Generic<Named>,
+        Generic {
+            values: HashMap::from([(
+                "key".to_string(),
+                Named {
+                    id: 0,
+                    name: String::new(),
+                    tags: None,
+                }
+            )])
+        }
+    );
+}
+
+#[test]
+fn derive_enum_serialization() {
+    serialize_deserialize!(Enum, Enum::Unit);
+    serialize_deserialize!(Enum, Enum::Unnamed(-1, None));
+    serialize_deserialize!(
+        Enum,
+        Enum::Named {
+            unit: Unit,
+            unnamed: Unnamed(1.0, "test".into())
+        }
+    );
+    // Unknown variant index
+    assert!(z_deserialize::<Enum>(&z_serialize(&3u8)).is_err());
+}
+
+#[test]
+fn derive_binary_format() {
+    // A struct is serialized as the tuple of its fields
+    let named = Named {
+        id: 500,
+        name: "test".into(),
+        tags: None,
+    };
+    assert_eq!(
+        z_serialize(&named).to_bytes(),
+        z_serialize(&(500u16, "test", None::<Vec<String>>)).to_bytes()
+    );
+    assert_eq!(
+        z_serialize(&named).to_bytes(),
+        vec![244, 1, 4, 116, 101, 115, 116, 0]
+    );
+    // An enum is serialized as its variant index followed by the tuple of its fields
+    assert_eq!(
+        z_serialize(&Enum::Unnamed(1, None)).to_bytes(),
+        vec![1, 1, 0, 0, 0, 0]
+    );
+    assert_eq!(z_serialize(&Enum::Unit).to_bytes(), vec![0]);
+}
+
+#[cfg(feature = "unstable")]
+#[test]
+fn derive_schema() {
+    use zenoh_ext::{Schema, ZSchema};
+
+    #[derive(Serialize, Schema)]
+    struct Named {
+        id: u16,
+        tags: Option<Vec<String>>,
+    }
+
+    #[derive(Serialize, Schema)]
+    enum Enum<T> {
+        Unit,
+        Unnamed(T, Named),
+    }
+
+    let schema = <Enum<f32>>::schema();
+    assert_eq!(
+        schema.to_string(),
+        "Enum[Unit,Unnamed{0:f32,1:Named{id:u16,tags:option<seq<str>>}}]"
+    );
+    assert_eq!(schema.to_string().parse::<ZSchema>().unwrap(), schema);
+
+    let value = Enum::Unnamed(
+        1.0f32,
+        Named {
+            id: 42,
+            tags: Some(vec!["a".into()]),
+        },
+    );
+    assert!(schema.validate(&z_serialize(&value)).is_ok());
+    assert!(schema.validate(&z_serialize(&Enum::<f32>::Unit)).is_ok());
+    assert_eq!(
+        schema
+            .validate(&z_serialize(&(1u8, 1.0f32, 42u16, 2u8)))
+            .unwrap_err()
+            .to_string(),
+        "Enum::Unnamed.1.tags: invalid option flag"
+    );
+}
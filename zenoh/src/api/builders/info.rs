@@ -18,6 +18,8 @@ use zenoh_config::wrappers::ZenohId;
 use zenoh_core::{Resolvable, Wait};
 use zenoh_protocol::core::WhatAmI;
 
+#[cfg(all(feature = "stats", feature = "unstable"))]
+use crate::api::{session::WeakSession, stats::SessionStats};
 use crate::net::runtime::Runtime;
 
 /// A builder returned by [`SessionInfo::zid()`](crate::session::SessionInfo::zid) that allows
@@ -169,3 +171,58 @@ impl IntoFuture for PeersZenohIdBuilder<'_> {
         std::future::ready(self.wait())
     }
 }
+
+/// A builder returned by [`SessionInfo::stats()`](crate::session::SessionInfo::stats) that allows
+/// to access the [`SessionStats`] of the current zenoh [`Session`](crate::Session).
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let stats = session.info().stats().await;
+/// for publisher in stats.publishers() {
+///     println!("{}: {}", publisher.key_expr(), publisher.samples_sent());
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+#[must_use = "Resolvables do nothing unless you resolve them using `.await` or `zenoh::Wait::wait`"]
+pub struct SessionStatsBuilder<'a> {
+    session: &'a WeakSession,
+}
+
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+impl<'a> SessionStatsBuilder<'a> {
+    pub(crate) fn new(session: &'a WeakSession) -> Self {
+        Self { session }
+    }
+}
+
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+impl Resolvable for SessionStatsBuilder<'_> {
+    type To = SessionStats;
+}
+
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+impl Wait for SessionStatsBuilder<'_> {
+    fn wait(self) -> <Self as Resolvable>::To {
+        SessionStats::new(self.session)
+    }
+}
+
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+impl IntoFuture for SessionStatsBuilder<'_> {
+    type Output = <Self as Resolvable>::To;
+    type IntoFuture = Ready<<Self as Resolvable>::To>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        std::future::ready(self.wait())
+    }
+}
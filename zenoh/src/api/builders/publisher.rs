@@ -23,6 +23,8 @@ use zenoh_protocol::core::Reliability;
 
 #[cfg(feature = "unstable")]
 use crate::api::sample::SourceInfo;
+#[cfg(all(feature = "stats", feature = "unstable"))]
+use crate::api::stats::EntityCounters;
 use crate::{
     api::{
         builders::sample::{
@@ -224,6 +226,8 @@ impl Wait for PublicationBuilder<PublisherBuilder<'_, '_>, PublicationBuilderPut
             #[cfg(feature = "unstable")]
             self.source_info,
             self.attachment,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            None,
         )
     }
 }
@@ -247,6 +251,8 @@ impl Wait for PublicationBuilder<PublisherBuilder<'_, '_>, PublicationBuilderDel
             #[cfg(feature = "unstable")]
             self.source_info,
             self.attachment,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            None,
         )
     }
 }
@@ -462,10 +468,14 @@ impl Wait for PublisherBuilder<'_, '_> {
         if !key_expr.is_fully_optimized(&self.session.0) {
             key_expr = self.session.declare_keyexpr(key_expr).wait()?;
         }
-        let id = self
-            .session
-            .0
-            .declare_publisher_inner(key_expr.clone(), self.destination)?;
+        #[cfg(all(feature = "stats", feature = "unstable"))]
+        let stats = std::sync::Arc::<EntityCounters>::default();
+        let id = self.session.0.declare_publisher_inner(
+            key_expr.clone(),
+            self.destination,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            stats.clone(),
+        )?;
         Ok(Publisher {
             session: self.session.downgrade(),
             id,
@@ -480,6 +490,8 @@ impl Wait for PublisherBuilder<'_, '_> {
             #[cfg(feature = "unstable")]
             matching_listeners: Default::default(),
             undeclare_on_drop: true,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            stats,
         })
     }
 }
@@ -510,6 +522,8 @@ impl Wait for PublicationBuilder<&Publisher<'_>, PublicationBuilderPut> {
             #[cfg(feature = "unstable")]
             self.source_info,
             self.attachment,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            Some(&self.publisher.stats),
         )
     }
 }
@@ -531,6 +545,8 @@ impl Wait for PublicationBuilder<&Publisher<'_>, PublicationBuilderDelete> {
             #[cfg(feature = "unstable")]
             self.source_info,
             self.attachment,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            Some(&self.publisher.stats),
         )
     }
 }
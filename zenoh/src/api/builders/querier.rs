@@ -26,7 +26,7 @@ use zenoh_result::ZResult;
 
 use super::sample::QoSBuilderTrait;
 #[cfg(feature = "unstable")]
-use crate::api::query::ReplyKeyExpr;
+use crate::api::query::{CancellationToken, ReplyKeyExpr};
 #[cfg(feature = "unstable")]
 use crate::api::sample::SourceInfo;
 #[cfg(feature = "unstable")]
@@ -226,6 +226,8 @@ pub struct QuerierGetBuilder<'a, 'b, Handler> {
     pub(crate) attachment: Option<ZBytes>,
     #[cfg(feature = "unstable")]
     pub(crate) source_info: SourceInfo,
+    #[cfg(feature = "unstable")]
+    pub(crate) cancellation_token: Option<CancellationToken>,
 }
 
 #[zenoh_macros::internal_trait]
@@ -361,6 +363,8 @@ impl<'a, 'b> QuerierGetBuilder<'a, 'b, DefaultHandler> {
             attachment,
             #[cfg(feature = "unstable")]
             source_info,
+            #[cfg(feature = "unstable")]
+            cancellation_token,
             handler: _,
         } = self;
         QuerierGetBuilder {
@@ -370,6 +374,8 @@ impl<'a, 'b> QuerierGetBuilder<'a, 'b, DefaultHandler> {
             attachment,
             #[cfg(feature = "unstable")]
             source_info,
+            #[cfg(feature = "unstable")]
+            cancellation_token,
             handler,
         }
     }
@@ -398,6 +404,16 @@ impl<'b, Handler> QuerierGetBuilder<'_, 'b, Handler> {
         self.parameters = parameters.into();
         self
     }
+
+    /// Set a [`CancellationToken`] to end the query before its timeout.
+    #[inline]
+    #[zenoh_macros::unstable]
+    pub fn cancellation_token(self, token: CancellationToken) -> Self {
+        Self {
+            cancellation_token: Some(token),
+            ..self
+        }
+    }
 }
 
 impl<Handler> Resolvable for QuerierGetBuilder<'_, '_, Handler>
@@ -437,6 +453,8 @@ where
                 self.attachment,
                 #[cfg(feature = "unstable")]
                 self.source_info,
+                #[cfg(feature = "unstable")]
+                self.cancellation_token,
                 callback,
             )
             .map(|_| receiver)
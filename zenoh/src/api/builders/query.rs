@@ -22,7 +22,7 @@ use zenoh_protocol::{core::CongestionControl, network::request::ext::QueryTarget
 use zenoh_result::ZResult;
 
 #[cfg(feature = "unstable")]
-use crate::api::query::ReplyKeyExpr;
+use crate::api::query::{CancellationToken, ReplyKeyExpr};
 #[cfg(feature = "unstable")]
 use crate::api::{sample::SourceInfo, selector::ZenohParameters};
 use crate::{
@@ -75,6 +75,8 @@ pub struct SessionGetBuilder<'a, 'b, Handler> {
     pub(crate) attachment: Option<ZBytes>,
     #[cfg(feature = "unstable")]
     pub(crate) source_info: SourceInfo,
+    #[cfg(feature = "unstable")]
+    pub(crate) cancellation_token: Option<CancellationToken>,
 }
 
 #[zenoh_macros::internal_trait]
@@ -212,6 +214,8 @@ impl<'a, 'b> SessionGetBuilder<'a, 'b, DefaultHandler> {
             attachment,
             #[cfg(feature = "unstable")]
             source_info,
+            #[cfg(feature = "unstable")]
+            cancellation_token,
             handler: _,
         } = self;
         SessionGetBuilder {
@@ -226,6 +230,8 @@ impl<'a, 'b> SessionGetBuilder<'a, 'b, DefaultHandler> {
             attachment,
             #[cfg(feature = "unstable")]
             source_info,
+            #[cfg(feature = "unstable")]
+            cancellation_token,
             handler,
         }
     }
@@ -276,6 +282,16 @@ impl<Handler> SessionGetBuilder<'_, '_, Handler> {
         Self { timeout, ..self }
     }
 
+    /// Set a [`CancellationToken`] to end the query before its timeout.
+    #[zenoh_macros::unstable]
+    #[inline]
+    pub fn cancellation_token(self, token: CancellationToken) -> Self {
+        Self {
+            cancellation_token: Some(token),
+            ..self
+        }
+    }
+
     ///
     ///
     /// By default, `get` guarantees that it will only receive replies whose key expressions intersect
@@ -336,6 +352,8 @@ where
                 self.attachment,
                 #[cfg(feature = "unstable")]
                 self.source_info,
+                #[cfg(feature = "unstable")]
+                self.cancellation_token,
                 callback,
             )
             .map(|_| receiver)
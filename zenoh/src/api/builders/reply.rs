@@ -252,6 +252,9 @@ impl Resolvable for ReplyErrBuilder<'_> {
 
 impl Wait for ReplyErrBuilder<'_> {
     fn wait(self) -> <Self as Resolvable>::To {
+        if self.query.inner.cancellation_token.is_cancelled() {
+            return Ok(());
+        }
         self.query.inner.primitives.send_response(Response {
             rid: self.query.inner.qid,
             wire_expr: WireExpr {
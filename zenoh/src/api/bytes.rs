@@ -235,7 +235,7 @@ const _: () = {
 #[derive(Debug)]
 pub struct ZBytesReader<'a>(ZBufReader<'a>);
 
-impl ZBytesReader<'_> {
+impl<'a> ZBytesReader<'a> {
     /// Returns the number of bytes that can still be read
     pub fn remaining(&self) -> usize {
         self.0.remaining()
@@ -245,6 +245,25 @@ impl ZBytesReader<'_> {
     pub fn is_empty(&self) -> bool {
         self.remaining() == 0
     }
+
+    /// Reads the next `len` bytes without copying them, which is possible only if they are
+    /// contiguous in memory, e.g. in the same network or shared memory buffer.
+    /// Nothing is read and `None` is returned otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use zenoh::bytes::ZBytes;
+    ///
+    /// let zbytes = ZBytes::from(vec![1u8, 2, 3]);
+    /// let mut reader = zbytes.reader();
+    /// assert_eq!(reader.read_contiguous(2), Some([1u8, 2].as_slice()));
+    /// assert_eq!(reader.read_contiguous(2), None);
+    /// assert_eq!(reader.read_contiguous(1), Some([3u8].as_slice()));
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn read_contiguous(&mut self, len: usize) -> Option<&'a [u8]> {
+        self.0.read_contiguous(len)
+    }
 }
 
 impl std::io::Read for ZBytesReader<'_> {
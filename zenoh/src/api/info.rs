@@ -13,6 +13,8 @@
 //
 
 //! Tools to access information about the current zenoh [`Session`](crate::Session).
+#[cfg(all(feature = "stats", feature = "unstable"))]
+use crate::api::{builders::info::SessionStatsBuilder, session::WeakSession};
 use crate::{
     api::builders::info::{PeersZenohIdBuilder, RoutersZenohIdBuilder, ZenohIdBuilder},
     net::runtime::Runtime,
@@ -32,6 +34,8 @@ use crate::{
 /// ```
 pub struct SessionInfo {
     pub(crate) runtime: Runtime,
+    #[cfg(all(feature = "stats", feature = "unstable"))]
+    pub(crate) session: WeakSession,
 }
 
 impl SessionInfo {
@@ -82,4 +86,23 @@ impl SessionInfo {
     pub fn peers_zid(&self) -> PeersZenohIdBuilder<'_> {
         PeersZenohIdBuilder::new(&self.runtime)
     }
+
+    /// Return the statistics of the current zenoh [`Session`](crate::Session): the ones of its
+    /// transports, both per transport and aggregated, and the ones of its publishers and subscribers.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    ///
+    /// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+    /// let stats = session.info().stats().await;
+    /// println!("{:?}", stats.total());
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> SessionStatsBuilder<'_> {
+        SessionStatsBuilder::new(&self.session)
+    }
 }
@@ -38,4 +38,6 @@ pub(crate) mod sample;
 pub(crate) mod scouting;
 pub(crate) mod selector;
 pub(crate) mod session;
+#[cfg(all(feature = "stats", feature = "unstable"))]
+pub(crate) mod stats;
 pub(crate) mod subscriber;
@@ -40,6 +40,8 @@ use {
     zenoh_protocol::core::Reliability,
 };
 
+#[cfg(all(feature = "stats", feature = "unstable"))]
+use crate::api::stats::EntityCounters;
 use crate::api::{
     builders::publisher::{
         PublicationBuilder, PublicationBuilderDelete, PublicationBuilderPut,
@@ -58,6 +60,8 @@ pub(crate) struct PublisherState {
     pub(crate) remote_id: Id,
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) destination: Locality,
+    #[cfg(all(feature = "stats", feature = "unstable"))]
+    pub(crate) stats: std::sync::Arc<EntityCounters>,
 }
 
 impl fmt::Debug for PublisherState {
@@ -113,6 +117,8 @@ pub struct Publisher<'a> {
     #[cfg(feature = "unstable")]
     pub(crate) matching_listeners: Arc<Mutex<HashSet<Id>>>,
     pub(crate) undeclare_on_drop: bool,
+    #[cfg(all(feature = "stats", feature = "unstable"))]
+    pub(crate) stats: std::sync::Arc<EntityCounters>,
 }
 
 impl<'a> Publisher<'a> {
@@ -405,6 +411,8 @@ impl Sink<Sample> for Publisher<'_> {
             #[cfg(feature = "unstable")]
             SourceInfo::empty(),
             attachment,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            Some(&self.stats),
         )
     }
 
@@ -165,6 +165,8 @@ impl<'a> Querier<'a> {
             querier: self,
             #[cfg(feature = "unstable")]
             source_info: SourceInfo::empty(),
+            #[cfg(feature = "unstable")]
+            cancellation_token: None,
             value: None,
             attachment: None,
             parameters: Parameters::empty(),
@@ -12,6 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+#[cfg(feature = "unstable")]
+use std::future::Future;
 use std::{collections::HashMap, error::Error, fmt::Display};
 
 #[cfg(feature = "unstable")]
@@ -26,7 +28,11 @@ pub use zenoh_protocol::network::request::ext::QueryTarget;
 pub use zenoh_protocol::zenoh::query::ConsolidationMode;
 
 use crate::api::{
-    bytes::ZBytes, encoding::Encoding, handlers::Callback, key_expr::KeyExpr, sample::Sample,
+    bytes::ZBytes,
+    encoding::Encoding,
+    handlers::Callback,
+    key_expr::KeyExpr,
+    sample::{Locality, Sample},
     selector::Selector,
 };
 
@@ -161,6 +167,7 @@ pub(crate) struct QueryState {
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) parameters: Parameters<'static>,
     pub(crate) reception_mode: ConsolidationMode,
+    pub(crate) destination: Locality,
     pub(crate) replies: Option<HashMap<OwnedKeyExpr, Reply>>,
     pub(crate) callback: Callback<Reply>,
 }
@@ -180,3 +187,50 @@ pub enum ReplyKeyExpr {
     /// Accept replies whose key expressions match the query key expression.
     MatchingQuery,
 }
+
+/// A token to cancel a [`get`](crate::Session::get) before its timeout.
+///
+/// Cancelling the token closes the reply handler of the query, frees the pending query state in
+/// the routers and signals the cancellation to the queryables through [`Query::cancelled`](crate::query::Query::cancelled).
+/// A token can be shared by several queries.
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+/// use zenoh::query::CancellationToken;
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let token = CancellationToken::default();
+/// let replies = session
+///     .get("key/expression")
+///     .cancellation_token(token.clone())
+///     .await
+///     .unwrap();
+/// token.cancel();
+/// while let Ok(reply) = replies.recv_async().await {
+///     println!("Received {:?}", reply.result())
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(pub(crate) tokio_util::sync::CancellationToken);
+
+#[zenoh_macros::unstable]
+impl CancellationToken {
+    /// Cancels the queries associated with this token.
+    pub fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    /// Returns `true` if this token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Waits until this token is cancelled.
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.0.cancelled()
+    }
+}
@@ -11,15 +11,19 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+#[cfg(feature = "unstable")]
+use std::future::Future;
 use std::{
+    collections::HashMap,
     fmt,
     future::{IntoFuture, Ready},
     ops::{Deref, DerefMut},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
+use tokio_util::sync::CancellationToken;
 use tracing::error;
-use zenoh_core::{Resolvable, Resolve, Wait};
+use zenoh_core::{zlock, Resolvable, Resolve, Wait};
 use zenoh_protocol::{
     core::{EntityId, Parameters, WireExpr, ZenohIdProto},
     network::{response, Mapping, RequestId, Response, ResponseFinal},
@@ -49,21 +53,35 @@ use crate::{
     net::primitives::Primitives,
 };
 
+/// The cancellation tokens of the queries a session is replying to, indexed by
+/// locality (`true` for session local queries) and request id.
+pub(crate) type IncomingQueries = Arc<Mutex<HashMap<(bool, RequestId), CancellationToken>>>;
+
 pub(crate) struct QueryInner {
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) parameters: Parameters<'static>,
     pub(crate) qid: RequestId,
     pub(crate) zid: ZenohIdProto,
     pub(crate) primitives: Arc<dyn Primitives>,
+    pub(crate) local: bool,
+    pub(crate) cancellation_token: CancellationToken,
+    pub(crate) incoming_queries: Option<IncomingQueries>,
 }
 
 impl Drop for QueryInner {
     fn drop(&mut self) {
-        self.primitives.send_response_final(ResponseFinal {
-            rid: self.qid,
-            ext_qos: response::ext::QoSType::RESPONSE_FINAL,
-            ext_tstamp: None,
-        });
+        if let Some(incoming_queries) = self.incoming_queries.as_ref() {
+            zlock!(incoming_queries).remove(&(self.local, self.qid));
+        }
+        // A cancelled query was already finalized by the querier
+        if !self.cancellation_token.is_cancelled() {
+            self.primitives.send_response_final(ResponseFinal {
+                rid: self.qid,
+                ext_qos: response::ext::QoSType::RESPONSE_FINAL,
+                ext_tstamp: None,
+                ext_cancel: None,
+            });
+        }
     }
 }
 
@@ -123,6 +141,37 @@ impl Query {
         self.attachment.as_mut()
     }
 
+    /// Returns `true` if the querier cancelled this Query.
+    ///
+    /// Replies to a cancelled Query are discarded.
+    #[zenoh_macros::unstable]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancellation_token.is_cancelled()
+    }
+
+    /// Waits until the querier cancels this Query.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+    /// let queryable = session.declare_queryable("key/expression").await.unwrap();
+    /// while let Ok(query) = queryable.recv_async().await {
+    ///     tokio::select! {
+    ///         _ = query.cancelled() => println!("Query cancelled"),
+    ///         _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {
+    ///             query.reply("key/expression", "value").await.unwrap();
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[zenoh_macros::unstable]
+    pub fn cancelled(&self) -> impl Future<Output = ()> + Send + '_ {
+        self.inner.cancellation_token.cancelled()
+    }
+
     /// Sends a reply in the form of [`Sample`] to this Query.
     ///
     /// By default, queries only accept replies whose key expression intersects with the query's.
@@ -251,6 +300,9 @@ impl IntoFuture for ReplySample<'_> {
 
 impl Query {
     pub(crate) fn _reply_sample(&self, sample: Sample) -> ZResult<()> {
+        if self.inner.cancellation_token.is_cancelled() {
+            return Ok(());
+        }
         let c = zcondfeat!(
             "unstable",
             !self._accepts_any_replies().unwrap_or(false),
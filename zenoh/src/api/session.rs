@@ -34,7 +34,7 @@ use uhlc::HLC;
 use zenoh_buffers::ZBuf;
 use zenoh_collections::SingleOrVec;
 use zenoh_config::{qos::PublisherQoSConfig, unwrap_or_default, wrappers::ZenohId};
-use zenoh_core::{zconfigurable, zread, Resolve, ResolveClosure, ResolveFuture, Wait};
+use zenoh_core::{zconfigurable, zlock, zread, Resolve, ResolveClosure, ResolveFuture, Wait};
 use zenoh_keyexpr::keyexpr_tree::KeBoxTree;
 #[cfg(feature = "unstable")]
 use zenoh_protocol::network::declare::SubscriberId;
@@ -53,8 +53,8 @@ use zenoh_protocol::{
         },
         ext,
         interest::{InterestId, InterestMode, InterestOptions},
-        push, request, AtomicRequestId, DeclareFinal, Interest, Mapping, Push, Request, RequestId,
-        Response, ResponseFinal,
+        push, request, response, AtomicRequestId, DeclareFinal, Interest, Mapping, Push, Request,
+        RequestId, Response, ResponseFinal,
     },
     zenoh::{
         query::{self, ext::QueryBodyType},
@@ -75,7 +75,7 @@ use crate::api::{
     builders::querier::QuerierBuilder,
     matching::{MatchingListenerState, MatchingStatus, MatchingStatusType},
     querier::QuerierState,
-    query::ReplyKeyExpr,
+    query::{CancellationToken, ReplyKeyExpr},
     sample::SourceInfo,
 };
 use crate::{
@@ -102,7 +102,7 @@ use crate::{
             ConsolidationMode, LivelinessQueryState, QueryConsolidation, QueryState, QueryTarget,
             Reply,
         },
-        queryable::{Query, QueryInner, QueryableState},
+        queryable::{IncomingQueries, Query, QueryInner, QueryableState},
         sample::{DataInfo, DataInfoIntoSample, Locality, QoS, Sample, SampleKind},
         selector::Selector,
         subscriber::{SubscriberKind, SubscriberState},
@@ -351,6 +351,8 @@ impl SessionState {
             key_expr: key_expr.clone().into_owned(),
             origin,
             callback,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            stats: Default::default(),
         };
 
         let declared_sub = origin != Locality::SessionLocal;
@@ -528,6 +530,7 @@ pub(crate) struct SessionInner {
     pub(crate) id: u16,
     owns_runtime: bool,
     task_controller: TaskController,
+    incoming_queries: IncomingQueries,
 }
 
 impl fmt::Debug for SessionInner {
@@ -689,6 +692,7 @@ impl Session {
                 id: SESSION_ID_COUNTER.fetch_add(1, Ordering::SeqCst),
                 owns_runtime,
                 task_controller: TaskController::default(),
+                incoming_queries: IncomingQueries::default(),
             }));
 
             runtime.new_handler(Arc::new(admin::Handler::new(session.downgrade())));
@@ -850,6 +854,8 @@ impl Session {
     pub fn info(&self) -> SessionInfo {
         SessionInfo {
             runtime: self.0.runtime.clone(),
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            session: self.downgrade(),
         }
     }
 
@@ -1217,6 +1223,8 @@ impl Session {
             handler: DefaultHandler::default(),
             #[cfg(feature = "unstable")]
             source_info: SourceInfo::empty(),
+            #[cfg(feature = "unstable")]
+            cancellation_token: None,
         }
     }
 }
@@ -1312,6 +1320,9 @@ impl SessionInner {
         &self,
         key_expr: KeyExpr,
         destination: Locality,
+        #[cfg(all(feature = "stats", feature = "unstable"))] stats: Arc<
+            crate::api::stats::EntityCounters,
+        >,
     ) -> ZResult<EntityId> {
         let mut state = zwrite!(self.state);
         tracing::trace!("declare_publisher({:?})", key_expr);
@@ -1322,6 +1333,8 @@ impl SessionInner {
             remote_id: id,
             key_expr: key_expr.clone().into_owned(),
             destination,
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            stats,
         };
 
         let declared_pub = (destination != Locality::SessionLocal)
@@ -1760,6 +1773,8 @@ impl SessionInner {
             key_expr: key_expr.clone().into_owned(),
             origin,
             callback: callback.clone(),
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            stats: Default::default(),
         };
 
         let sub_state = Arc::new(sub_state);
@@ -2063,6 +2078,8 @@ impl SessionInner {
         attachment: Option<ZBytes>,
     ) {
         let mut callbacks = SingleOrVec::default();
+        #[cfg(all(feature = "stats", feature = "unstable"))]
+        let payload_len = zenoh_buffers::buffer::Buffer::len(&payload);
         let state = zread!(self.state);
         if state.primitives.is_none() {
             return; // Session closing or closed
@@ -2074,6 +2091,8 @@ impl SessionInner {
                         if sub.origin == Locality::Any
                             || (local == (sub.origin == Locality::SessionLocal))
                         {
+                            #[cfg(all(feature = "stats", feature = "unstable"))]
+                            sub.stats.inc(payload_len);
                             callbacks.push((sub.callback.clone(), res.key_expr.clone().into()));
                         }
                     }
@@ -2098,6 +2117,8 @@ impl SessionInner {
                             || (local == (sub.origin == Locality::SessionLocal)))
                             && key_expr.intersects(&sub.key_expr)
                         {
+                            #[cfg(all(feature = "stats", feature = "unstable"))]
+                            sub.stats.inc(payload_len);
                             callbacks.push((sub.callback.clone(), key_expr.clone().into_owned()));
                         }
                     }
@@ -2143,13 +2164,20 @@ impl SessionInner {
         timestamp: Option<uhlc::Timestamp>,
         #[cfg(feature = "unstable")] source_info: SourceInfo,
         attachment: Option<ZBytes>,
+        #[cfg(all(feature = "stats", feature = "unstable"))] stats: Option<
+            &crate::api::stats::EntityCounters,
+        >,
     ) -> ZResult<()> {
         trace!("write({:?}, [...])", key_expr);
         let primitives = zread!(self.state).primitives()?;
+        #[cfg(all(feature = "stats", feature = "unstable"))]
+        if let Some(stats) = stats {
+            stats.inc(payload.len());
+        }
         let timestamp = timestamp.or_else(|| self.runtime.new_timestamp());
         let wire_expr = key_expr.to_wire(self);
         if destination != Locality::SessionLocal {
-            primitives.send_push(
+            let _scheduled = primitives.route_push(
                 Push {
                     wire_expr: wire_expr.to_owned(),
                     ext_qos: push::ext::QoSType::new(
@@ -2189,6 +2217,10 @@ impl SessionInner {
                 #[cfg(not(feature = "unstable"))]
                 Reliability::DEFAULT,
             );
+            #[cfg(all(feature = "stats", feature = "unstable"))]
+            if let (false, Some(stats)) = (_scheduled, stats) {
+                stats.inc_dropped();
+            }
         }
         if destination != Locality::Remote {
             let data_info = DataInfo {
@@ -2237,6 +2269,7 @@ impl SessionInner {
         value: Option<(ZBytes, Encoding)>,
         attachment: Option<ZBytes>,
         #[cfg(feature = "unstable")] source: SourceInfo,
+        #[cfg(feature = "unstable")] cancellation_token: Option<CancellationToken>,
         callback: Callback<Reply>,
     ) -> ZResult<()> {
         tracing::trace!(
@@ -2259,11 +2292,21 @@ impl SessionInner {
         };
 
         let token = self.task_controller.get_cancellation_token();
+        #[cfg(feature = "unstable")]
+        let cancelled = async move {
+            match cancellation_token {
+                Some(cancellation_token) => cancellation_token.cancelled().await,
+                None => std::future::pending().await,
+            }
+        };
+        #[cfg(not(feature = "unstable"))]
+        let cancelled = std::future::pending::<()>();
         self.task_controller
             .spawn_with_rt(zenoh_runtime::ZRuntime::Net, {
                 let session = WeakSession::new(self);
                 async move {
                     tokio::select! {
+                        _ = cancelled => session.cancel_query(qid),
                         _ = tokio::time::sleep(timeout) => {
                             let mut state = zwrite!(session.state);
                             if let Some(query) = state.queries.remove(&qid) {
@@ -2295,6 +2338,7 @@ impl SessionInner {
                 key_expr: key_expr.clone().into_owned(),
                 parameters: parameters.clone().into_owned(),
                 reception_mode: consolidation,
+                destination,
                 replies: (consolidation != ConsolidationMode::None).then(HashMap::new),
                 callback,
             },
@@ -2406,6 +2450,37 @@ impl SessionInner {
         Ok(())
     }
 
+    /// Ends a pending query early, closing its callback and notifying the queryables.
+    fn cancel_query(self: &Arc<Self>, qid: RequestId) {
+        let mut state = zwrite!(self.state);
+        let Some(query) = state.queries.remove(&qid) else {
+            return;
+        };
+        let primitives = state.primitives.clone();
+        drop(state);
+        tracing::debug!("Cancel query {}", qid);
+        if query.destination != Locality::SessionLocal {
+            if let Some(primitives) = primitives {
+                primitives.send_response_final(ResponseFinal {
+                    rid: qid,
+                    ext_qos: response::ext::QoSType::RESPONSE_FINAL,
+                    ext_tstamp: None,
+                    ext_cancel: Some(response::ext::Cancel::new()),
+                });
+            }
+        }
+        if query.destination != Locality::Remote {
+            self.cancel_incoming_query(true, qid);
+        }
+    }
+
+    fn cancel_incoming_query(&self, local: bool, qid: RequestId) {
+        if let Some(token) = zlock!(self.incoming_queries).remove(&(local, qid)) {
+            tracing::debug!("Query {} cancelled by the querier", qid);
+            token.cancel();
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn handle_query(
         self: &Arc<Self>,
@@ -2462,6 +2537,8 @@ impl SessionInner {
 
         let zid = self.zid();
 
+        let cancellation_token = tokio_util::sync::CancellationToken::new();
+        zlock!(self.incoming_queries).insert((local, qid), cancellation_token.clone());
         let query_inner = Arc::new(QueryInner {
             key_expr,
             parameters: parameters.to_owned().into(),
@@ -2472,6 +2549,9 @@ impl SessionInner {
             } else {
                 primitives
             },
+            local,
+            cancellation_token,
+            incoming_queries: Some(self.incoming_queries.clone()),
         });
         let mut query = Query {
             inner: query_inner,
@@ -3019,6 +3099,10 @@ impl Primitives for WeakSession {
 
     fn send_response_final(&self, msg: ResponseFinal) {
         trace!("recv ResponseFinal {:?}", msg);
+        if msg.ext_cancel.is_some() {
+            self.cancel_incoming_query(false, msg.rid);
+            return;
+        }
         let mut state = zwrite!(self.state);
         if state.primitives.is_none() {
             return; // Session closing or closed
@@ -3060,8 +3144,9 @@ impl crate::net::primitives::EPrimitives for WeakSession {
     }
 
     #[inline]
-    fn send_push(&self, msg: Push, reliability: Reliability) {
-        (self as &dyn Primitives).send_push(msg, reliability)
+    fn send_push(&self, msg: Push, reliability: Reliability) -> bool {
+        (self as &dyn Primitives).send_push(msg, reliability);
+        true
     }
 
     #[inline]
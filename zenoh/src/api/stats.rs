@@ -0,0 +1,251 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Statistics of a zenoh [`Session`](crate::Session).
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use zenoh_config::wrappers::{EntityGlobalId, ZenohId};
+use zenoh_protocol::core::{EntityGlobalIdProto, WhatAmI};
+use zenoh_transport::stats::TransportStatsReport;
+
+use crate::api::{
+    key_expr::KeyExpr, publisher::PublisherState, session::SessionInner,
+    subscriber::SubscriberState, Id,
+};
+
+/// The counters of a publisher or a subscriber.
+#[derive(Debug, Default)]
+pub(crate) struct EntityCounters {
+    samples: AtomicUsize,
+    bytes: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+impl EntityCounters {
+    pub(crate) fn inc(&self, bytes: usize) {
+        self.samples.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn inc_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Statistics of a zenoh [`Session`](crate::Session), returned by
+/// [`SessionInfo::stats()`](crate::session::SessionInfo::stats).
+///
+/// # Examples
+/// ```
+/// # #[tokio::main]
+/// # async fn main() {
+///
+/// let session = zenoh::open(zenoh::Config::default()).await.unwrap();
+/// let stats = session.info().stats().await;
+/// for transport in stats.transports() {
+///     println!("{}: {:?}", transport.zid(), transport.report());
+/// }
+/// # }
+/// ```
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub struct SessionStats {
+    total: TransportStatsReport,
+    transports: Vec<TransportStats>,
+    publishers: Vec<PublisherStats>,
+    subscribers: Vec<SubscriberStats>,
+}
+
+#[zenoh_macros::unstable]
+impl SessionStats {
+    pub(crate) fn new(session: &SessionInner) -> Self {
+        let manager = session.runtime.manager();
+        let transports = zenoh_runtime::ZRuntime::Application
+            .block_in_place(manager.get_transports_unicast())
+            .into_iter()
+            .filter_map(|t| {
+                Some(TransportStats {
+                    zid: t.get_zid().ok()?.into(),
+                    whatami: t.get_whatami().ok()?,
+                    report: t.get_stats().ok()?.report(),
+                })
+            })
+            .collect();
+        let state = zread!(session.state);
+        Self {
+            total: manager.get_stats().report(),
+            transports,
+            publishers: state
+                .publishers
+                .values()
+                .map(|p| PublisherStats::new(session, p))
+                .collect(),
+            subscribers: state
+                .subscribers
+                .values()
+                .map(|s| SubscriberStats::new(session, s))
+                .collect(),
+        }
+    }
+
+    /// Returns the statistics aggregated over all the transports of the session.
+    pub fn total(&self) -> &TransportStatsReport {
+        &self.total
+    }
+
+    /// Returns the statistics of each unicast transport of the session.
+    pub fn transports(&self) -> &[TransportStats] {
+        &self.transports
+    }
+
+    /// Returns the statistics of each publisher declared on the session.
+    pub fn publishers(&self) -> &[PublisherStats] {
+        &self.publishers
+    }
+
+    /// Returns the statistics of each subscriber declared on the session.
+    pub fn subscribers(&self) -> &[SubscriberStats] {
+        &self.subscribers
+    }
+}
+
+/// Statistics of a unicast transport of a zenoh [`Session`](crate::Session).
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub struct TransportStats {
+    zid: ZenohId,
+    whatami: WhatAmI,
+    report: TransportStatsReport,
+}
+
+#[zenoh_macros::unstable]
+impl TransportStats {
+    /// Returns the [`ZenohId`] of the remote node.
+    pub fn zid(&self) -> ZenohId {
+        self.zid
+    }
+
+    /// Returns the [`WhatAmI`] of the remote node.
+    pub fn whatami(&self) -> WhatAmI {
+        self.whatami
+    }
+
+    /// Returns the statistics of the transport.
+    pub fn report(&self) -> &TransportStatsReport {
+        &self.report
+    }
+}
+
+/// Statistics of a [`Publisher`](crate::pubsub::Publisher).
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub struct PublisherStats {
+    id: EntityGlobalId,
+    key_expr: KeyExpr<'static>,
+    samples_sent: usize,
+    bytes_sent: usize,
+    samples_dropped: usize,
+}
+
+#[zenoh_macros::unstable]
+impl PublisherStats {
+    fn new(session: &SessionInner, state: &PublisherState) -> Self {
+        Self {
+            id: entity_global_id(session, state.id),
+            key_expr: state.key_expr.clone(),
+            samples_sent: state.stats.samples.load(Ordering::Relaxed),
+            bytes_sent: state.stats.bytes.load(Ordering::Relaxed),
+            samples_dropped: state.stats.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the [`EntityGlobalId`] of the publisher.
+    pub fn id(&self) -> EntityGlobalId {
+        self.id
+    }
+
+    /// Returns the key expression of the publisher.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    /// Returns the number of samples put or deleted through the publisher.
+    pub fn samples_sent(&self) -> usize {
+        self.samples_sent
+    }
+
+    /// Returns the number of payload bytes put through the publisher.
+    pub fn bytes_sent(&self) -> usize {
+        self.bytes_sent
+    }
+
+    /// Returns the number of samples of the publisher dropped by the transports
+    /// because of congestion control.
+    ///
+    /// A sample dropped by several of the transports it was routed to is counted once.
+    pub fn samples_dropped(&self) -> usize {
+        self.samples_dropped
+    }
+}
+
+/// Statistics of a [`Subscriber`](crate::pubsub::Subscriber).
+#[zenoh_macros::unstable]
+#[derive(Clone, Debug)]
+pub struct SubscriberStats {
+    id: EntityGlobalId,
+    key_expr: KeyExpr<'static>,
+    samples_received: usize,
+    bytes_received: usize,
+}
+
+#[zenoh_macros::unstable]
+impl SubscriberStats {
+    fn new(session: &SessionInner, state: &SubscriberState) -> Self {
+        Self {
+            id: entity_global_id(session, state.id),
+            key_expr: state.key_expr.clone(),
+            samples_received: state.stats.samples.load(Ordering::Relaxed),
+            bytes_received: state.stats.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the [`EntityGlobalId`] of the subscriber.
+    pub fn id(&self) -> EntityGlobalId {
+        self.id
+    }
+
+    /// Returns the key expression of the subscriber.
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        &self.key_expr
+    }
+
+    /// Returns the number of samples delivered to the subscriber.
+    pub fn samples_received(&self) -> usize {
+        self.samples_received
+    }
+
+    /// Returns the number of payload bytes delivered to the subscriber.
+    pub fn bytes_received(&self) -> usize {
+        self.bytes_received
+    }
+}
+
+#[zenoh_macros::unstable]
+fn entity_global_id(session: &SessionInner, eid: Id) -> EntityGlobalId {
+    EntityGlobalIdProto {
+        zid: session.runtime.zid().into(),
+        eid,
+    }
+    .into()
+}
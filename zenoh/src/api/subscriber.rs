@@ -37,6 +37,8 @@ pub(crate) struct SubscriberState {
     pub(crate) key_expr: KeyExpr<'static>,
     pub(crate) origin: Locality,
     pub(crate) callback: Callback<Sample>,
+    #[cfg(all(feature = "stats", feature = "unstable"))]
+    pub(crate) stats: crate::api::stats::EntityCounters,
 }
 
 impl fmt::Debug for SubscriberState {
@@ -219,6 +219,21 @@ pub mod session {
     };
 }
 
+/// Statistics of a [`Session`], its transports, publishers and subscribers
+///
+/// The statistics are collected when the `stats` and `unstable` features are enabled and are
+/// accessed through [`SessionInfo::stats()`](crate::session::SessionInfo::stats).
+#[zenoh_macros::unstable]
+#[cfg(feature = "stats")]
+pub mod stats {
+    pub use zenoh_transport::stats::TransportStatsReport;
+
+    pub use crate::api::{
+        builders::info::SessionStatsBuilder,
+        stats::{PublisherStats, SessionStats, SubscriberStats, TransportStats},
+    };
+}
+
 /// Sample primitives
 ///
 /// The [`Sample`](crate::sample::Sample) structure is the data unit received from [`Subscriber`](crate::pubsub::Subscriber)
@@ -294,7 +309,7 @@ pub mod query {
     pub use crate::api::{
         builders::querier::{QuerierBuilder, QuerierGetBuilder},
         querier::Querier,
-        query::ReplyKeyExpr,
+        query::{CancellationToken, ReplyKeyExpr},
         selector::ZenohParameters,
     };
     pub use crate::api::{
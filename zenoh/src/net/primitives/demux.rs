@@ -14,14 +14,17 @@
 use std::{any::Any, sync::Arc};
 
 use zenoh_link::Link;
-use zenoh_protocol::network::{NetworkBody, NetworkMessage};
+use zenoh_protocol::{
+    core::Reliability,
+    network::{NetworkBody, NetworkMessage},
+};
 use zenoh_result::ZResult;
 use zenoh_transport::{unicast::TransportUnicast, TransportPeerEventHandler};
 
 use super::Primitives;
 use crate::net::routing::{
     dispatcher::face::Face,
-    interceptor::{InterceptorTrait, InterceptorsChain},
+    interceptor::{DelayQueue, InterceptorTrait, InterceptorsChain},
     RoutingContext,
 };
 
@@ -29,6 +32,7 @@ pub struct DeMux {
     face: Face,
     pub(crate) transport: Option<TransportUnicast>,
     pub(crate) interceptor: Arc<InterceptorsChain>,
+    delayed: DelayQueue,
 }
 
 impl DeMux {
@@ -37,14 +41,32 @@ impl DeMux {
         transport: Option<TransportUnicast>,
         interceptor: Arc<InterceptorsChain>,
     ) -> Self {
+        let delayed = DelayQueue::new({
+            let face = face.clone();
+            move |msg: NetworkMessage| route(&face, msg.body, msg.reliability)
+        });
         Self {
             face,
             transport,
             interceptor,
+            delayed,
         }
     }
 }
 
+/// Routes a message received on `face`, except the OAM ones which are handled by the [DeMux].
+fn route(face: &Face, body: NetworkBody, reliability: Reliability) {
+    match body {
+        NetworkBody::Push(m) => face.send_push(m, reliability),
+        NetworkBody::Declare(m) => face.send_declare(m),
+        NetworkBody::Interest(m) => face.send_interest(m),
+        NetworkBody::Request(m) => face.send_request(m),
+        NetworkBody::Response(m) => face.send_response(m),
+        NetworkBody::ResponseFinal(m) => face.send_response_final(m),
+        NetworkBody::OAM(_) => tracing::error!("Unexpected OAM message routed on {face}"),
+    }
+}
+
 impl TransportPeerEventHandler for DeMux {
     #[inline]
     fn handle_message(&self, mut msg: NetworkMessage) -> ZResult<()> {
@@ -62,16 +84,14 @@ impl TransportPeerEventHandler for DeMux {
                 Some(ctx) => ctx,
                 None => return Ok(()),
             };
+            if let Some(deadline) = ctx.delayed_until {
+                self.delayed.push(deadline, ctx.msg);
+                return Ok(());
+            }
             msg = ctx.msg;
         }
 
         match msg.body {
-            NetworkBody::Push(m) => self.face.send_push(m, msg.reliability),
-            NetworkBody::Declare(m) => self.face.send_declare(m),
-            NetworkBody::Interest(m) => self.face.send_interest(m),
-            NetworkBody::Request(m) => self.face.send_request(m),
-            NetworkBody::Response(m) => self.face.send_response(m),
-            NetworkBody::ResponseFinal(m) => self.face.send_response_final(m),
             NetworkBody::OAM(m) => {
                 if let Some(transport) = self.transport.as_ref() {
                     let mut declares = vec![];
@@ -91,6 +111,7 @@ impl TransportPeerEventHandler for DeMux {
                     }
                 }
             }
+            body => route(&self.face, body, msg.reliability),
         }
 
         Ok(())
@@ -48,7 +48,8 @@ pub(crate) trait EPrimitives: Send + Sync {
 
     fn send_declare(&self, ctx: RoutingContext<Declare>);
 
-    fn send_push(&self, msg: Push, reliability: Reliability);
+    /// Sends a push message, returning `false` if the transport dropped it.
+    fn send_push(&self, msg: Push, reliability: Reliability) -> bool;
 
     fn send_request(&self, msg: Request);
 
@@ -81,7 +82,9 @@ impl EPrimitives for DummyPrimitives {
 
     fn send_declare(&self, _ctx: RoutingContext<Declare>) {}
 
-    fn send_push(&self, _msg: Push, _reliability: Reliability) {}
+    fn send_push(&self, _msg: Push, _reliability: Reliability) -> bool {
+        true
+    }
 
     fn send_request(&self, _msg: Request) {}
 
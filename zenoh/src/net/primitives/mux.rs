@@ -25,7 +25,7 @@ use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
 use super::EPrimitives;
 use crate::net::routing::{
     dispatcher::face::{Face, WeakFace},
-    interceptor::{InterceptorTrait, InterceptorsChain},
+    interceptor::{DelayQueue, InterceptorTrait, InterceptorsChain},
     RoutingContext,
 };
 
@@ -33,14 +33,35 @@ pub struct Mux {
     pub handler: TransportUnicast,
     pub(crate) face: OnceLock<WeakFace>,
     pub(crate) interceptor: InterceptorsChain,
+    delayed: DelayQueue,
 }
 
 impl Mux {
     pub(crate) fn new(handler: TransportUnicast, interceptor: InterceptorsChain) -> Mux {
+        let delayed = DelayQueue::new({
+            let handler = handler.clone();
+            move |msg| {
+                let _ = handler.schedule(msg);
+            }
+        });
         Mux {
             handler,
             face: OnceLock::new(),
             interceptor,
+            delayed,
+        }
+    }
+
+    /// Schedules a message on the transport, returning `false` if it was dropped.
+    ///
+    /// Delayed messages are considered as scheduled, as they are sent from another task.
+    fn schedule(&self, ctx: RoutingContext<NetworkMessage>) -> bool {
+        match ctx.delayed_until {
+            Some(deadline) => {
+                self.delayed.push(deadline, ctx.msg);
+                true
+            }
+            None => matches!(self.handler.schedule(ctx.msg), Ok(true)),
         }
     }
 }
@@ -58,6 +79,7 @@ impl EPrimitives for Mux {
             outface: ctx.outface,
             prefix: ctx.prefix,
             full_expr: ctx.full_expr,
+            delayed_until: ctx.delayed_until,
         };
         let prefix = ctx
             .wire_expr()
@@ -68,7 +90,7 @@ impl EPrimitives for Mux {
             .as_ref()
             .and_then(|p| p.get_egress_cache(ctx.outface.get().unwrap()));
         if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-            let _ = self.handler.schedule(ctx.msg);
+            self.schedule(ctx);
         }
     }
 
@@ -84,6 +106,7 @@ impl EPrimitives for Mux {
             outface: ctx.outface,
             prefix: ctx.prefix,
             full_expr: ctx.full_expr,
+            delayed_until: ctx.delayed_until,
         };
         let prefix = ctx
             .wire_expr()
@@ -94,11 +117,11 @@ impl EPrimitives for Mux {
             .as_ref()
             .and_then(|p| p.get_egress_cache(ctx.outface.get().unwrap()));
         if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-            let _ = self.handler.schedule(ctx.msg);
+            self.schedule(ctx);
         }
     }
 
-    fn send_push(&self, msg: Push, reliability: Reliability) {
+    fn send_push(&self, msg: Push, reliability: Reliability) -> bool {
         let msg = NetworkMessage {
             body: NetworkBody::Push(msg),
             reliability,
@@ -106,7 +129,7 @@ impl EPrimitives for Mux {
             size: None,
         };
         if self.interceptor.interceptors.is_empty() {
-            let _ = self.handler.schedule(msg);
+            matches!(self.handler.schedule(msg), Ok(true))
         } else if let Some(face) = self.face.get().and_then(|f| f.upgrade()) {
             let ctx = RoutingContext::new_out(msg, face.clone());
             let prefix = ctx
@@ -115,11 +138,14 @@ impl EPrimitives for Mux {
                 .flatten()
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
-            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+            match self.interceptor.intercept(ctx, cache) {
+                Some(ctx) => self.schedule(ctx),
+                // Messages filtered by the interceptors are not dropped by the transport
+                None => true,
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
+            false
         }
     }
 
@@ -141,7 +167,7 @@ impl EPrimitives for Mux {
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
             if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+                self.schedule(ctx);
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
@@ -166,7 +192,7 @@ impl EPrimitives for Mux {
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
             if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+                self.schedule(ctx);
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
@@ -191,7 +217,7 @@ impl EPrimitives for Mux {
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(&face));
             if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+                self.schedule(ctx);
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
@@ -232,6 +258,7 @@ impl EPrimitives for McastMux {
             outface: ctx.outface,
             prefix: ctx.prefix,
             full_expr: ctx.full_expr,
+            delayed_until: ctx.delayed_until,
         };
         let prefix = ctx
             .wire_expr()
@@ -258,6 +285,7 @@ impl EPrimitives for McastMux {
             outface: ctx.outface,
             prefix: ctx.prefix,
             full_expr: ctx.full_expr,
+            delayed_until: ctx.delayed_until,
         };
         let prefix = ctx
             .wire_expr()
@@ -272,7 +300,7 @@ impl EPrimitives for McastMux {
         }
     }
 
-    fn send_push(&self, msg: Push, reliability: Reliability) {
+    fn send_push(&self, msg: Push, reliability: Reliability) -> bool {
         let msg = NetworkMessage {
             body: NetworkBody::Push(msg),
             reliability,
@@ -280,7 +308,7 @@ impl EPrimitives for McastMux {
             size: None,
         };
         if self.interceptor.interceptors.is_empty() {
-            let _ = self.handler.schedule(msg);
+            matches!(self.handler.schedule(msg), Ok(true))
         } else if let Some(face) = self.face.get() {
             let ctx = RoutingContext::new_out(msg, face.clone());
             let prefix = ctx
@@ -289,11 +317,14 @@ impl EPrimitives for McastMux {
                 .flatten()
                 .cloned();
             let cache = prefix.as_ref().and_then(|p| p.get_egress_cache(face));
-            if let Some(ctx) = self.interceptor.intercept(ctx, cache) {
-                let _ = self.handler.schedule(ctx.msg);
+            match self.interceptor.intercept(ctx, cache) {
+                Some(ctx) => matches!(self.handler.schedule(ctx.msg), Ok(true)),
+                // Messages filtered by the interceptors are not dropped by the transport
+                None => true,
             }
         } else {
             tracing::error!("Uninitialized multiplexer!");
+            false
         }
     }
 
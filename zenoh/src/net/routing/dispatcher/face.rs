@@ -15,7 +15,7 @@ use std::{
     any::Any,
     collections::HashMap,
     fmt,
-    sync::{Arc, Weak},
+    sync::{Arc, Mutex, Weak},
     time::Duration,
 };
 
@@ -57,6 +57,8 @@ pub(crate) struct InterestState {
     pub(crate) finalized: bool,
 }
 
+pub(crate) type ForwardedQuery = (Weak<FaceState>, RequestId);
+
 pub struct FaceState {
     pub(crate) id: usize,
     pub(crate) zid: ZenohIdProto,
@@ -72,6 +74,11 @@ pub struct FaceState {
     pub(crate) remote_mappings: HashMap<ExprId, Arc<Resource>>,
     pub(crate) next_qid: RequestId,
     pub(crate) pending_queries: HashMap<RequestId, (Arc<Query>, CancellationToken)>,
+    // The queries received from this face, indexed by their id, with the faces and ids they
+    // were forwarded to
+    pub(crate) forwarded_queries: Mutex<HashMap<RequestId, Vec<ForwardedQuery>>>,
+    // Whether the peer decodes the cancellation of a query
+    pub(crate) query_cancellation: bool,
     pub(crate) mcast_group: Option<TransportMulticast>,
     pub(crate) in_interceptors: Option<Arc<InterceptorsChain>>,
     pub(crate) hat: Box<dyn Any + Send + Sync>,
@@ -89,6 +96,7 @@ impl FaceState {
         mcast_group: Option<TransportMulticast>,
         in_interceptors: Option<Arc<InterceptorsChain>>,
         hat: Box<dyn Any + Send + Sync>,
+        query_cancellation: bool,
     ) -> Arc<FaceState> {
         Arc::new(FaceState {
             id,
@@ -104,6 +112,8 @@ impl FaceState {
             remote_mappings: HashMap::new(),
             next_qid: 0,
             pending_queries: HashMap::new(),
+            forwarded_queries: Mutex::new(HashMap::new()),
+            query_cancellation,
             mcast_group,
             in_interceptors,
             hat,
@@ -207,6 +217,12 @@ pub struct Face {
 }
 
 impl Face {
+    /// Routes a push message, returning `false` if a transport dropped it.
+    #[inline]
+    pub(crate) fn route_push(&self, msg: Push, reliability: Reliability) -> bool {
+        route_data(&self.tables, &self.state, msg, reliability)
+    }
+
     pub fn downgrade(&self) -> WeakFace {
         WeakFace {
             tables: Arc::downgrade(&self.tables),
@@ -388,7 +404,7 @@ impl Primitives for Face {
 
     #[inline]
     fn send_push(&self, msg: Push, reliability: Reliability) {
-        route_data(&self.tables, &self.state, msg, reliability);
+        self.route_push(msg, reliability);
     }
 
     fn send_request(&self, msg: Request) {
@@ -425,7 +441,11 @@ impl Primitives for Face {
     }
 
     fn send_response_final(&self, msg: ResponseFinal) {
-        route_send_response_final(&self.tables, &mut self.state.clone(), msg.rid);
+        if msg.ext_cancel.is_some() {
+            route_cancel_query(&self.tables, &self.state, msg.rid);
+        } else {
+            route_send_response_final(&self.tables, &mut self.state.clone(), msg.rid);
+        }
     }
 
     fn send_close(&self) {
@@ -22,5 +22,7 @@ pub mod interests;
 pub mod pubsub;
 pub mod queries;
 pub mod resource;
+#[cfg(feature = "stats")]
+pub mod stats;
 pub mod tables;
 pub mod token;
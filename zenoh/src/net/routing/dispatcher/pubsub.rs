@@ -305,7 +305,7 @@ macro_rules! treat_timestamp {
                                     "Error treating timestamp for received Data ({}). Drop it!",
                                     e
                                 );
-                                return;
+                                return true;
                             } else {
                                 data.timestamp = Some(hlc.new_timestamp());
                                 tracing::error!(
@@ -388,12 +388,43 @@ macro_rules! inc_stats {
     };
 }
 
+#[cfg(feature = "stats")]
+macro_rules! inc_ke_stats {
+    (
+        $ke_stats:expr,
+        $txrx:ident,
+        $body:expr
+    ) => {
+        paste::paste! {
+            for stats in $ke_stats.iter() {
+                use zenoh_buffers::buffer::Buffer;
+                match &$body {
+                    PushBody::Put(p) => {
+                        stats.[<inc_ $txrx _z_put_msgs>](1);
+                        let mut n =  p.payload.len();
+                        if let Some(a) = p.ext_attachment.as_ref() {
+                           n += a.buffer.len();
+                        }
+                        stats.[<inc_ $txrx _z_put_pl_bytes>](n);
+                    }
+                    PushBody::Del(_) => {
+                        stats.[<inc_ $txrx _z_del_msgs>](1);
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// Routes a push message received on `face`.
+///
+/// Returns `false` if the transport of any of the faces it was routed to dropped it.
 pub fn route_data(
     tables_ref: &Arc<TablesLock>,
     face: &FaceState,
     mut msg: Push,
     reliability: Reliability,
-) {
+) -> bool {
     let tables = zread!(tables_ref.tables);
     match tables
         .get_mapping(face, &msg.wire_expr.scope, msg.wire_expr.mapping)
@@ -416,6 +447,10 @@ pub fn route_data(
             } else {
                 inc_stats!(face, rx, admin, msg.payload)
             }
+            #[cfg(feature = "stats")]
+            let ke_stats = tables_ref.key_expr_stats.matching(expr.full_expr());
+            #[cfg(feature = "stats")]
+            inc_ke_stats!(ke_stats, rx, msg.payload);
 
             if tables.hat_code.ingress_filter(&tables, face, &mut expr) {
                 let res = Resource::get_resource(&prefix, expr.suffix);
@@ -438,8 +473,10 @@ pub fn route_data(
                             } else {
                                 inc_stats!(outface, tx, admin, msg.payload)
                             }
+                            #[cfg(feature = "stats")]
+                            inc_ke_stats!(ke_stats, tx, msg.payload);
 
-                            outface.primitives.send_push(
+                            return outface.primitives.send_push(
                                 Push {
                                     wire_expr: key_expr.into(),
                                     ext_qos: msg.ext_qos,
@@ -448,7 +485,7 @@ pub fn route_data(
                                     payload: msg.payload,
                                 },
                                 reliability,
-                            )
+                            );
                         }
                     } else {
                         let route = route
@@ -462,6 +499,7 @@ pub fn route_data(
                             .collect::<Vec<Direction>>();
 
                         drop(tables);
+                        let mut scheduled = true;
                         for (outface, key_expr, context) in route {
                             #[cfg(feature = "stats")]
                             if !admin {
@@ -469,8 +507,10 @@ pub fn route_data(
                             } else {
                                 inc_stats!(outface, tx, admin, msg.payload)
                             }
+                            #[cfg(feature = "stats")]
+                            inc_ke_stats!(ke_stats, tx, msg.payload);
 
-                            outface.primitives.send_push(
+                            scheduled &= outface.primitives.send_push(
                                 Push {
                                     wire_expr: key_expr,
                                     ext_qos: msg.ext_qos,
@@ -479,8 +519,9 @@ pub fn route_data(
                                     payload: msg.payload.clone(),
                                 },
                                 reliability,
-                            )
+                            );
                         }
+                        return scheduled;
                     }
                 }
             }
@@ -493,4 +534,5 @@ pub fn route_data(
             );
         }
     }
+    true
 }
@@ -553,6 +553,67 @@ macro_rules! inc_res_stats {
     };
 }
 
+#[cfg(feature = "stats")]
+macro_rules! inc_ke_req_stats {
+    (
+        $ke_stats:expr,
+        $txrx:ident,
+        $body:expr
+    ) => {
+        paste::paste! {
+            for stats in $ke_stats.iter() {
+                use zenoh_buffers::buffer::Buffer;
+                match &$body {
+                    RequestBody::Query(q) => {
+                        stats.[<inc_ $txrx _z_query_msgs>](1);
+                        stats.[<inc_ $txrx _z_query_pl_bytes>](
+                            q.ext_body.as_ref().map(|b| b.payload.len()).unwrap_or(0),
+                        );
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "stats")]
+macro_rules! inc_ke_res_stats {
+    (
+        $ke_stats:expr,
+        $txrx:ident,
+        $body:expr
+    ) => {
+        paste::paste! {
+            for stats in $ke_stats.iter() {
+                use zenoh_buffers::buffer::Buffer;
+                stats.[<inc_ $txrx _z_reply_msgs>](1);
+                match &$body {
+                    ResponseBody::Reply(r) => {
+                        let mut n = 0;
+                        match &r.payload {
+                            ReplyBody::Put(p) => {
+                                if let Some(a) = p.ext_attachment.as_ref() {
+                                   n += a.buffer.len();
+                                }
+                                n += p.payload.len();
+                            }
+                            ReplyBody::Del(d) => {
+                                if let Some(a) = d.ext_attachment.as_ref() {
+                                   n += a.buffer.len();
+                                }
+                            }
+                        }
+                        stats.[<inc_ $txrx _z_reply_pl_bytes>](n);
+                    }
+                    ResponseBody::Err(e) => {
+                        stats.[<inc_ $txrx _z_reply_pl_bytes>](e.payload.len());
+                    }
+                }
+            }
+        }
+    };
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn route_query(
     tables_ref: &Arc<TablesLock>,
@@ -588,6 +649,10 @@ pub fn route_query(
             } else {
                 inc_req_stats!(face, rx, admin, body)
             }
+            #[cfg(feature = "stats")]
+            let ke_stats = tables_ref.key_expr_stats.matching(expr.full_expr());
+            #[cfg(feature = "stats")]
+            inc_ke_req_stats!(ke_stats, rx, body);
 
             if rtables.hat_code.ingress_filter(&rtables, face, &mut expr) {
                 let res = Resource::get_resource(&prefix, expr.suffix);
@@ -602,6 +667,15 @@ pub fn route_query(
                 let queries_lock = zwrite!(tables_ref.queries_lock);
                 let route =
                     compute_final_route(&rtables, &route, face, &mut expr, &ext_target, query);
+                if !route.is_empty() {
+                    zlock!(face.forwarded_queries).insert(
+                        qid,
+                        route
+                            .values()
+                            .map(|((outface, _, _), outqid)| (Arc::downgrade(outface), *outqid))
+                            .collect(),
+                    );
+                }
                 let timeout = ext_timeout.unwrap_or(rtables.queries_default_timeout);
                 drop(queries_lock);
                 drop(rtables);
@@ -616,6 +690,7 @@ pub fn route_query(
                         rid: qid,
                         ext_qos: response::ext::QoSType::RESPONSE_FINAL,
                         ext_tstamp: None,
+                        ext_cancel: None,
                     });
                 } else {
                     for ((outface, key_expr, context), outqid) in route.values() {
@@ -628,6 +703,8 @@ pub fn route_query(
                         } else {
                             inc_req_stats!(outface, tx, admin, body)
                         }
+                        #[cfg(feature = "stats")]
+                        inc_ke_req_stats!(ke_stats, tx, body);
 
                         tracing::trace!(
                             "Propagate query {}:{} to {}:{}",
@@ -656,6 +733,7 @@ pub fn route_query(
                     rid: qid,
                     ext_qos: response::ext::QoSType::RESPONSE_FINAL,
                     ext_tstamp: None,
+                    ext_cancel: None,
                 });
             }
         }
@@ -670,6 +748,7 @@ pub fn route_query(
                 rid: qid,
                 ext_qos: response::ext::QoSType::RESPONSE_FINAL,
                 ext_tstamp: None,
+                ext_cancel: None,
             });
         }
     }
@@ -686,6 +765,20 @@ pub(crate) fn route_send_response(
     key_expr: WireExpr,
     body: ResponseBody,
 ) {
+    // Resolved before locking the queries as routing takes the locks in the opposite order
+    #[cfg(feature = "stats")]
+    let ke_stats = if key_expr.scope == zenoh_protocol::core::EMPTY_EXPR_ID {
+        tables_ref.key_expr_stats.matching(key_expr.suffix.as_ref())
+    } else {
+        zread!(tables_ref.tables)
+            .get_mapping(face, &key_expr.scope, key_expr.mapping)
+            .map(|prefix| {
+                tables_ref
+                    .key_expr_stats
+                    .matching(&(prefix.expr() + key_expr.suffix.as_ref()))
+            })
+            .unwrap_or_default()
+    };
     let queries_lock = zread!(tables_ref.queries_lock);
     #[cfg(feature = "stats")]
     let admin = key_expr.as_str().starts_with("@/");
@@ -695,6 +788,8 @@ pub(crate) fn route_send_response(
     } else {
         inc_res_stats!(face, rx, admin, body)
     }
+    #[cfg(feature = "stats")]
+    inc_ke_res_stats!(ke_stats, rx, body);
 
     match face.pending_queries.get(&qid) {
         Some((query, _)) => {
@@ -706,6 +801,8 @@ pub(crate) fn route_send_response(
             } else {
                 inc_res_stats!(query.src_face, tx, admin, body)
             }
+            #[cfg(feature = "stats")]
+            inc_ke_res_stats!(ke_stats, tx, body);
 
             query.src_face.primitives.send_response(Response {
                 rid: query.src_qid,
@@ -747,6 +844,59 @@ pub(crate) fn route_send_response_final(
     }
 }
 
+pub(crate) fn route_cancel_query(
+    tables_ref: &Arc<TablesLock>,
+    face: &Arc<FaceState>,
+    qid: RequestId,
+) {
+    let Some(forwarded) = zlock!(face.forwarded_queries).remove(&qid) else {
+        tracing::debug!("Route cancel {}:{}: Query not found!", face, qid);
+        return;
+    };
+    let queries_lock = zwrite!(tables_ref.queries_lock);
+    let mut cancelled = vec![];
+    for (outface, outqid) in forwarded {
+        let Some(mut outface) = outface.upgrade() else {
+            continue;
+        };
+        // Peers that can't decode the cancellation keep the query until its final reply
+        if !outface.query_cancellation {
+            continue;
+        }
+        if outface
+            .pending_queries
+            .get(&outqid)
+            .is_some_and(|(query, _)| query.src_face.id == face.id && query.src_qid == qid)
+        {
+            if let Some(query) = get_mut_unchecked(&mut outface)
+                .pending_queries
+                .remove(&outqid)
+            {
+                cancelled.push((outface, outqid, query));
+            }
+        }
+    }
+    drop(queries_lock);
+
+    // The requester already dropped the query: no final reply is propagated back
+    for (outface, outqid, (_, cancellation_token)) in cancelled {
+        cancellation_token.cancel();
+        tracing::debug!(
+            "Propagate cancel {}:{} to {}:{}",
+            face,
+            qid,
+            outface,
+            outqid
+        );
+        outface.primitives.send_response_final(ResponseFinal {
+            rid: outqid,
+            ext_qos: response::ext::QoSType::RESPONSE_FINAL,
+            ext_tstamp: None,
+            ext_cancel: Some(response::ext::Cancel::new()),
+        });
+    }
+}
+
 pub(crate) fn finalize_pending_queries(tables_ref: &TablesLock, face: &mut Arc<FaceState>) {
     let queries_lock = zwrite!(tables_ref.queries_lock);
     for (_, query) in get_mut_unchecked(face).pending_queries.drain() {
@@ -759,6 +909,7 @@ pub(crate) fn finalize_pending_query(query: (Arc<Query>, CancellationToken)) {
     let (query, cancellation_token) = query;
     cancellation_token.cancel();
     if let Some(query) = Arc::into_inner(query) {
+        zlock!(query.src_face.forwarded_queries).remove(&query.src_qid);
         tracing::debug!("Propagate final reply {}:{}", query.src_face, query.src_qid);
         query
             .src_face
@@ -768,6 +919,7 @@ pub(crate) fn finalize_pending_query(query: (Arc<Query>, CancellationToken)) {
                 rid: query.src_qid,
                 ext_qos: response::ext::QoSType::RESPONSE_FINAL,
                 ext_tstamp: None,
+                ext_cancel: None,
             });
     }
 }
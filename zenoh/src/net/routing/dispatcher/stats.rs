@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use zenoh_config::Config;
+use zenoh_core::{zread, zwrite};
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+use zenoh_result::ZResult;
+use zenoh_transport::stats::{KeyExprStats, KeyExprStatsReport};
+
+/// Maximum number of prefixes accounted when `stats/key_expr_depth` is configured.
+const MAX_KEY_EXPR_PREFIXES: usize = 1024;
+
+/// Statistics of the traffic routed by this node, per configured key expression
+/// (`stats/key_exprs`) and per key expression prefix (`stats/key_expr_depth`).
+#[derive(Default)]
+pub struct KeyExprStatsCollector {
+    key_exprs: Vec<(OwnedKeyExpr, Arc<KeyExprStats>)>,
+    depth: Option<usize>,
+    prefixes: RwLock<HashMap<String, Arc<KeyExprStats>>>,
+}
+
+impl KeyExprStatsCollector {
+    pub(crate) fn new(config: &Config) -> ZResult<Self> {
+        let depth = *config.stats().key_expr_depth();
+        if depth == Some(0) {
+            bail!("Stats property `key_expr_depth` must be strictly positive");
+        }
+        Ok(Self {
+            key_exprs: config
+                .stats()
+                .key_exprs()
+                .iter()
+                .map(|ke| (ke.clone(), Arc::default()))
+                .collect(),
+            depth,
+            prefixes: RwLock::default(),
+        })
+    }
+
+    /// Returns the statistics the traffic on `key_expr` is accounted on: the ones of the
+    /// configured key expressions that include `key_expr` and the one of its prefix.
+    pub(crate) fn matching(&self, key_expr: &str) -> Vec<Arc<KeyExprStats>> {
+        if self.key_exprs.is_empty() && self.depth.is_none() {
+            return vec![];
+        }
+        let Ok(ke) = keyexpr::new(key_expr) else {
+            return vec![];
+        };
+        let mut res: Vec<_> = self
+            .key_exprs
+            .iter()
+            .filter(|(configured, _)| configured.includes(ke))
+            .map(|(_, stats)| stats.clone())
+            .collect();
+        if let Some(depth) = self.depth {
+            let prefix = match key_expr.match_indices('/').nth(depth - 1) {
+                Some((i, _)) => &key_expr[..i],
+                None => key_expr,
+            };
+            if let Some(stats) = self.prefix_stats(prefix) {
+                res.push(stats);
+            }
+        }
+        res
+    }
+
+    fn prefix_stats(&self, prefix: &str) -> Option<Arc<KeyExprStats>> {
+        if let Some(stats) = zread!(self.prefixes).get(prefix) {
+            return Some(stats.clone());
+        }
+        let mut prefixes = zwrite!(self.prefixes);
+        if prefixes.len() >= MAX_KEY_EXPR_PREFIXES && !prefixes.contains_key(prefix) {
+            tracing::trace!("Traffic on key expression prefix {} not accounted", prefix);
+            return None;
+        }
+        Some(prefixes.entry(prefix.to_string()).or_default().clone())
+    }
+
+    /// Returns the reports of the configured key expressions.
+    pub fn key_exprs_report(&self) -> Vec<(String, KeyExprStatsReport)> {
+        self.key_exprs
+            .iter()
+            .map(|(ke, stats)| (ke.to_string(), stats.report()))
+            .collect()
+    }
+
+    /// Returns the reports of the key expression prefixes, sorted by prefix.
+    pub fn prefixes_report(&self) -> Vec<(String, KeyExprStatsReport)> {
+        let mut res: Vec<_> = zread!(self.prefixes)
+            .iter()
+            .map(|(prefix, stats)| (prefix.clone(), stats.report()))
+            .collect();
+        res.sort_by(|(a, _), (b, _)| a.cmp(b));
+        res
+    }
+}
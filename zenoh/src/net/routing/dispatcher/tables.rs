@@ -177,4 +177,6 @@ pub struct TablesLock {
     pub tables: RwLock<Tables>,
     pub(crate) ctrl_lock: Mutex<Box<dyn HatTrait + Send + Sync>>,
     pub queries_lock: RwLock<()>,
+    #[cfg(feature = "stats")]
+    pub(crate) key_expr_stats: super::stats::KeyExprStatsCollector,
 }
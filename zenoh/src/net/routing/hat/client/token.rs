@@ -12,7 +12,10 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    collections::HashSet,
+    sync::{atomic::Ordering, Arc},
+};
 
 use zenoh_config::WhatAmI;
 use zenoh_protocol::network::{
@@ -434,4 +437,13 @@ impl HatTokenTrait for HatCode {
     ) -> Option<Arc<Resource>> {
         forget_simple_token(tables, face, id, res, send_declare)
     }
+
+    fn get_tokens(&self, tables: &Tables) -> Vec<Arc<Resource>> {
+        // Compute the list of known tokens (keys)
+        let mut tokens = HashSet::new();
+        for src_face in tables.faces.values() {
+            tokens.extend(face_hat!(src_face).remote_tokens.values().cloned());
+        }
+        Vec::from_iter(tokens)
+    }
 }
@@ -786,4 +786,9 @@ impl HatTokenTrait for HatCode {
             forget_simple_token(tables, face, id, send_declare)
         }
     }
+
+    fn get_tokens(&self, tables: &Tables) -> Vec<Arc<Resource>> {
+        // Compute the list of known tokens (keys)
+        hat!(tables).linkstatepeer_tokens.iter().cloned().collect()
+    }
 }
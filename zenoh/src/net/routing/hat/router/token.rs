@@ -1176,4 +1176,9 @@ impl HatTokenTrait for HatCode {
             _ => forget_simple_token(tables, face, id, send_declare),
         }
     }
+
+    fn get_tokens(&self, tables: &Tables) -> Vec<Arc<Resource>> {
+        // Compute the list of known tokens (keys)
+        hat!(tables).router_tokens.iter().cloned().collect()
+    }
 }
@@ -18,53 +18,104 @@
 //!
 //! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
 
-use std::{any::Any, collections::HashSet, iter, sync::Arc};
+use std::{
+    any::Any,
+    collections::HashMap,
+    iter,
+    sync::{Arc, Mutex, OnceLock, RwLock, Weak},
+    time::Duration,
+};
 
 use itertools::Itertools;
 use zenoh_config::{
-    AclConfig, AclMessage, CertCommonName, InterceptorFlow, Interface, Permission, Username,
+    AclConfig, AclMessage, CertCommonName, Config, InterceptorFlow, Interface, Permission,
+    PubKeyFingerprint, Username,
 };
+use zenoh_core::{zlock, zread, zwrite};
 use zenoh_protocol::{
     core::ZenohIdProto,
     network::{
-        interest::InterestMode, Declare, DeclareBody, Interest, NetworkBody, NetworkMessage, Push,
-        Request, Response,
+        declare::{self, common::ext::WireExprType},
+        interest::InterestMode,
+        Declare, DeclareBody, Interest, NetworkBody, NetworkMessage, Push, Request, Response,
+        UndeclareQueryable, UndeclareSubscriber, UndeclareToken,
     },
     zenoh::{PushBody, RequestBody},
 };
 use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
 use zenoh_transport::{
     multicast::TransportMulticast,
     unicast::{authentication::AuthId, TransportUnicast},
 };
 
 use super::{
-    authorization::PolicyEnforcer, EgressInterceptor, IngressInterceptor, InterceptorFactory,
-    InterceptorFactoryTrait, InterceptorTrait,
+    audit::{now_millis, AclAuditEvent, AclAuditor},
+    authorization::{FlowPolicy, PolicyEnforcer},
+    EgressInterceptor, IngressInterceptor, InterceptorFactory, InterceptorFactoryTrait,
+    InterceptorTrait,
 };
 use crate::{
     api::key_expr::KeyExpr,
-    net::routing::{interceptor::authorization::SubjectQuery, RoutingContext},
+    net::{
+        primitives::Primitives,
+        routing::{
+            dispatcher::face::WeakFace, interceptor::authorization::SubjectQuery, RoutingContext,
+        },
+        runtime::Runtime,
+    },
 };
+
+const POLICY_FILE_POLL_PERIOD: Duration = Duration::from_secs(1);
+
 pub struct AclEnforcer {
-    enforcer: Arc<PolicyEnforcer>,
+    state: Arc<AclEnforcerState>,
+    _policy_file_watcher: Option<TerminatableTask>,
 }
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+
+/// The reloadable state of the access control, shared with the per-transport interceptors.
+struct AclEnforcerState {
+    config: Mutex<AclConfig>,
+    enforcer: RwLock<Arc<PolicyEnforcer>>,
+    transports: Mutex<Vec<Weak<AclTransport>>>,
+    auditor: Option<Arc<AclAuditor>>,
+}
+
+#[derive(Clone)]
 pub struct AuthSubject {
     id: usize,
     name: String,
+    /// The rules of the subject with templated key expressions, expanded for the transport.
+    templated_policy: Option<Arc<FlowPolicy>>,
 }
 
-struct EgressAclEnforcer {
+/// The policy enforcer in use and the subjects it matched for a given transport.
+pub struct AclState {
     policy_enforcer: Arc<PolicyEnforcer>,
     subject: Vec<AuthSubject>,
+}
+
+/// Key of a declaration: its kind, the node that declared it and its id.
+type DeclarationKey = (AclMessage, u16, u32);
+
+/// The access control state of a unicast transport, shared by its ingress and egress interceptors.
+struct AclTransport {
     zid: ZenohIdProto,
+    queries: Vec<SubjectQuery>,
+    state: RwLock<Arc<AclState>>,
+    face: OnceLock<WeakFace>,
+    /// The subscribers, queryables and liveliness tokens declared by the remote node
+    /// that were allowed in ingress, with their key expressions.
+    declarations: Mutex<HashMap<DeclarationKey, String>>,
+    auditor: Option<Arc<AclAuditor>>,
+}
+
+struct EgressAclEnforcer {
+    transport: Arc<AclTransport>,
 }
 
 struct IngressAclEnforcer {
-    policy_enforcer: Arc<PolicyEnforcer>,
-    subject: Vec<AuthSubject>,
-    zid: ZenohIdProto,
+    transport: Arc<AclTransport>,
 }
 
 pub(crate) fn acl_interceptor_factories(
@@ -73,13 +124,10 @@ pub(crate) fn acl_interceptor_factories(
     let mut res: Vec<InterceptorFactory> = vec![];
 
     if acl_config.enabled {
-        let mut policy_enforcer = PolicyEnforcer::new();
-        match policy_enforcer.init(acl_config) {
-            Ok(_) => {
+        match AclEnforcer::new(acl_config) {
+            Ok(acl_enforcer) => {
                 tracing::debug!("Access control is enabled");
-                res.push(Box::new(AclEnforcer {
-                    enforcer: Arc::new(policy_enforcer),
-                }))
+                res.push(Box::new(acl_enforcer))
             }
             Err(e) => bail!("Access control not enabled due to: {}", e),
         }
@@ -90,6 +138,242 @@ pub(crate) fn acl_interceptor_factories(
     Ok(res)
 }
 
+fn new_policy_enforcer(acl_config: &AclConfig) -> ZResult<PolicyEnforcer> {
+    let mut policy_enforcer = PolicyEnforcer::new();
+    policy_enforcer.init(&acl_config.with_policy_file()?)?;
+    Ok(policy_enforcer)
+}
+
+impl AclEnforcer {
+    fn new(acl_config: &AclConfig) -> ZResult<Self> {
+        let state = Arc::new(AclEnforcerState {
+            config: Mutex::new(acl_config.clone()),
+            enforcer: RwLock::new(Arc::new(new_policy_enforcer(acl_config)?)),
+            transports: Mutex::new(vec![]),
+            auditor: acl_config
+                .audit
+                .as_ref()
+                .map(AclAuditor::new)
+                .transpose()?
+                .map(Arc::new),
+        });
+        let _policy_file_watcher = acl_config.policy_file.clone().map(|path| {
+            let state = Arc::downgrade(&state);
+            TerminatableTask::spawn_abortable(zenoh_runtime::ZRuntime::Net, async move {
+                let mut content = read_policy_file(&path).await;
+                loop {
+                    tokio::time::sleep(POLICY_FILE_POLL_PERIOD).await;
+                    if state.strong_count() == 0 {
+                        break;
+                    }
+                    let new_content = read_policy_file(&path).await;
+                    if new_content != content {
+                        tracing::info!(
+                            "ACL policy file {} changed, reloading access control",
+                            path
+                        );
+                        content = new_content;
+                        let state = state.clone();
+                        // Reloading reads the policy file again
+                        let reloaded = tokio::task::spawn_blocking(move || {
+                            let state = state.upgrade()?;
+                            let acl_config = zlock!(state.config).clone();
+                            Some(state.reload(&acl_config))
+                        })
+                        .await;
+                        if let Ok(Some(Err(e))) = reloaded {
+                            tracing::error!("Access control not reloaded due to: {}", e);
+                        }
+                    }
+                }
+            })
+        });
+        Ok(Self {
+            state,
+            _policy_file_watcher,
+        })
+    }
+}
+
+/// Reads the policy file, if it exists, without blocking the runtime.
+async fn read_policy_file(path: &str) -> Option<Vec<u8>> {
+    let path = path.to_string();
+    tokio::task::spawn_blocking(move || std::fs::read(path).ok())
+        .await
+        .ok()
+        .flatten()
+}
+
+impl AclEnforcerState {
+    /// Replaces the policy enforcer and re-evaluates the existing transports against it.
+    ///
+    /// The declarations of the remote nodes that are not allowed anymore are undeclared.
+    fn reload(&self, acl_config: &AclConfig) -> ZResult<()> {
+        let policy_enforcer = Arc::new(new_policy_enforcer(acl_config)?);
+        *zlock!(self.config) = acl_config.clone();
+        *zwrite!(self.enforcer) = policy_enforcer.clone();
+        if !policy_enforcer.acl_enabled {
+            tracing::warn!(
+                "Access control has been disabled at runtime: all messages are now allowed"
+            );
+        }
+
+        let transports = {
+            let mut transports = zlock!(self.transports);
+            transports.retain(|t| t.strong_count() > 0);
+            transports
+                .iter()
+                .filter_map(Weak::upgrade)
+                .collect::<Vec<_>>()
+        };
+        let mut undeclarations = vec![];
+        for transport in transports {
+            transport.set_policy_enforcer(policy_enforcer.clone());
+            let Some(face) = transport.face.get().cloned() else {
+                continue;
+            };
+            let ingress = IngressAclEnforcer {
+                transport: transport.clone(),
+            };
+            let mut declarations = zlock!(transport.declarations);
+            declarations.retain(|(kind, node_id, id), key_expr| {
+                let log_msg = match kind {
+                    AclMessage::DeclareSubscriber => "Declare Subscriber (ingress)",
+                    AclMessage::DeclareQueryable => "Declare Queryable (ingress)",
+                    _ => "Liveliness Token (ingress)",
+                };
+                if !ingress.is_enabled()
+                    || ingress.action(*kind, log_msg, key_expr) == Permission::Allow
+                {
+                    return true;
+                }
+                tracing::debug!(
+                    "{} is not allowed anymore to declare {:?} on {}: undeclaring it",
+                    transport.zid,
+                    kind,
+                    key_expr
+                );
+                if let Some(undeclaration) = undeclaration(*kind, *node_id, *id) {
+                    undeclarations.push((face.clone(), undeclaration));
+                }
+                false
+            });
+        }
+        if !undeclarations.is_empty() {
+            // The routing tables may be locked by the caller: undeclare from another task.
+            zenoh_runtime::ZRuntime::Net.spawn(async move {
+                for (face, undeclaration) in undeclarations {
+                    if let Some(face) = face.upgrade() {
+                        face.send_declare(undeclaration);
+                    }
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn auth_subjects(
+        policy_enforcer: &PolicyEnforcer,
+        queries: &[SubjectQuery],
+    ) -> Vec<AuthSubject> {
+        let mut auth_subjects: Vec<AuthSubject> = Vec::new();
+        for query in queries {
+            if let Some(entry) = policy_enforcer.subject_store.query(query) {
+                let name = format!("{query}");
+                if auth_subjects
+                    .iter()
+                    .any(|subject| subject.id == entry.id && subject.name == name)
+                {
+                    continue;
+                }
+                auth_subjects.push(AuthSubject {
+                    id: entry.id,
+                    name,
+                    templated_policy: policy_enforcer
+                        .templated_policy(entry.id, query)
+                        .map(Arc::new),
+                });
+            }
+        }
+        auth_subjects
+    }
+}
+
+/// Builds the undeclaration of the given declaration, as if it was sent by the remote node.
+fn undeclaration(kind: AclMessage, node_id: u16, id: u32) -> Option<Declare> {
+    let body = match kind {
+        AclMessage::DeclareSubscriber => DeclareBody::UndeclareSubscriber(UndeclareSubscriber {
+            id,
+            ext_wire_expr: WireExprType::null(),
+        }),
+        AclMessage::DeclareQueryable => DeclareBody::UndeclareQueryable(UndeclareQueryable {
+            id,
+            ext_wire_expr: WireExprType::null(),
+        }),
+        AclMessage::LivelinessToken => DeclareBody::UndeclareToken(UndeclareToken {
+            id,
+            ext_wire_expr: WireExprType::null(),
+        }),
+        _ => return None,
+    };
+    Some(Declare {
+        interest_id: None,
+        ext_qos: declare::ext::QoSType::DECLARE,
+        ext_tstamp: None,
+        ext_nodeid: declare::ext::NodeIdType { node_id },
+        body,
+    })
+}
+
+impl AclTransport {
+    fn set_policy_enforcer(&self, policy_enforcer: Arc<PolicyEnforcer>) {
+        let subject = AclEnforcerState::auth_subjects(&policy_enforcer, &self.queries);
+        if subject.is_empty() {
+            tracing::info!(
+                "{} did not match any configured ACL subject. Default permission `{:?}` will be applied on all messages",
+                self.zid,
+                policy_enforcer.default_permission
+            );
+        }
+        *zwrite!(self.state) = Arc::new(AclState {
+            policy_enforcer,
+            subject,
+        });
+    }
+
+    /// Keeps track of the declarations allowed in ingress so that they can be
+    /// undeclared if the access control is reloaded.
+    fn track_declaration(&self, ctx: &RoutingContext<NetworkMessage>, key_expr: Option<&str>) {
+        let NetworkBody::Declare(Declare {
+            ext_nodeid, body, ..
+        }) = &ctx.msg.body
+        else {
+            return;
+        };
+        let node_id = ext_nodeid.node_id;
+        let (kind, id, declared) = match body {
+            DeclareBody::DeclareSubscriber(d) => (AclMessage::DeclareSubscriber, d.id, true),
+            DeclareBody::UndeclareSubscriber(u) => (AclMessage::DeclareSubscriber, u.id, false),
+            DeclareBody::DeclareQueryable(d) => (AclMessage::DeclareQueryable, d.id, true),
+            DeclareBody::UndeclareQueryable(u) => (AclMessage::DeclareQueryable, u.id, false),
+            DeclareBody::DeclareToken(d) => (AclMessage::LivelinessToken, d.id, true),
+            DeclareBody::UndeclareToken(u) => (AclMessage::LivelinessToken, u.id, false),
+            _ => return,
+        };
+        if let Some(face) = ctx.inface() {
+            self.face.get_or_init(|| face.downgrade());
+        }
+        let mut declarations = zlock!(self.declarations);
+        if declared {
+            if let Some(key_expr) = key_expr {
+                declarations.insert((kind, node_id, id), key_expr.to_string());
+            }
+        } else {
+            declarations.remove(&(kind, node_id, id));
+        }
+    }
+}
+
 impl InterceptorFactoryTrait for AclEnforcer {
     fn new_transport_unicast(
         &self,
@@ -105,6 +389,7 @@ impl InterceptorFactoryTrait for AclEnforcer {
 
         let mut cert_common_names = Vec::new();
         let mut username = None;
+        let mut pubkey_fingerprint = None;
 
         for auth_id in auth_ids {
             match auth_id {
@@ -118,6 +403,15 @@ impl InterceptorFactoryTrait for AclEnforcer {
                     }
                     username = Some(Username(value));
                 }
+                AuthId::PubKeyFingerprint(value) => {
+                    if pubkey_fingerprint.is_some() {
+                        tracing::error!(
+                            "Transport should not report more than one public key fingerprint"
+                        );
+                        return (None, None);
+                    }
+                    pubkey_fingerprint = Some(PubKeyFingerprint(value));
+                }
                 AuthId::None => {}
             }
         }
@@ -146,26 +440,6 @@ impl InterceptorFactoryTrait for AclEnforcer {
             tracing::warn!("Transport returned multiple network interfaces, current ACL logic might incorrectly apply filters in this case!");
         }
 
-        let mut auth_subjects = HashSet::new();
-
-        for ((username, interface), cert_common_name) in iter::once(username)
-            .cartesian_product(interfaces.into_iter())
-            .cartesian_product(cert_common_names.into_iter())
-        {
-            let query = SubjectQuery {
-                interface,
-                cert_common_name,
-                username,
-            };
-
-            if let Some(entry) = self.enforcer.subject_store.query(&query) {
-                auth_subjects.insert(AuthSubject {
-                    id: entry.id,
-                    name: format!("{query}"),
-                });
-            }
-        }
-
         let zid = match transport.get_zid() {
             Ok(zid) => zid,
             Err(err) => {
@@ -173,34 +447,53 @@ impl InterceptorFactoryTrait for AclEnforcer {
                 return (None, None);
             }
         };
-        // FIXME: Investigate if `AuthSubject` can have duplicates above and try to avoid this conversion
-        let auth_subjects = auth_subjects.into_iter().collect::<Vec<AuthSubject>>();
-        if auth_subjects.is_empty() {
-            tracing::info!(
-                "{zid} did not match any configured ACL subject. Default permission `{:?}` will be applied on all messages",
-                self.enforcer.default_permission
-            );
+        let whatami = match transport.get_whatami() {
+            Ok(whatami) => whatami,
+            Err(err) => {
+                tracing::error!("Couldn't get Transport whatami: {}", err);
+                return (None, None);
+            }
+        };
+
+        let queries = iter::once(username)
+            .cartesian_product(interfaces)
+            .cartesian_product(cert_common_names)
+            .map(|((username, interface), cert_common_name)| SubjectQuery {
+                interface,
+                cert_common_name,
+                username,
+                pubkey_fingerprint: pubkey_fingerprint.clone(),
+                zid: Some(zid.into()),
+                whatami: Some(whatami),
+            })
+            .collect::<Vec<_>>();
+
+        let policy_enforcer = zread!(self.state.enforcer).clone();
+        let acl_transport = Arc::new(AclTransport {
+            zid,
+            queries,
+            state: RwLock::new(Arc::new(AclState {
+                policy_enforcer: policy_enforcer.clone(),
+                subject: vec![],
+            })),
+            face: OnceLock::new(),
+            declarations: Mutex::new(HashMap::new()),
+            auditor: self.state.auditor.clone(),
+        });
+        acl_transport.set_policy_enforcer(policy_enforcer);
+        {
+            let mut transports = zlock!(self.state.transports);
+            transports.retain(|t| t.strong_count() > 0);
+            transports.push(Arc::downgrade(&acl_transport));
         }
+        // Both interceptors are always created as the enabled flows may change when reloading
         let ingress_interceptor = Box::new(IngressAclEnforcer {
-            policy_enforcer: self.enforcer.clone(),
-            zid,
-            subject: auth_subjects.clone(),
+            transport: acl_transport.clone(),
         });
         let egress_interceptor = Box::new(EgressAclEnforcer {
-            policy_enforcer: self.enforcer.clone(),
-            zid,
-            subject: auth_subjects,
+            transport: acl_transport,
         });
-        (
-            self.enforcer
-                .interface_enabled
-                .ingress
-                .then_some(ingress_interceptor),
-            self.enforcer
-                .interface_enabled
-                .egress
-                .then_some(egress_interceptor),
-        )
+        (Some(ingress_interceptor), Some(egress_interceptor))
     }
 
     fn new_transport_multicast(
@@ -215,6 +508,16 @@ impl InterceptorFactoryTrait for AclEnforcer {
         tracing::debug!("Peer Multicast is disabled in interceptor");
         None
     }
+
+    fn update_config(&self, config: &Config) -> ZResult<()> {
+        self.state.reload(config.access_control())
+    }
+
+    fn set_runtime(&self, runtime: &Runtime) {
+        if let Some(auditor) = &self.state.auditor {
+            auditor.set_runtime(runtime);
+        }
+    }
 }
 
 impl InterceptorTrait for IngressAclEnforcer {
@@ -237,6 +540,11 @@ impl InterceptorTrait for IngressAclEnforcer {
             })
             .or_else(|| ctx.full_expr());
 
+        if !self.is_enabled() {
+            self.transport.track_declaration(&ctx, key_expr);
+            return Some(ctx);
+        }
+
         match &ctx.msg.body {
             NetworkBody::Request(Request {
                 payload: RequestBody::Query(_),
@@ -423,6 +731,7 @@ impl InterceptorTrait for IngressAclEnforcer {
             // Unfiltered remaining message types
             NetworkBody::Interest(_) | NetworkBody::OAM(_) | NetworkBody::ResponseFinal(_) => {}
         }
+        self.transport.track_declaration(&ctx, key_expr);
         Some(ctx)
     }
 }
@@ -447,6 +756,10 @@ impl InterceptorTrait for EgressAclEnforcer {
             })
             .or_else(|| ctx.full_expr());
 
+        if !self.is_enabled() {
+            return Some(ctx);
+        }
+
         match &ctx.msg.body {
             NetworkBody::Request(Request {
                 payload: RequestBody::Query(_),
@@ -629,18 +942,34 @@ impl InterceptorTrait for EgressAclEnforcer {
     }
 }
 pub trait AclActionMethods {
-    fn policy_enforcer(&self) -> Arc<PolicyEnforcer>;
+    fn state(&self) -> Arc<AclState>;
     fn zid(&self) -> ZenohIdProto;
     fn flow(&self) -> InterceptorFlow;
-    fn authn_ids(&self) -> Vec<AuthSubject>;
+    fn auditor(&self) -> Option<&AclAuditor>;
+    fn is_enabled(&self) -> bool {
+        let state = self.state();
+        state.policy_enforcer.acl_enabled
+            && match self.flow() {
+                InterceptorFlow::Ingress => state.policy_enforcer.interface_enabled.ingress,
+                InterceptorFlow::Egress => state.policy_enforcer.interface_enabled.egress,
+            }
+    }
     fn action(&self, action: AclMessage, log_msg: &str, key_expr: &str) -> Permission {
-        let policy_enforcer = self.policy_enforcer();
-        let authn_ids: Vec<AuthSubject> = self.authn_ids();
+        let state = self.state();
+        let policy_enforcer = &state.policy_enforcer;
         let zid = self.zid();
         let mut decision = policy_enforcer.default_permission;
-        for subject in &authn_ids {
-            match policy_enforcer.policy_decision_point(subject.id, self.flow(), action, key_expr) {
-                Ok(Permission::Allow) => {
+        let mut decision_subject = None;
+        let mut decision_rule = None;
+        for subject in &state.subject {
+            match policy_enforcer.policy_decision_point(
+                subject.id,
+                subject.templated_policy.as_deref(),
+                self.flow(),
+                action,
+                key_expr,
+            ) {
+                Ok((Permission::Allow, rule_id)) => {
                     tracing::trace!(
                         "{} on {} is authorized to {} on {}",
                         zid,
@@ -649,9 +978,11 @@ pub trait AclActionMethods {
                         key_expr
                     );
                     decision = Permission::Allow;
+                    decision_subject = Some(subject);
+                    decision_rule = rule_id;
                     break;
                 }
-                Ok(Permission::Deny) => {
+                Ok((Permission::Deny, rule_id)) => {
                     tracing::debug!(
                         "{} on {} is unauthorized to {} on {}",
                         zid,
@@ -661,6 +992,8 @@ pub trait AclActionMethods {
                     );
 
                     decision = Permission::Deny;
+                    decision_subject = Some(subject);
+                    decision_rule = rule_id;
                     continue;
                 }
                 Err(e) => {
@@ -672,46 +1005,63 @@ pub trait AclActionMethods {
                         key_expr,
                         e
                     );
-                    return Permission::Deny;
+                    decision = Permission::Deny;
+                    decision_subject = Some(subject);
+                    decision_rule = None;
+                    break;
                 }
             }
         }
+        if let Some(auditor) = self.auditor() {
+            if auditor.is_audited(decision, key_expr) {
+                auditor.audit(AclAuditEvent {
+                    timestamp: now_millis(),
+                    zid: zid.to_string(),
+                    subject: decision_subject.map(|s| s.name.clone()),
+                    key_expr: key_expr.to_string(),
+                    message: action,
+                    flow: self.flow(),
+                    rule_id: decision_rule.map(|rule_id| rule_id.to_string()),
+                    decision,
+                });
+            }
+        }
         decision
     }
 }
 
 impl AclActionMethods for EgressAclEnforcer {
-    fn policy_enforcer(&self) -> Arc<PolicyEnforcer> {
-        self.policy_enforcer.clone()
+    fn state(&self) -> Arc<AclState> {
+        zread!(self.transport.state).clone()
     }
 
     fn zid(&self) -> ZenohIdProto {
-        self.zid
+        self.transport.zid
     }
 
     fn flow(&self) -> InterceptorFlow {
         InterceptorFlow::Egress
     }
 
-    fn authn_ids(&self) -> Vec<AuthSubject> {
-        self.subject.clone()
+    fn auditor(&self) -> Option<&AclAuditor> {
+        self.transport.auditor.as_deref()
     }
 }
 
 impl AclActionMethods for IngressAclEnforcer {
-    fn policy_enforcer(&self) -> Arc<PolicyEnforcer> {
-        self.policy_enforcer.clone()
+    fn state(&self) -> Arc<AclState> {
+        zread!(self.transport.state).clone()
     }
 
     fn zid(&self) -> ZenohIdProto {
-        self.zid
+        self.transport.zid
     }
 
     fn flow(&self) -> InterceptorFlow {
         InterceptorFlow::Ingress
     }
 
-    fn authn_ids(&self) -> Vec<AuthSubject> {
-        self.subject.clone()
+    fn auditor(&self) -> Option<&AclAuditor> {
+        self.transport.auditor.as_deref()
     }
 }
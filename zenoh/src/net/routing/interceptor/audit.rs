@@ -0,0 +1,238 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{Arc, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use zenoh_buffers::ZBuf;
+use zenoh_config::{AclAuditConf, AclMessage, InterceptorFlow, Permission};
+use zenoh_protocol::{
+    core::Reliability,
+    network::{push, Push},
+    zenoh::{PushBody, Put},
+};
+use zenoh_result::ZResult;
+use zenoh_task::TerminatableTask;
+
+use crate::{
+    api::encoding::Encoding,
+    net::{
+        primitives::{DummyPrimitives, Primitives},
+        routing::dispatcher::face::WeakFace,
+        runtime::{Runtime, WeakRuntime},
+    },
+};
+
+/// Maximum number of audit events waiting to be written or published.
+const AUDIT_EVENTS_QUEUE_SIZE: usize = 1024;
+
+/// Suffix of the adminspace key expression on which audit events are published.
+const AUDIT_EVENTS_SUFFIX: &str = "acl/events";
+
+/// An access control decision, as written or published by the [`AclAuditor`].
+#[derive(Debug, Serialize)]
+pub(crate) struct AclAuditEvent {
+    /// Milliseconds since the UNIX epoch.
+    pub(crate) timestamp: u64,
+    /// The remote node the message was received from (ingress) or sent to (egress).
+    pub(crate) zid: String,
+    /// The ACL subject that led to the decision, `None` if the default permission was applied.
+    pub(crate) subject: Option<String>,
+    pub(crate) key_expr: String,
+    pub(crate) message: AclMessage,
+    pub(crate) flow: InterceptorFlow,
+    /// The ACL rule that led to the decision, `None` if the default permission was applied.
+    pub(crate) rule_id: Option<String>,
+    pub(crate) decision: Permission,
+}
+
+/// Writes access control decisions to a JSON-lines file and/or publishes them
+/// on `@/<zid>/<whatami>/acl/events`.
+pub struct AclAuditor {
+    decisions: Vec<Permission>,
+    sender: flume::Sender<AclAuditEvent>,
+    runtime: Arc<OnceLock<WeakRuntime>>,
+    _task: TerminatableTask,
+}
+
+impl AclAuditor {
+    pub(crate) fn new(conf: &AclAuditConf) -> ZResult<Self> {
+        let file = match &conf.file {
+            Some(path) => Some(spawn_file_writer(
+                File::options()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| zerror!("Couldn't open ACL audit file {}: {}", path, e))?,
+            )?),
+            None => None,
+        };
+        let publish = conf.publish.unwrap_or(true);
+        let decisions = conf.decisions.clone().unwrap_or(vec![Permission::Deny]);
+        if decisions.is_empty() {
+            bail!("ACL audit property `decisions` cannot be empty");
+        }
+
+        let (sender, receiver) = flume::bounded::<AclAuditEvent>(AUDIT_EVENTS_QUEUE_SIZE);
+        let runtime = Arc::new(OnceLock::<WeakRuntime>::new());
+        let task_runtime = runtime.clone();
+        let task = TerminatableTask::spawn_abortable(zenoh_runtime::ZRuntime::Net, async move {
+            let mut publisher = None;
+            while let Ok(event) = receiver.recv_async().await {
+                let json = match serde_json::to_string(&event) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        tracing::error!("Couldn't serialize ACL audit event: {}", e);
+                        continue;
+                    }
+                };
+                if let Some(file) = &file {
+                    if let Err(e) = file.try_send(json.clone()) {
+                        tracing::debug!("ACL audit event not written: {}", e);
+                    }
+                }
+                if publish {
+                    if publisher.is_none() {
+                        publisher = task_runtime
+                            .get()
+                            .and_then(WeakRuntime::upgrade)
+                            .map(|runtime| AuditPublisher::new(&runtime));
+                    }
+                    match &publisher {
+                        Some(publisher) => publisher.publish(json),
+                        None => tracing::debug!("Runtime not ready, ACL audit event not published"),
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            decisions,
+            sender,
+            runtime,
+            _task: task,
+        })
+    }
+
+    pub(crate) fn set_runtime(&self, runtime: &Runtime) {
+        let _ = self.runtime.set(Runtime::downgrade(runtime));
+    }
+
+    pub(crate) fn is_audited(&self, decision: Permission, key_expr: &str) -> bool {
+        // The audit events themselves are not audited, otherwise denying them would loop
+        self.decisions.contains(&decision)
+            && !(key_expr.starts_with("@/") && key_expr.ends_with(AUDIT_EVENTS_SUFFIX))
+    }
+
+    pub(crate) fn audit(&self, event: AclAuditEvent) {
+        if let Err(e) = self.sender.try_send(event) {
+            tracing::debug!("ACL audit event dropped: {}", e);
+        }
+    }
+}
+
+/// Spawns the thread writing the audit events to the audit file, so that the file I/O does not
+/// block the runtime. The thread stops once the returned sender is dropped.
+fn spawn_file_writer(file: File) -> ZResult<flume::Sender<String>> {
+    let (sender, receiver) = flume::bounded::<String>(AUDIT_EVENTS_QUEUE_SIZE);
+    std::thread::Builder::new()
+        .name("acl-audit-file".to_string())
+        .spawn(move || {
+            let mut writer = BufWriter::new(file);
+            for line in receiver.iter() {
+                if let Err(e) = writeln!(writer, "{line}") {
+                    tracing::error!("Couldn't write ACL audit event: {}", e);
+                }
+                // Flushes once the pending events are written
+                if receiver.is_empty() {
+                    if let Err(e) = writer.flush() {
+                        tracing::error!("Couldn't write ACL audit event: {}", e);
+                    }
+                }
+            }
+        })?;
+    Ok(sender)
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+struct AuditPublisher {
+    key_expr: String,
+    // Weak as the routing tables own the auditor
+    face: WeakFace,
+}
+
+impl AuditPublisher {
+    fn new(runtime: &Runtime) -> Self {
+        Self {
+            key_expr: format!(
+                "@/{}/{}/{AUDIT_EVENTS_SUFFIX}",
+                runtime.zid(),
+                runtime.whatami()
+            ),
+            face: runtime
+                .router()
+                .new_primitives(Arc::new(DummyPrimitives))
+                .downgrade(),
+        }
+    }
+
+    fn publish(&self, json: String) {
+        let Some(face) = self.face.upgrade() else {
+            return;
+        };
+        face.send_push(
+            Push {
+                wire_expr: self.key_expr.clone().into(),
+                ext_qos: push::ext::QoSType::DEFAULT,
+                ext_tstamp: None,
+                ext_nodeid: push::ext::NodeIdType::DEFAULT,
+                payload: PushBody::Put(Put {
+                    timestamp: None,
+                    encoding: Encoding::APPLICATION_JSON.into(),
+                    ext_sinfo: None,
+                    #[cfg(feature = "shared-memory")]
+                    ext_shm: None,
+                    ext_attachment: None,
+                    ext_unknown: vec![],
+                    payload: ZBuf::from(json.into_bytes()),
+                }),
+            },
+            Reliability::Reliable,
+        );
+    }
+}
+
+impl Drop for AuditPublisher {
+    fn drop(&mut self) {
+        if let Some(face) = self.face.upgrade() {
+            face.send_close();
+        }
+    }
+}
@@ -17,28 +17,36 @@
 //! This module is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
-use std::collections::HashMap;
+use std::{collections::HashMap, iter, sync::Arc};
 
 use ahash::RandomState;
 use itertools::Itertools;
 use zenoh_config::{
     AclConfig, AclConfigPolicyEntry, AclConfigRule, AclConfigSubjects, AclMessage, CertCommonName,
-    InterceptorFlow, Interface, Permission, PolicyRule, Username,
+    InterceptorFlow, Interface, Permission, PolicyRule, PubKeyFingerprint, Username, WhatAmI,
+    ZenohId,
 };
 use zenoh_keyexpr::{
     keyexpr,
-    keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut, KeBoxTree},
+    keyexpr_tree::{IKeyExprTree, IKeyExprTreeMut, IKeyExprTreeNode, KeBoxTree},
+    OwnedKeyExpr,
 };
 use zenoh_result::ZResult;
 type PolicyForSubject = FlowPolicy;
 
 type PolicyMap = HashMap<usize, PolicyForSubject, RandomState>;
 
+/// Rules whose key expression is a template, per subject.
+type TemplateMap = HashMap<usize, Vec<PolicyRule>, RandomState>;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct Subject {
     pub(crate) interface: SubjectProperty<Interface>,
     pub(crate) cert_common_name: SubjectProperty<CertCommonName>,
     pub(crate) username: SubjectProperty<Username>,
+    pub(crate) pubkey_fingerprint: SubjectProperty<PubKeyFingerprint>,
+    pub(crate) zid: SubjectProperty<ZenohId>,
+    pub(crate) whatami: SubjectProperty<WhatAmI>,
 }
 
 impl Subject {
@@ -48,6 +56,11 @@ impl Subject {
             && self
                 .cert_common_name
                 .matches(query.cert_common_name.as_ref())
+            && self
+                .pubkey_fingerprint
+                .matches(query.pubkey_fingerprint.as_ref())
+            && self.zid.matches(query.zid.as_ref())
+            && self.whatami.matches(query.whatami.as_ref())
     }
 }
 
@@ -74,6 +87,9 @@ pub(crate) struct SubjectQuery {
     pub(crate) interface: Option<Interface>,
     pub(crate) cert_common_name: Option<CertCommonName>,
     pub(crate) username: Option<Username>,
+    pub(crate) pubkey_fingerprint: Option<PubKeyFingerprint>,
+    pub(crate) zid: Option<ZenohId>,
+    pub(crate) whatami: Option<WhatAmI>,
 }
 
 impl std::fmt::Display for SubjectQuery {
@@ -82,6 +98,13 @@ impl std::fmt::Display for SubjectQuery {
             self.interface.as_ref().map(|face| format!("{face}")),
             self.cert_common_name.as_ref().map(|ccn| format!("{ccn}")),
             self.username.as_ref().map(|username| format!("{username}")),
+            self.pubkey_fingerprint
+                .as_ref()
+                .map(|fingerprint| format!("{fingerprint}")),
+            self.zid.as_ref().map(|zid| format!("ZenohId({zid})")),
+            self.whatami
+                .as_ref()
+                .map(|whatami| format!("WhatAmI({whatami})")),
         ];
         write!(
             f,
@@ -157,7 +180,8 @@ impl SubjectMapBuilder {
     }
 }
 
-type KeTreeRule = KeBoxTree<bool>;
+/// Key expressions tree of a permission, weighted by the id of the first rule that set it.
+type KeTreeRule = KeBoxTree<Arc<str>>;
 
 #[derive(Default)]
 struct PermissionPolicy {
@@ -166,7 +190,6 @@ struct PermissionPolicy {
 }
 
 impl PermissionPolicy {
-    #[allow(dead_code)]
     fn permission(&self, permission: Permission) -> &KeTreeRule {
         match permission {
             Permission::Allow => &self.allow,
@@ -229,6 +252,31 @@ pub struct FlowPolicy {
 }
 
 impl FlowPolicy {
+    /// Returns the id of the rule setting `permission` on `key_expr`, if any.
+    fn matching_rule(
+        &self,
+        flow: InterceptorFlow,
+        message: AclMessage,
+        permission: Permission,
+        key_expr: &keyexpr,
+    ) -> Option<Arc<str>> {
+        self.flow(flow)
+            .action(message)
+            .permission(permission)
+            .nodes_including(key_expr)
+            .find_map(|node| node.weight().cloned())
+    }
+
+    fn insert(&mut self, rule: &PolicyRule, key_expr: &keyexpr) {
+        let rule_tree = self
+            .flow_mut(rule.flow)
+            .action_mut(rule.message)
+            .permission_mut(rule.permission);
+        if rule_tree.weight_at(key_expr).is_none() {
+            rule_tree.insert(key_expr, rule.rule_id.as_str().into());
+        }
+    }
+
     fn flow(&self, flow: InterceptorFlow) -> &ActionPolicy {
         match flow {
             InterceptorFlow::Ingress => &self.ingress,
@@ -254,6 +302,7 @@ pub struct PolicyEnforcer {
     pub(crate) default_permission: Permission,
     pub(crate) subject_store: SubjectStore,
     pub(crate) policy_map: PolicyMap,
+    pub(crate) template_map: TemplateMap,
     pub(crate) interface_enabled: InterfaceEnabled,
 }
 
@@ -270,6 +319,7 @@ impl PolicyEnforcer {
             default_permission: Permission::Deny,
             subject_store: SubjectStore::default(),
             policy_map: PolicyMap::default(),
+            template_map: TemplateMap::default(),
             interface_enabled: InterfaceEnabled::default(),
         }
     }
@@ -298,6 +348,7 @@ impl PolicyEnforcer {
                         tracing::warn!("Access control policies list is empty in config file")
                     });
                     self.policy_map = PolicyMap::default();
+                    self.template_map = TemplateMap::default();
                     self.subject_store = SubjectStore::default();
                     if self.default_permission == Permission::Deny {
                         self.interface_enabled = InterfaceEnabled {
@@ -338,18 +389,44 @@ impl PolicyEnforcer {
                         if subject.interfaces.as_ref().is_some_and(Vec::is_empty) {
                             bail!("Subject property `interfaces` cannot be empty");
                         }
+
+                        if subject
+                            .pubkey_fingerprints
+                            .as_ref()
+                            .is_some_and(Vec::is_empty)
+                        {
+                            bail!("Subject property `pubkey_fingerprints` cannot be empty");
+                        }
+
+                        if subject.zids.as_ref().is_some_and(Vec::is_empty) {
+                            bail!("Subject property `zids` cannot be empty");
+                        }
+
+                        if subject.whatamis.as_ref().is_some_and(Vec::is_empty) {
+                            bail!("Subject property `whatamis` cannot be empty");
+                        }
                     }
                     let policy_information =
                         self.policy_information_point(subjects, rules, policies)?;
 
                     let mut main_policy: PolicyMap = PolicyMap::default();
+                    let mut template_map: TemplateMap = TemplateMap::default();
                     for rule in policy_information.policy_rules {
                         let subject_policy = main_policy.entry(rule.subject_id).or_default();
-                        subject_policy
-                            .flow_mut(rule.flow)
-                            .action_mut(rule.message)
-                            .permission_mut(rule.permission)
-                            .insert(keyexpr::new(&rule.key_expr)?, true);
+                        if is_key_expr_template(&rule.key_expr) {
+                            // Templates are expanded for each transport matching the subject,
+                            // check them now with placeholder values
+                            expand_key_expr_template(&rule.key_expr, &SubjectQuery::sample())
+                                .map_err(|e| {
+                                    zerror!(
+                                        "Invalid key expression template in rule '{}': {}",
+                                        rule.rule_id,
+                                        e
+                                    )
+                                })?;
+                        } else {
+                            subject_policy.insert(&rule, keyexpr::new(&rule.key_expr)?);
+                        }
 
                         if self.default_permission == Permission::Deny {
                             self.interface_enabled = InterfaceEnabled {
@@ -366,8 +443,12 @@ impl PolicyEnforcer {
                                 }
                             }
                         }
+                        if is_key_expr_template(&rule.key_expr) {
+                            template_map.entry(rule.subject_id).or_default().push(rule);
+                        }
                     }
                     self.policy_map = main_policy;
+                    self.template_map = template_map;
                     self.subject_store = policy_information.subject_map;
                 }
             } else {
@@ -461,6 +542,20 @@ impl PolicyEnforcer {
                     config_subject.id
                 );
             }
+            if config_subject
+                .pubkey_fingerprints
+                .as_ref()
+                .is_some_and(|fingerprints| {
+                    fingerprints
+                        .iter()
+                        .any(|fingerprint| fingerprint.0.trim().is_empty())
+                })
+            {
+                bail!(
+                    "Found empty pubkey_fingerprint value in subject '{}'",
+                    config_subject.id
+                );
+            }
             // Map properties to SubjectProperty type
             // FIXME: Unnecessary .collect() because of different iterator types
             let interfaces = config_subject
@@ -492,20 +587,65 @@ impl PolicyEnforcer {
                         .collect::<Vec<_>>()
                 })
                 .unwrap_or(vec![SubjectProperty::Wildcard]);
+            // FIXME: Unnecessary .collect() because of different iterator types
+            let pubkey_fingerprints = config_subject
+                .pubkey_fingerprints
+                .map(|fingerprints| {
+                    fingerprints
+                        .into_iter()
+                        .map(|fingerprint| {
+                            // fingerprints are hex strings, compared case-insensitively
+                            SubjectProperty::Exactly(PubKeyFingerprint(
+                                fingerprint.0.to_ascii_lowercase(),
+                            ))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or(vec![SubjectProperty::Wildcard]);
+            // FIXME: Unnecessary .collect() because of different iterator types
+            let zids = config_subject
+                .zids
+                .map(|zids| {
+                    zids.into_iter()
+                        .map(SubjectProperty::Exactly)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or(vec![SubjectProperty::Wildcard]);
+            // FIXME: Unnecessary .collect() because of different iterator types
+            let whatamis = config_subject
+                .whatamis
+                .map(|whatamis| {
+                    whatamis
+                        .into_iter()
+                        .map(SubjectProperty::Exactly)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or(vec![SubjectProperty::Wildcard]);
 
             // create ACL subject combinations
             let subject_combination_ids = interfaces
                 .into_iter()
                 .cartesian_product(cert_common_names)
                 .cartesian_product(usernames)
-                .map(|((interface, cert_common_name), username)| {
-                    let subject = Subject {
-                        interface,
-                        cert_common_name,
-                        username,
-                    };
-                    subject_map_builder.insert_or_get(subject)
-                })
+                .cartesian_product(pubkey_fingerprints)
+                .cartesian_product(zids)
+                .cartesian_product(whatamis)
+                .map(
+                    |(
+                        ((((interface, cert_common_name), username), pubkey_fingerprint), zid),
+                        whatami,
+                    )| {
+                        let subject = Subject {
+                            interface,
+                            cert_common_name,
+                            username,
+                            pubkey_fingerprint,
+                            zid,
+                            whatami,
+                        };
+                        subject_map_builder.insert_or_get(subject)
+                    },
+                )
                 .collect();
             subject_id_map.insert(config_subject.id.clone(), subject_combination_ids);
         }
@@ -553,6 +693,7 @@ impl PolicyEnforcer {
                             for message in &rule.messages {
                                 for key_expr in &rule.key_exprs {
                                     policy_rules.push(PolicyRule {
+                                        rule_id: rule.id.clone(),
                                         subject_id: *subject_id,
                                         key_expr: key_expr.clone(),
                                         message: *message,
@@ -573,48 +714,131 @@ impl PolicyEnforcer {
     }
 
     /**
-     * Check each msg against the ACL ruleset for allow/deny
+     * Check each msg against the ACL ruleset for allow/deny,
+     * returning the id of the rule that led to the decision (if any)
      */
     pub fn policy_decision_point(
         &self,
         subject: usize,
+        templated_policy: Option<&FlowPolicy>,
         flow: InterceptorFlow,
         message: AclMessage,
         key_expr: &str,
-    ) -> ZResult<Permission> {
+    ) -> ZResult<(Permission, Option<Arc<str>>)> {
         let policy_map = &self.policy_map;
         if policy_map.is_empty() {
-            return Ok(self.default_permission);
+            return Ok((self.default_permission, None));
         }
         match policy_map.get(&subject) {
             Some(single_policy) => {
-                let deny_result = single_policy
-                    .flow(flow)
-                    .action(message)
-                    .deny
-                    .nodes_including(keyexpr::new(&key_expr)?)
-                    .count();
-                if deny_result != 0 {
-                    return Ok(Permission::Deny);
+                let key_expr = keyexpr::new(&key_expr)?;
+                let policies = || iter::once(single_policy).chain(templated_policy);
+                let deny_result = policies().find_map(|policy| {
+                    policy.matching_rule(flow, message, Permission::Deny, key_expr)
+                });
+                if let Some(rule_id) = deny_result {
+                    return Ok((Permission::Deny, Some(rule_id)));
                 }
                 if self.default_permission == Permission::Allow {
-                    Ok(Permission::Allow)
+                    Ok((Permission::Allow, None))
                 } else {
-                    let allow_result = single_policy
-                        .flow(flow)
-                        .action(message)
-                        .allow
-                        .nodes_including(keyexpr::new(&key_expr)?)
-                        .count();
-
-                    if allow_result != 0 {
-                        Ok(Permission::Allow)
-                    } else {
-                        Ok(Permission::Deny)
+                    let allow_result = policies().find_map(|policy| {
+                        policy.matching_rule(flow, message, Permission::Allow, key_expr)
+                    });
+
+                    match allow_result {
+                        Some(rule_id) => Ok((Permission::Allow, Some(rule_id))),
+                        None => Ok((Permission::Deny, None)),
                     }
                 }
             }
-            None => Ok(self.default_permission),
+            None => Ok((self.default_permission, None)),
+        }
+    }
+
+    /// Expands the key expression templates of the rules of `subject` with the attributes of
+    /// the transport described by `query`, returning `None` if the subject has no such rules.
+    ///
+    /// A templated key expression is ignored if one of its placeholders has no value for the
+    /// transport, or if the value is not a valid key expression chunk.
+    pub(crate) fn templated_policy(
+        &self,
+        subject: usize,
+        query: &SubjectQuery,
+    ) -> Option<FlowPolicy> {
+        let rules = self.template_map.get(&subject)?;
+        let mut policy = FlowPolicy::default();
+        for rule in rules {
+            match expand_key_expr_template(&rule.key_expr, query) {
+                Ok(Some(key_expr)) => policy.insert(rule, &key_expr),
+                Ok(None) => tracing::debug!(
+                    "Key expression '{}' of rule '{}' is not applied to {}: missing placeholder value",
+                    rule.key_expr,
+                    rule.rule_id,
+                    query
+                ),
+                Err(e) => tracing::warn!(
+                    "Key expression '{}' of rule '{}' is not applied to {}: {}",
+                    rule.key_expr,
+                    rule.rule_id,
+                    query,
+                    e
+                ),
+            }
+        }
+        Some(policy)
+    }
+}
+
+impl SubjectQuery {
+    /// A query with a value for every key expression template placeholder.
+    fn sample() -> Self {
+        SubjectQuery {
+            interface: None,
+            cert_common_name: Some(CertCommonName("cert_common_name".into())),
+            username: Some(Username("username".into())),
+            pubkey_fingerprint: None,
+            zid: Some(ZenohId::default()),
+            whatami: None,
+        }
+    }
+}
+
+fn is_key_expr_template(key_expr: &str) -> bool {
+    key_expr.contains("${")
+}
+
+/// Substitutes the `${username}`, `${cert_common_name}` and `${zid}` placeholders of `template`
+/// with the attributes of `query`, returning `None` if one of them has no value.
+fn expand_key_expr_template(template: &str, query: &SubjectQuery) -> ZResult<Option<OwnedKeyExpr>> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| zerror!("unterminated placeholder in '{}'", template))?
+            + start;
+        let value = match &rest[start + 2..end] {
+            "username" => query.username.as_ref().map(|username| username.0.clone()),
+            "cert_common_name" => query.cert_common_name.as_ref().map(|ccn| ccn.0.clone()),
+            "zid" => query.zid.as_ref().map(ZenohId::to_string),
+            placeholder => bail!(
+                "unknown placeholder '${{{}}}' in '{}'",
+                placeholder,
+                template
+            ),
+        };
+        let Some(value) = value else {
+            return Ok(None);
+        };
+        // The value must not change the structure of the key expression
+        if value.contains('/') || keyexpr::new(&value).map_or(true, |ke| ke.is_wild()) {
+            bail!("'{}' is not a valid key expression chunk", value);
         }
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &rest[end + 1..];
     }
+    expanded.push_str(rest);
+    Ok(Some(OwnedKeyExpr::try_from(expanded)?))
 }
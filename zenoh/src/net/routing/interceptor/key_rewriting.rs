@@ -0,0 +1,218 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
+
+use std::cell::OnceCell;
+
+use zenoh_config::{
+    CertCommonName, InterceptorFlow, Interface, KeyRewritingItemConf, KeyRewritingRuleConf,
+    Username,
+};
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+use zenoh_protocol::{
+    core::{WireExpr, EMPTY_EXPR_ID},
+    network::{Declare, DeclareBody, NetworkBody},
+};
+use zenoh_result::ZResult;
+
+use crate::net::routing::interceptor::*;
+
+pub(crate) fn key_rewriting_interceptor_factories(
+    config: &Vec<KeyRewritingItemConf>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for kr in config {
+        res.push(Box::new(KeyRewritingInterceptorFactory::new(kr.clone())?));
+    }
+
+    Ok(res)
+}
+
+pub(crate) struct KeyRewritingInterceptorFactory {
+    interfaces: Option<Vec<Interface>>,
+    cert_common_names: Option<Vec<CertCommonName>>,
+    usernames: Option<Vec<Username>>,
+    ingress: bool,
+    egress: bool,
+    rules: Vec<KeyRewritingRuleConf>,
+}
+
+impl KeyRewritingInterceptorFactory {
+    pub(crate) fn new(conf: KeyRewritingItemConf) -> ZResult<Self> {
+        for rule in &conf.rules {
+            if rule.remote.is_wild() || rule.local.is_wild() {
+                bail!(
+                    "Key rewriting rule '{}' <-> '{}' is invalid: prefixes cannot contain wildcards",
+                    rule.remote,
+                    rule.local
+                );
+            }
+        }
+        if conf.interfaces.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Key rewriting property `interfaces` cannot be empty");
+        }
+        if conf.cert_common_names.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Key rewriting property `cert_common_names` cannot be empty");
+        }
+        if conf.usernames.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Key rewriting property `usernames` cannot be empty");
+        }
+        let (ingress, egress) = match &conf.flows {
+            Some(flows) if flows.is_empty() => {
+                bail!("Key rewriting property `flows` cannot be empty")
+            }
+            Some(flows) => (
+                flows.iter().any(|f| matches!(f, InterceptorFlow::Ingress)),
+                flows.iter().any(|f| matches!(f, InterceptorFlow::Egress)),
+            ),
+            None => (true, true),
+        };
+        Ok(Self {
+            interfaces: conf.interfaces,
+            cert_common_names: conf.cert_common_names,
+            usernames: conf.usernames,
+            ingress,
+            egress,
+            rules: conf.rules,
+        })
+    }
+}
+
+impl InterceptorFactoryTrait for KeyRewritingInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New key rewriter transport unicast {:?}", transport);
+        if !transport_matches(
+            transport,
+            self.interfaces.as_deref(),
+            self.cert_common_names.as_deref(),
+            self.usernames.as_deref(),
+        ) {
+            return (None, None);
+        }
+
+        let ingress: Option<IngressInterceptor> = self.ingress.then(|| {
+            Box::new(ComputeOnMiss::new(KeyRewritingInterceptor::new(
+                self.rules
+                    .iter()
+                    .map(|r| (r.remote.clone(), r.local.clone())),
+            ))) as IngressInterceptor
+        });
+        let egress: Option<EgressInterceptor> = self.egress.then(|| {
+            Box::new(ComputeOnMiss::new(KeyRewritingInterceptor::new(
+                self.rules
+                    .iter()
+                    .map(|r| (r.local.clone(), r.remote.clone())),
+            ))) as EgressInterceptor
+        });
+        (ingress, egress)
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        None
+    }
+}
+
+pub(crate) struct KeyRewritingInterceptor {
+    /// `(from, to)` prefixes, sorted by decreasing `from` length so that the longest prefix matches first.
+    rules: Vec<(OwnedKeyExpr, OwnedKeyExpr)>,
+}
+
+impl KeyRewritingInterceptor {
+    pub(crate) fn new(rules: impl Iterator<Item = (OwnedKeyExpr, OwnedKeyExpr)>) -> Self {
+        let mut rules: Vec<_> = rules.collect();
+        rules.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        for (from, to) in &rules {
+            tracing::debug!("New key rewriter rule enabled: {} -> {}", from, to);
+        }
+        Self { rules }
+    }
+
+    fn rewrite(&self, key_expr: &keyexpr) -> Option<OwnedKeyExpr> {
+        self.rules
+            .iter()
+            .find_map(|(from, to)| replace_prefix(key_expr, from, to))
+    }
+}
+
+/// Replaces the `from` chunks prefix of `key_expr` with `to`, if `key_expr` starts with `from`.
+fn replace_prefix(key_expr: &keyexpr, from: &keyexpr, to: &keyexpr) -> Option<OwnedKeyExpr> {
+    let rest = key_expr.as_str().strip_prefix(from.as_str())?;
+    if rest.is_empty() {
+        Some(to.into())
+    } else if rest.starts_with('/') {
+        OwnedKeyExpr::try_from(format!("{to}{rest}")).ok()
+    } else {
+        None
+    }
+}
+
+impl InterceptorTrait for KeyRewritingInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        Some(Box::new(self.rewrite(key_expr)))
+    }
+
+    fn intercept(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        // Key expressions declarations are left untouched: the messages referring to them
+        // are rewritten with full key expressions instead.
+        if matches!(
+            ctx.msg.body,
+            NetworkBody::Declare(Declare {
+                body: DeclareBody::DeclareKeyExpr(_),
+                ..
+            })
+        ) {
+            return Some(ctx);
+        }
+        let Some(cache) = cache else {
+            return Some(ctx);
+        };
+        let Some(rewritten) = cache.downcast_ref::<Option<OwnedKeyExpr>>() else {
+            tracing::debug!("unexpected cache type {:?}", ctx.full_expr());
+            return Some(ctx);
+        };
+        if let Some(rewritten) = rewritten {
+            if let Some(wire_expr) = ctx.wire_expr_mut() {
+                tracing::trace!("Key rewriter rewriting {:?} to {}", wire_expr, rewritten);
+                *wire_expr = WireExpr {
+                    scope: EMPTY_EXPR_ID,
+                    suffix: rewritten.to_string().into(),
+                    mapping: wire_expr.mapping,
+                };
+                ctx.prefix = OnceCell::new();
+                ctx.full_expr = OnceCell::from(rewritten.to_string());
+            }
+        }
+        Some(ctx)
+    }
+}
@@ -21,20 +21,35 @@
 mod access_control;
 use access_control::acl_interceptor_factories;
 
+mod audit;
 mod authorization;
-use std::any::Any;
+use std::{
+    any::Any,
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::{Arc, OnceLock},
+};
 
-use zenoh_config::Config;
+use zenoh_config::{CertCommonName, Config, Interface, Username};
 use zenoh_protocol::network::NetworkMessage;
 use zenoh_result::ZResult;
-use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast};
+use zenoh_transport::{
+    multicast::TransportMulticast,
+    unicast::{authentication::AuthId, TransportUnicast},
+};
 
 use super::RoutingContext;
-use crate::api::key_expr::KeyExpr;
+use crate::{api::key_expr::KeyExpr, net::runtime::Runtime};
 
 pub mod downsampling;
 use crate::net::routing::interceptor::downsampling::downsampling_interceptor_factories;
 
+mod rate_limiting;
+use rate_limiting::rate_limiting_interceptor_factories;
+
+mod key_rewriting;
+use key_rewriting::key_rewriting_interceptor_factories;
+
 pub(crate) trait InterceptorTrait {
     fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>>;
 
@@ -56,6 +71,13 @@ pub(crate) trait InterceptorFactoryTrait {
     ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>);
     fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<EgressInterceptor>;
     fn new_peer_multicast(&self, transport: &TransportMulticast) -> Option<IngressInterceptor>;
+    /// Called when the configuration is modified at runtime, allowing the factory and the
+    /// interceptors it created to reload their configuration.
+    fn update_config(&self, _config: &Config) -> ZResult<()> {
+        Ok(())
+    }
+    /// Called once the runtime owning the routing tables is built.
+    fn set_runtime(&self, _runtime: &Runtime) {}
 }
 
 pub(crate) type InterceptorFactory = Box<dyn InterceptorFactoryTrait + Send + Sync>;
@@ -65,10 +87,59 @@ pub(crate) fn interceptor_factories(config: &Config) -> ZResult<Vec<InterceptorF
     // Uncomment to log the interceptors initialisation
     // res.push(Box::new(LoggerInterceptor {}));
     res.extend(downsampling_interceptor_factories(config.downsampling())?);
+    res.extend(rate_limiting_interceptor_factories(config.rate_limiting())?);
     res.extend(acl_interceptor_factories(config.access_control())?);
+    // Key rewriting must come last as it invalidates the key expressions caches of the chain
+    res.extend(key_rewriting_interceptor_factories(config.key_rewriting())?);
     Ok(res)
 }
 
+/// Checks whether a transport matches the optional lists of interfaces, TLS certificate common names
+/// and usernames of an interceptor configuration. An absent list matches any transport.
+pub(crate) fn transport_matches(
+    transport: &TransportUnicast,
+    interfaces: Option<&[Interface]>,
+    cert_common_names: Option<&[CertCommonName]>,
+    usernames: Option<&[Username]>,
+) -> bool {
+    if let Some(interfaces) = interfaces {
+        let Ok(links) = transport.get_links() else {
+            return false;
+        };
+        if !links
+            .iter()
+            .flat_map(|link| link.interfaces.iter())
+            .any(|face| interfaces.iter().any(|i| &i.0 == face))
+        {
+            return false;
+        }
+    }
+    if cert_common_names.is_none() && usernames.is_none() {
+        return true;
+    }
+    let Ok(auth_ids) = transport.get_auth_ids() else {
+        return false;
+    };
+    let mut cert_common_name_matched = cert_common_names.is_none();
+    let mut username_matched = usernames.is_none();
+    for auth_id in auth_ids {
+        match auth_id {
+            AuthId::CertCommonName(value) => {
+                if let Some(ccns) = cert_common_names {
+                    cert_common_name_matched |= ccns.iter().any(|ccn| ccn.0 == value);
+                }
+            }
+            AuthId::Username(value) => {
+                if let Some(usernames) = usernames {
+                    username_matched |= usernames.iter().any(|u| u.0 == value);
+                }
+            }
+            AuthId::PubKeyFingerprint(_) | AuthId::None => {}
+        }
+    }
+    cert_common_name_matched && username_matched
+}
+
 pub(crate) struct InterceptorsChain {
     pub(crate) interceptors: Vec<Interceptor>,
 }
@@ -159,6 +230,101 @@ impl<T: InterceptorTrait> InterceptorTrait for ComputeOnMiss<T> {
     }
 }
 
+type DelayedMessage = (tokio::time::Instant, NetworkMessage);
+
+/// A delayed message waiting in a [`DelayQueue`], ordered by deadline then by arrival so that the
+/// messages sharing a deadline are sent in the order they were delayed.
+struct PendingMessage {
+    deadline: tokio::time::Instant,
+    seq: u64,
+    msg: NetworkMessage,
+}
+
+impl PartialEq for PendingMessage {
+    fn eq(&self, other: &Self) -> bool {
+        (self.deadline, self.seq) == (other.deadline, other.seq)
+    }
+}
+
+impl Eq for PendingMessage {}
+
+impl PartialOrd for PendingMessage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingMessage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.deadline, self.seq).cmp(&(other.deadline, other.seq))
+    }
+}
+
+/// Sends the messages delayed by the interceptors of a transport once their
+/// [`RoutingContext::delayed_until`] is reached, without blocking the thread that intercepted them.
+///
+/// The messages are sent by a single task, spawned with the first delayed message and stopped when
+/// the queue is dropped. They are sent in the order of their deadlines, so that a message delayed
+/// by a rule does not hold up the messages with earlier deadlines. The deadlines given by a rate
+/// limiter to the messages of a key never decrease, which preserves their order.
+pub(crate) struct DelayQueue {
+    send: Arc<dyn Fn(NetworkMessage) + Send + Sync>,
+    queue: OnceLock<flume::Sender<DelayedMessage>>,
+}
+
+impl DelayQueue {
+    pub(crate) fn new(send: impl Fn(NetworkMessage) + Send + Sync + 'static) -> Self {
+        Self {
+            send: Arc::new(send),
+            queue: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn push(&self, deadline: tokio::time::Instant, msg: NetworkMessage) {
+        let queue = self.queue.get_or_init(|| {
+            let (tx, rx) = flume::unbounded::<DelayedMessage>();
+            let send = self.send.clone();
+            zenoh_runtime::ZRuntime::Net.spawn(async move {
+                let mut pending = BinaryHeap::new();
+                let mut seq = 0u64;
+                loop {
+                    let next = pending
+                        .peek()
+                        .map(|Reverse(m): &Reverse<PendingMessage>| m.deadline);
+                    let expired = async {
+                        match next {
+                            Some(deadline) => tokio::time::sleep_until(deadline).await,
+                            None => std::future::pending().await,
+                        }
+                    };
+                    tokio::select! {
+                        received = rx.recv_async() => {
+                            let Ok((deadline, msg)) = received else {
+                                break;
+                            };
+                            pending.push(Reverse(PendingMessage { deadline, seq, msg }));
+                            seq += 1;
+                        }
+                        _ = expired => {
+                            let now = tokio::time::Instant::now();
+                            while let Some(Reverse(m)) = pending.peek() {
+                                if m.deadline > now {
+                                    break;
+                                }
+                                if let Some(Reverse(m)) = pending.pop() {
+                                    send(m.msg);
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+            tx
+        });
+        let _ = queue.send((deadline, msg));
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) struct IngressMsgLogger {}
 
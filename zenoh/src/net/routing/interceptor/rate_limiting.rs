@@ -0,0 +1,385 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](https://docs.rs/zenoh/latest/zenoh)
+
+use std::sync::Mutex;
+
+use zenoh_buffers::buffer::Buffer;
+use zenoh_config::{
+    CertCommonName, InterceptorFlow, Interface, RateLimitItemConf, RateLimitRuleConf,
+    RateLimitStrategy, Username,
+};
+use zenoh_core::zlock;
+use zenoh_keyexpr::keyexpr_tree::{
+    impls::KeyedSetProvider, support::UnknownWildness, IKeyExprTree, IKeyExprTreeMut, KeBoxTree,
+};
+use zenoh_protocol::{
+    core::WireExpr,
+    network::{NetworkBody, Push, Request, Response},
+    zenoh::{PushBody, RequestBody, ResponseBody},
+};
+use zenoh_result::ZResult;
+
+use crate::net::routing::interceptor::*;
+
+const DEFAULT_MAX_DELAY_MS: u64 = 100;
+
+pub(crate) fn rate_limiting_interceptor_factories(
+    config: &Vec<RateLimitItemConf>,
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+
+    for rl in config {
+        res.push(Box::new(RateLimitingInterceptorFactory::new(rl.clone())?));
+    }
+
+    Ok(res)
+}
+
+pub(crate) struct RateLimitingInterceptorFactory {
+    interfaces: Option<Vec<Interface>>,
+    cert_common_names: Option<Vec<CertCommonName>>,
+    usernames: Option<Vec<Username>>,
+    flow: InterceptorFlow,
+    strategy: RateLimitStrategy,
+    max_delay: tokio::time::Duration,
+    rules: Vec<RateLimitRuleConf>,
+}
+
+impl RateLimitingInterceptorFactory {
+    pub(crate) fn new(conf: RateLimitItemConf) -> ZResult<Self> {
+        for rule in &conf.rules {
+            if rule.messages_per_sec.is_none() && rule.bytes_per_sec.is_none() {
+                bail!(
+                    "Rate limiting rule for '{}' must set `messages_per_sec` and/or `bytes_per_sec`",
+                    rule.key_expr
+                );
+            }
+            for (name, value) in [
+                ("messages_per_sec", rule.messages_per_sec),
+                ("bytes_per_sec", rule.bytes_per_sec),
+                ("messages_burst", rule.messages_burst),
+                ("bytes_burst", rule.bytes_burst),
+            ] {
+                if value.is_some_and(|v| !v.is_finite() || v <= 0.0) {
+                    bail!(
+                        "Rate limiting rule for '{}' has invalid `{}`: must be a positive number",
+                        rule.key_expr,
+                        name
+                    );
+                }
+            }
+        }
+        if conf.interfaces.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Rate limiting property `interfaces` cannot be empty");
+        }
+        if conf.cert_common_names.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Rate limiting property `cert_common_names` cannot be empty");
+        }
+        if conf.usernames.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Rate limiting property `usernames` cannot be empty");
+        }
+        Ok(Self {
+            interfaces: conf.interfaces,
+            cert_common_names: conf.cert_common_names,
+            usernames: conf.usernames,
+            flow: conf.flow,
+            strategy: conf.strategy,
+            max_delay: tokio::time::Duration::from_millis(
+                conf.max_delay_ms.unwrap_or(DEFAULT_MAX_DELAY_MS),
+            ),
+            rules: conf.rules,
+        })
+    }
+}
+
+impl InterceptorFactoryTrait for RateLimitingInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<IngressInterceptor>, Option<EgressInterceptor>) {
+        tracing::debug!("New rate limiter transport unicast {:?}", transport);
+        if !transport_matches(
+            transport,
+            self.interfaces.as_deref(),
+            self.cert_common_names.as_deref(),
+            self.usernames.as_deref(),
+        ) {
+            return (None, None);
+        }
+
+        let interceptor = Box::new(ComputeOnMiss::new(RateLimitingInterceptor::new(
+            self.rules.clone(),
+            self.strategy,
+            self.max_delay,
+        )));
+        match self.flow {
+            InterceptorFlow::Ingress => (Some(interceptor), None),
+            InterceptorFlow::Egress => (None, Some(interceptor)),
+        }
+    }
+
+    fn new_transport_multicast(
+        &self,
+        _transport: &TransportMulticast,
+    ) -> Option<EgressInterceptor> {
+        None
+    }
+
+    fn new_peer_multicast(&self, _transport: &TransportMulticast) -> Option<IngressInterceptor> {
+        None
+    }
+}
+
+/// A token bucket refilled continuously at `rate` tokens per second up to `capacity`.
+///
+/// The amount of tokens may become negative when a message is admitted with a delay:
+/// the debt is paid back by the refill before any following message is admitted.
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: tokio::time::Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64, capacity: f64, now: tokio::time::Instant) -> Self {
+        Self {
+            rate,
+            capacity,
+            tokens: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: tokio::time::Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time to wait before `amount` tokens are available, `None` if they never will be.
+    fn wait_time(&self, amount: f64) -> Option<tokio::time::Duration> {
+        if amount > self.capacity {
+            None
+        } else if self.tokens >= amount {
+            Some(tokio::time::Duration::ZERO)
+        } else {
+            Some(tokio::time::Duration::from_secs_f64(
+                (amount - self.tokens) / self.rate,
+            ))
+        }
+    }
+
+    fn consume(&mut self, amount: f64) {
+        self.tokens -= amount;
+    }
+}
+
+struct RuleState {
+    messages: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl RuleState {
+    fn new(rule: &RateLimitRuleConf, now: tokio::time::Instant) -> Self {
+        Self {
+            messages: rule.messages_per_sec.map(|rate| {
+                TokenBucket::new(rate, rule.messages_burst.unwrap_or(rate.max(1.0)), now)
+            }),
+            bytes: rule
+                .bytes_per_sec
+                .map(|rate| TokenBucket::new(rate, rule.bytes_burst.unwrap_or(rate), now)),
+        }
+    }
+
+    /// Tries to admit a message of `size` bytes, returning the time the message has to be delayed
+    /// or `None` if it must be dropped.
+    fn admit(
+        &mut self,
+        size: usize,
+        strategy: RateLimitStrategy,
+        max_delay: tokio::time::Duration,
+    ) -> Option<tokio::time::Duration> {
+        let now = tokio::time::Instant::now();
+        let mut wait = tokio::time::Duration::ZERO;
+        if let Some(bucket) = self.messages.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_time(1.0)?);
+        }
+        if let Some(bucket) = self.bytes.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_time(size as f64)?);
+        }
+        let admitted = match strategy {
+            RateLimitStrategy::Drop => wait.is_zero(),
+            RateLimitStrategy::Delay => wait <= max_delay,
+        };
+        if !admitted {
+            return None;
+        }
+        if let Some(bucket) = self.messages.as_mut() {
+            bucket.consume(1.0);
+        }
+        if let Some(bucket) = self.bytes.as_mut() {
+            bucket.consume(size as f64);
+        }
+        Some(wait)
+    }
+}
+
+pub(crate) struct RateLimitingInterceptor {
+    ke_id: Mutex<KeBoxTree<usize, UnknownWildness, KeyedSetProvider>>,
+    ke_state: Mutex<Vec<RuleState>>,
+    strategy: RateLimitStrategy,
+    max_delay: tokio::time::Duration,
+}
+
+impl RateLimitingInterceptor {
+    pub(crate) fn new(
+        rules: Vec<RateLimitRuleConf>,
+        strategy: RateLimitStrategy,
+        max_delay: tokio::time::Duration,
+    ) -> Self {
+        let now = tokio::time::Instant::now();
+        let mut ke_id = KeBoxTree::default();
+        let mut ke_state = Vec::with_capacity(rules.len());
+        for (id, rule) in rules.iter().enumerate() {
+            ke_id.insert(&rule.key_expr, id);
+            ke_state.push(RuleState::new(rule, now));
+            tracing::debug!(
+                "New rate limiter rule enabled: key_expr={:?}, messages_per_sec={:?}, bytes_per_sec={:?}",
+                rule.key_expr,
+                rule.messages_per_sec,
+                rule.bytes_per_sec,
+            );
+        }
+        Self {
+            ke_id: Mutex::new(ke_id),
+            ke_state: Mutex::new(ke_state),
+            strategy,
+            max_delay,
+        }
+    }
+}
+
+fn push_payload_size(body: &PushBody) -> usize {
+    match body {
+        PushBody::Put(p) => {
+            p.payload.len() + p.ext_attachment.as_ref().map_or(0, |a| a.buffer.len())
+        }
+        PushBody::Del(d) => d.ext_attachment.as_ref().map_or(0, |a| a.buffer.len()),
+    }
+}
+
+/// Returns the payload size of the messages subject to rate limiting.
+fn payload_size(body: &NetworkBody) -> Option<usize> {
+    match body {
+        NetworkBody::Push(Push { payload, .. }) => Some(push_payload_size(payload)),
+        NetworkBody::Request(Request {
+            payload: RequestBody::Query(q),
+            ..
+        }) => Some(q.ext_body.as_ref().map_or(0, |b| b.payload.len())),
+        NetworkBody::Response(Response { payload, .. }) => match payload {
+            ResponseBody::Reply(r) => Some(push_payload_size(&r.payload)),
+            ResponseBody::Err(e) => Some(e.payload.len()),
+        },
+        _ => None,
+    }
+}
+
+/// Replaces the wire expression of a delayed message by its full key expression, so that it does
+/// not refer to a key expression declaration that may be undeclared before the message is sent.
+fn resolve_wire_expr(ctx: &mut RoutingContext<NetworkMessage>) {
+    let Some(full_expr) = ctx.full_expr().map(str::to_string) else {
+        return;
+    };
+    let wire_expr = match &mut ctx.msg.body {
+        NetworkBody::Push(m) => &mut m.wire_expr,
+        NetworkBody::Request(m) => &mut m.wire_expr,
+        NetworkBody::Response(m) => &mut m.wire_expr,
+        _ => return,
+    };
+    *wire_expr = WireExpr {
+        scope: 0,
+        suffix: full_expr.into(),
+        mapping: wire_expr.mapping,
+    };
+}
+
+impl InterceptorTrait for RateLimitingInterceptor {
+    fn compute_keyexpr_cache(&self, key_expr: &KeyExpr<'_>) -> Option<Box<dyn Any + Send + Sync>> {
+        let ke_id = zlock!(self.ke_id);
+        if let Some(node) = ke_id.intersecting_keys(key_expr).next() {
+            if let Some(id) = ke_id.weight_at(&node) {
+                return Some(Box::new(Some(*id)));
+            }
+        }
+        Some(Box::new(None::<usize>))
+    }
+
+    fn intercept(
+        &self,
+        mut ctx: RoutingContext<NetworkMessage>,
+        cache: Option<&Box<dyn Any + Send + Sync>>,
+    ) -> Option<RoutingContext<NetworkMessage>> {
+        let Some(size) = payload_size(&ctx.msg.body) else {
+            return Some(ctx);
+        };
+        let Some(cache) = cache else {
+            return Some(ctx);
+        };
+        let Some(id) = cache.downcast_ref::<Option<usize>>() else {
+            tracing::debug!("unexpected cache type {:?}", ctx.full_expr());
+            return Some(ctx);
+        };
+        let Some(id) = id else {
+            return Some(ctx);
+        };
+
+        let wait = {
+            let mut ke_state = zlock!(self.ke_state);
+            match ke_state.get_mut(*id) {
+                Some(state) => state.admit(size, self.strategy, self.max_delay),
+                None => {
+                    tracing::debug!("unexpected cache ID {}", id);
+                    return Some(ctx);
+                }
+            }
+        };
+        match wait {
+            Some(wait) => {
+                if !wait.is_zero() {
+                    tracing::trace!("Rate limiter delaying {:?} by {:?}", ctx.full_expr(), wait);
+                    // The message is sent later by the transport's delay queue: sleeping here would
+                    // block every message received or sent on the transport
+                    resolve_wire_expr(&mut ctx);
+                    let deadline = tokio::time::Instant::now() + wait;
+                    ctx.delayed_until =
+                        Some(ctx.delayed_until.map_or(deadline, |d| d.max(deadline)));
+                }
+                Some(ctx)
+            }
+            None => {
+                tracing::trace!("Rate limiter dropping {:?}", ctx.full_expr());
+                None
+            }
+        }
+    }
+}
@@ -38,6 +38,8 @@ pub(crate) struct RoutingContext<Msg> {
     pub(crate) outface: OnceCell<Face>,
     pub(crate) prefix: OnceCell<Arc<Resource>>,
     pub(crate) full_expr: OnceCell<String>,
+    /// Set by the interceptors delaying the message: it must not be sent before this instant.
+    pub(crate) delayed_until: Option<tokio::time::Instant>,
 }
 
 impl<Msg> RoutingContext<Msg> {
@@ -49,6 +51,7 @@ impl<Msg> RoutingContext<Msg> {
             outface: OnceCell::new(),
             prefix: OnceCell::new(),
             full_expr: OnceCell::new(),
+            delayed_until: None,
         }
     }
 
@@ -60,6 +63,7 @@ impl<Msg> RoutingContext<Msg> {
             outface: OnceCell::new(),
             prefix: OnceCell::new(),
             full_expr: OnceCell::new(),
+            delayed_until: None,
         }
     }
 
@@ -71,6 +75,7 @@ impl<Msg> RoutingContext<Msg> {
             outface: OnceCell::from(outface),
             prefix: OnceCell::new(),
             full_expr: OnceCell::new(),
+            delayed_until: None,
         }
     }
 
@@ -82,6 +87,7 @@ impl<Msg> RoutingContext<Msg> {
             outface: OnceCell::new(),
             prefix: OnceCell::new(),
             full_expr: OnceCell::from(expr),
+            delayed_until: None,
         }
     }
 
@@ -121,6 +127,30 @@ impl RoutingContext<NetworkMessage> {
         }
     }
 
+    #[inline]
+    pub(crate) fn wire_expr_mut(&mut self) -> Option<&mut WireExpr<'static>> {
+        use zenoh_protocol::network::{DeclareBody, NetworkBody};
+        match &mut self.msg.body {
+            NetworkBody::Push(m) => Some(&mut m.wire_expr),
+            NetworkBody::Request(m) => Some(&mut m.wire_expr),
+            NetworkBody::Response(m) => Some(&mut m.wire_expr),
+            NetworkBody::ResponseFinal(_) => None,
+            NetworkBody::Interest(m) => m.wire_expr.as_mut(),
+            NetworkBody::Declare(m) => match &mut m.body {
+                DeclareBody::DeclareKeyExpr(m) => Some(&mut m.wire_expr),
+                DeclareBody::UndeclareKeyExpr(_) => None,
+                DeclareBody::DeclareSubscriber(m) => Some(&mut m.wire_expr),
+                DeclareBody::UndeclareSubscriber(m) => Some(&mut m.ext_wire_expr.wire_expr),
+                DeclareBody::DeclareQueryable(m) => Some(&mut m.wire_expr),
+                DeclareBody::UndeclareQueryable(m) => Some(&mut m.ext_wire_expr.wire_expr),
+                DeclareBody::DeclareToken(m) => Some(&mut m.wire_expr),
+                DeclareBody::UndeclareToken(m) => Some(&mut m.ext_wire_expr.wire_expr),
+                DeclareBody::DeclareFinal(_) => None,
+            },
+            NetworkBody::OAM(_) => None,
+        }
+    }
+
     #[inline]
     pub(crate) fn prefix(&self) -> Option<&Arc<Resource>> {
         if let Some(face) = self.outface.get() {
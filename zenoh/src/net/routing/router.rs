@@ -23,6 +23,8 @@ use zenoh_protocol::core::{WhatAmI, ZenohIdProto};
 use zenoh_result::ZResult;
 use zenoh_transport::{multicast::TransportMulticast, unicast::TransportUnicast, TransportPeer};
 
+#[cfg(feature = "stats")]
+use super::dispatcher::stats::KeyExprStatsCollector;
 pub(crate) use super::dispatcher::token::*;
 pub use super::dispatcher::{pubsub::*, queries::*, resource::*};
 use super::{
@@ -57,6 +59,8 @@ impl Router {
                 tables: RwLock::new(Tables::new(zid, whatami, hlc, config)?),
                 ctrl_lock: Mutex::new(hat::new_hat(whatami, config)),
                 queries_lock: RwLock::new(()),
+                #[cfg(feature = "stats")]
+                key_expr_stats: KeyExprStatsCollector::new(config)?,
             }),
         })
     }
@@ -65,9 +69,22 @@ impl Router {
         let ctrl_lock = zlock!(self.tables.ctrl_lock);
         let mut tables = zwrite!(self.tables.tables);
         tables.runtime = Some(Runtime::downgrade(&runtime));
+        for interceptor in &tables.interceptors {
+            interceptor.set_runtime(&runtime);
+        }
         ctrl_lock.init(&mut tables, runtime)
     }
 
+    /// Propagates a runtime configuration update to the interceptors.
+    pub(crate) fn update_interceptors_config(&self, config: &Config) {
+        let tables = zread!(self.tables.tables);
+        for interceptor in &tables.interceptors {
+            if let Err(e) = interceptor.update_config(config) {
+                tracing::error!("Error updating interceptor configuration: {}", e);
+            }
+        }
+    }
+
     pub(crate) fn new_primitives(
         &self,
         primitives: Arc<dyn EPrimitives + Send + Sync>,
@@ -92,6 +109,7 @@ impl Router {
                     None,
                     None,
                     ctrl_lock.new_face(),
+                    true,
                 )
             })
             .clone();
@@ -123,6 +141,7 @@ impl Router {
         let fid = tables.face_counter;
         tables.face_counter += 1;
         let zid = transport.get_zid()?;
+        let query_cancellation = transport.get_patch()?.has_query_cancellation();
         #[cfg(feature = "stats")]
         let stats = transport.get_stats()?;
         let (ingress, egress): (Vec<_>, Vec<_>) = tables
@@ -151,6 +170,7 @@ impl Router {
                     None,
                     Some(ingress.clone()),
                     ctrl_lock.new_face(),
+                    query_cancellation,
                 )
             })
             .clone();
@@ -203,6 +223,7 @@ impl Router {
             Some(transport),
             None,
             ctrl_lock.new_face(),
+            false,
         );
         let _ = mux.face.set(Face {
             state: face.clone(),
@@ -242,6 +263,7 @@ impl Router {
             Some(transport),
             Some(interceptor.clone()),
             ctrl_lock.new_face(),
+            false,
         );
         tables.mcast_faces.push(face_state.clone());
 
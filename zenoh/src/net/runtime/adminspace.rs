@@ -17,6 +17,7 @@ use std::{
 };
 
 use serde_json::json;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, trace};
 use zenoh_buffers::buffer::SplitBuffer;
 use zenoh_config::{unwrap_or_default, wrappers::ZenohId, ConfigValidator, WhatAmI};
@@ -37,7 +38,10 @@ use zenoh_protocol::{
 use zenoh_result::ZResult;
 use zenoh_transport::unicast::TransportUnicast;
 
-use super::{routing::dispatcher::face::Face, Runtime};
+use super::{
+    routing::dispatcher::{face::Face, resource::Resource},
+    Runtime,
+};
 #[cfg(feature = "plugins")]
 use crate::api::plugins::PluginsManager;
 use crate::{
@@ -437,6 +441,7 @@ impl Primitives for AdminSpace {
                             rid: msg.id,
                             ext_qos: ext::QoSType::RESPONSE_FINAL,
                             ext_tstamp: None,
+                            ext_cancel: None,
                         });
                         return;
                     }
@@ -450,6 +455,7 @@ impl Primitives for AdminSpace {
                             rid: msg.id,
                             ext_qos: ext::QoSType::RESPONSE_FINAL,
                             ext_tstamp: None,
+                            ext_cancel: None,
                         });
                         return;
                     }
@@ -463,6 +469,9 @@ impl Primitives for AdminSpace {
                         qid: msg.id,
                         zid: zid.into(),
                         primitives,
+                        local: false,
+                        cancellation_token: CancellationToken::new(),
+                        incoming_queries: None,
                     }),
                     eid: self.queryable_id,
                     value: query
@@ -505,8 +514,9 @@ impl crate::net::primitives::EPrimitives for AdminSpace {
     }
 
     #[inline]
-    fn send_push(&self, msg: Push, reliability: Reliability) {
-        (self as &dyn Primitives).send_push(msg, reliability)
+    fn send_push(&self, msg: Push, reliability: Reliability) -> bool {
+        (self as &dyn Primitives).send_push(msg, reliability);
+        true
     }
 
     #[inline]
@@ -613,6 +623,20 @@ fn local_data(context: &AdminContext, query: Query) {
                 "stats".to_string(),
                 json!(transport_mgr.get_stats().report()),
             );
+            let ke_stats = &context.runtime.state.router.tables.key_expr_stats;
+            let to_json = |reports: Vec<(String, _)>| {
+                reports
+                    .into_iter()
+                    .map(|(ke, report)| (ke, json!(report)))
+                    .collect::<serde_json::Map<_, _>>()
+            };
+            json.as_object_mut().unwrap().insert(
+                "key_expr_stats".to_string(),
+                json!({
+                    "key_exprs": to_json(ke_stats.key_exprs_report()),
+                    "prefixes": to_json(ke_stats.prefixes_report()),
+                }),
+            );
         }
     }
 
@@ -650,14 +674,20 @@ zenoh_build{{version="{}"}} 1
     );
 
     #[cfg(feature = "stats")]
-    metrics.push_str(
-        &context
-            .runtime
-            .manager()
-            .get_stats()
-            .report()
-            .openmetrics_text(),
-    );
+    {
+        metrics.push_str(
+            &context
+                .runtime
+                .manager()
+                .get_stats()
+                .report()
+                .openmetrics_text(),
+        );
+        metrics.push_str(&transports_metrics(context));
+        metrics.push_str(&key_exprs_metrics(context));
+    }
+
+    metrics.push_str(&routing_metrics(context));
 
     if let Err(e) = query
         .reply(reply_key, metrics)
@@ -668,6 +698,129 @@ zenoh_build{{version="{}"}} 1
     }
 }
 
+/// Per transport, per link and per priority statistics, labelled with the remote
+/// zid and whatami (and the link source and destination or the priority).
+#[cfg(feature = "stats")]
+fn transports_metrics(context: &AdminContext) -> String {
+    use zenoh_transport::stats::{TransportPriorityStatsReport, TransportStatsReport};
+
+    let transports = zenoh_runtime::ZRuntime::Net
+        .block_in_place(context.runtime.manager().get_transports_unicast());
+    let mut transport_reports = vec![];
+    let mut link_reports = vec![];
+    let mut priority_reports = vec![];
+    for transport in transports {
+        let (Ok(zid), Ok(whatami), Ok(stats)) = (
+            transport.get_zid(),
+            transport.get_whatami(),
+            transport.get_stats(),
+        ) else {
+            continue;
+        };
+        let labels = format!("zid=\"{zid}\",whatami=\"{whatami}\"");
+        for (link, report) in transport.get_links_stats().unwrap_or_default() {
+            link_reports.push((
+                format!("{labels},src=\"{}\",dst=\"{}\"", link.src, link.dst),
+                report,
+            ));
+        }
+        for (priority, report) in transport.get_priorities_stats().unwrap_or_default() {
+            priority_reports.push((format!("{labels},priority=\"{priority:?}\""), report));
+        }
+        transport_reports.push((labels, stats.report()));
+    }
+
+    let mut metrics = String::new();
+    let reports: Vec<_> = transport_reports
+        .iter()
+        .map(|(labels, report)| (labels.as_str(), report))
+        .collect();
+    metrics.push_str(&TransportStatsReport::labelled_openmetrics_text(
+        "transport_",
+        &reports,
+    ));
+    let reports: Vec<_> = link_reports
+        .iter()
+        .map(|(labels, report)| (labels.as_str(), report))
+        .collect();
+    metrics.push_str(&TransportStatsReport::labelled_openmetrics_text(
+        "link_", &reports,
+    ));
+    let reports: Vec<_> = priority_reports
+        .iter()
+        .map(|(labels, report)| (labels.as_str(), report))
+        .collect();
+    metrics.push_str(&TransportPriorityStatsReport::labelled_openmetrics_text(
+        "priority_",
+        &reports,
+    ));
+    metrics
+}
+
+/// Statistics of the traffic routed by this node, labelled with the configured
+/// key expression or the key expression prefix.
+#[cfg(feature = "stats")]
+fn key_exprs_metrics(context: &AdminContext) -> String {
+    use zenoh_transport::stats::KeyExprStatsReport;
+
+    let ke_stats = &context.runtime.state.router.tables.key_expr_stats;
+    let reports: Vec<_> = ke_stats
+        .key_exprs_report()
+        .into_iter()
+        .map(|(ke, report)| (format!("key_expr=\"{ke}\""), report))
+        .chain(
+            ke_stats
+                .prefixes_report()
+                .into_iter()
+                .map(|(prefix, report)| (format!("prefix=\"{prefix}\""), report)),
+        )
+        .collect();
+    let reports: Vec<_> = reports
+        .iter()
+        .map(|(labels, report)| (labels.as_str(), report))
+        .collect();
+    KeyExprStatsReport::labelled_openmetrics_text("key_expr_", &reports)
+}
+
+/// Gauges of the routing tables content.
+fn routing_metrics(context: &AdminContext) -> String {
+    fn count_resources(res: &Arc<Resource>) -> usize {
+        1 + res.children.values().map(count_resources).sum::<usize>()
+    }
+
+    let tables = zread!(context.runtime.state.router.tables.tables);
+    let gauges = [
+        (
+            "routing_subscribers",
+            "Number of subscriptions known by the routing tables.",
+            tables.hat_code.get_subscriptions(&tables).len(),
+        ),
+        (
+            "routing_queryables",
+            "Number of queryables known by the routing tables.",
+            tables.hat_code.get_queryables(&tables).len(),
+        ),
+        (
+            "routing_tokens",
+            "Number of liveliness tokens known by the routing tables.",
+            tables.hat_code.get_tokens(&tables).len(),
+        ),
+        (
+            "routing_resources",
+            "Number of resources in the routing tables.",
+            // The root resource is not accounted
+            count_resources(&tables.root_res) - 1,
+        ),
+    ];
+    let mut metrics = String::new();
+    for (name, help, value) in gauges {
+        metrics.push_str(&format!(
+            "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n"
+        ));
+    }
+    metrics
+}
+
 fn routers_linkstate_data(context: &AdminContext, query: Query) {
     let reply_key: OwnedKeyExpr = format!(
         "@/{}/{}/linkstate/routers",
@@ -220,6 +220,17 @@ impl RuntimeBuilder {
                                         if let Err(e) = runtime2.update_peers().await {
                                             tracing::error!("Error updating peers: {}", e);
                                         }
+                                    } else if event.trim_start_matches('/').starts_with("access_control") {
+                                        let config = runtime2.config().lock().0.clone();
+                                        let router = runtime2.router();
+                                        // Reloading the interceptors may read files, e.g. the ACL policy file
+                                        let reloaded = tokio::task::spawn_blocking(move || {
+                                            router.update_interceptors_config(&config)
+                                        })
+                                        .await;
+                                        if let Err(e) = reloaded {
+                                            tracing::error!("Error updating interceptors configuration: {}", e);
+                                        }
                                     }
                                 },
                                 None => { break; }
@@ -25,9 +25,13 @@ use zenoh_protocol::{
         key_expr::keyexpr, Encoding, ExprId, Reliability, WhatAmI, WireExpr, ZenohIdProto,
         EMPTY_EXPR_ID,
     },
-    network::{ext, Declare, DeclareBody, DeclareKeyExpr, Push},
-    zenoh::{PushBody, Put},
+    network::{
+        declare::queryable::ext::QueryableInfoType, ext, request::ext::QueryTarget, response,
+        Declare, DeclareBody, DeclareKeyExpr, Push, Request, ResponseFinal,
+    },
+    zenoh::{query::ConsolidationMode, PushBody, Put, Query, RequestBody},
 };
+use zenoh_sync::get_mut_unchecked;
 
 use crate::net::{
     primitives::{DummyPrimitives, EPrimitives, Primitives},
@@ -552,8 +556,9 @@ impl EPrimitives for ClientPrimitives {
         }
     }
 
-    fn send_push(&self, msg: zenoh_protocol::network::Push, _reliability: Reliability) {
+    fn send_push(&self, msg: zenoh_protocol::network::Push, _reliability: Reliability) -> bool {
         *zlock!(self.data) = Some(msg.wire_expr.to_owned());
+        true
     }
 
     fn send_request(&self, msg: zenoh_protocol::network::Request) {
@@ -905,3 +910,267 @@ fn client_test() {
     // mapping strategy check
     // assert_eq!(primitives2.get_last_key().unwrap(), KeyExpr::IdWithSuffix(31, "/z2_pub1".to_string()));
 }
+
+/// Primitives whose transport drops the pushed messages when `dropping` is set.
+struct DroppingPrimitives {
+    dropping: bool,
+    pushed: std::sync::atomic::AtomicUsize,
+}
+
+impl DroppingPrimitives {
+    fn new(dropping: bool) -> Self {
+        DroppingPrimitives {
+            dropping,
+            pushed: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    fn pushed(&self) -> usize {
+        self.pushed.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+impl EPrimitives for DroppingPrimitives {
+    fn send_interest(&self, _ctx: RoutingContext<zenoh_protocol::network::Interest>) {}
+
+    fn send_declare(&self, _ctx: RoutingContext<zenoh_protocol::network::Declare>) {}
+
+    fn send_push(&self, _msg: zenoh_protocol::network::Push, _reliability: Reliability) -> bool {
+        self.pushed
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        !self.dropping
+    }
+
+    fn send_request(&self, _msg: zenoh_protocol::network::Request) {}
+
+    fn send_response(&self, _msg: zenoh_protocol::network::Response) {}
+
+    fn send_response_final(&self, _msg: zenoh_protocol::network::ResponseFinal) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn dropped_data_test() {
+    let config = Config::default();
+    let router = Router::new(
+        ZenohIdProto::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    let publisher = router.new_primitives(Arc::new(DummyPrimitives {}));
+    let subscribers = [
+        Arc::new(DroppingPrimitives::new(true)),
+        Arc::new(DroppingPrimitives::new(true)),
+        Arc::new(DroppingPrimitives::new(false)),
+    ];
+    let faces = subscribers
+        .iter()
+        .map(|p| router.new_primitives(p.clone()))
+        .collect::<Vec<_>>();
+    let keys = [
+        "test/dropped/all/**",
+        "test/dropped/all/**",
+        "test/dropped/**",
+    ];
+    for (face, key) in faces.iter().zip(keys) {
+        declare_subscription(
+            zlock!(tables.ctrl_lock).as_ref(),
+            &tables,
+            &mut face.state.clone(),
+            0,
+            &key.into(),
+            &SubscriberInfo,
+            NodeId::default(),
+            &mut |p, m| p.send_declare(m),
+        );
+    }
+
+    let push = |key: &'static str| Push {
+        wire_expr: key.into(),
+        ext_qos: ext::QoSType::DEFAULT,
+        ext_tstamp: None,
+        ext_nodeid: ext::NodeIdType { node_id: 0 },
+        payload: PushBody::Put(Put {
+            timestamp: None,
+            encoding: Encoding::empty(),
+            ext_sinfo: None,
+            #[cfg(feature = "shared-memory")]
+            ext_shm: None,
+            ext_unknown: vec![],
+            payload: ZBuf::empty(),
+            ext_attachment: None,
+        }),
+    };
+
+    // Routed to the two dropping faces and the third one: reported dropped once
+    assert!(!route_data(
+        &tables,
+        &publisher.state,
+        push("test/dropped/all/a"),
+        Reliability::Reliable,
+    ));
+    assert_eq!(
+        subscribers.iter().map(|s| s.pushed()).collect::<Vec<_>>(),
+        [1, 1, 1]
+    );
+
+    // Only routed to the face that does not drop
+    assert!(route_data(
+        &tables,
+        &publisher.state,
+        push("test/dropped/other"),
+        Reliability::Reliable,
+    ));
+    assert_eq!(
+        subscribers.iter().map(|s| s.pushed()).collect::<Vec<_>>(),
+        [1, 1, 2]
+    );
+
+    // Not routed at all
+    assert!(route_data(
+        &tables,
+        &publisher.state,
+        push("test/other"),
+        Reliability::Reliable,
+    ));
+}
+
+/// Primitives recording the requests and final replies sent to a face.
+#[derive(Default)]
+struct QueryPrimitives {
+    requests: std::sync::Mutex<Vec<Request>>,
+    finals: std::sync::Mutex<Vec<ResponseFinal>>,
+}
+
+impl EPrimitives for QueryPrimitives {
+    fn send_interest(&self, _ctx: RoutingContext<zenoh_protocol::network::Interest>) {}
+
+    fn send_declare(&self, _ctx: RoutingContext<zenoh_protocol::network::Declare>) {}
+
+    fn send_push(&self, _msg: zenoh_protocol::network::Push, _reliability: Reliability) -> bool {
+        true
+    }
+
+    fn send_request(&self, msg: Request) {
+        zlock!(self.requests).push(msg);
+    }
+
+    fn send_response(&self, _msg: zenoh_protocol::network::Response) {}
+
+    fn send_response_final(&self, msg: ResponseFinal) {
+        zlock!(self.finals).push(msg);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[test]
+fn cancelled_query_test() {
+    let config = Config::default();
+    let router = Router::new(
+        ZenohIdProto::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        Some(Arc::new(HLC::default())),
+        &config,
+    )
+    .unwrap();
+    let tables = router.tables.clone();
+
+    let querier = Arc::new(QueryPrimitives::default());
+    let querier_face = router.new_primitives(querier.clone());
+    let queryables = [
+        Arc::new(QueryPrimitives::default()),
+        Arc::new(QueryPrimitives::default()),
+    ];
+    let faces = queryables
+        .iter()
+        .map(|p| router.new_primitives(p.clone()))
+        .collect::<Vec<_>>();
+    // The second queryable is an older peer that can't decode the cancellation of a query
+    get_mut_unchecked(&mut faces[1].state.clone()).query_cancellation = false;
+    for face in faces.iter() {
+        declare_queryable(
+            zlock!(tables.ctrl_lock).as_ref(),
+            &tables,
+            &mut face.state.clone(),
+            0,
+            &"test/cancel/**".into(),
+            &QueryableInfoType {
+                complete: true,
+                distance: 0,
+            },
+            NodeId::default(),
+            &mut |p, m| p.send_declare(m),
+        );
+    }
+
+    querier_face.send_request(Request {
+        id: 1,
+        wire_expr: "test/cancel/a".into(),
+        ext_qos: ext::QoSType::REQUEST,
+        ext_tstamp: None,
+        ext_nodeid: ext::NodeIdType { node_id: 0 },
+        ext_target: QueryTarget::All,
+        ext_budget: None,
+        ext_timeout: None,
+        payload: RequestBody::Query(Query {
+            consolidation: ConsolidationMode::DEFAULT,
+            parameters: String::new(),
+            ext_sinfo: None,
+            ext_body: None,
+            ext_attachment: None,
+            ext_unknown: vec![],
+        }),
+    });
+    let outqids = queryables
+        .iter()
+        .map(|q| {
+            let requests = zlock!(q.requests);
+            assert_eq!(requests.len(), 1);
+            requests[0].id
+        })
+        .collect::<Vec<_>>();
+
+    let cancel = ResponseFinal {
+        rid: 1,
+        ext_qos: response::ext::QoSType::RESPONSE_FINAL,
+        ext_tstamp: None,
+        ext_cancel: Some(response::ext::Cancel::new()),
+    };
+    querier_face.send_response_final(cancel.clone());
+
+    // Only the queryable that negotiated it receives the cancellation
+    assert_eq!(
+        *zlock!(queryables[0].finals),
+        [ResponseFinal {
+            rid: outqids[0],
+            ..cancel.clone()
+        }]
+    );
+    assert!(zlock!(queryables[1].finals).is_empty());
+    assert!(zlock!(querier.finals).is_empty());
+
+    // The older peer keeps the query until its final reply, which is propagated as usual
+    faces[1].send_response_final(ResponseFinal {
+        rid: outqids[1],
+        ext_qos: response::ext::QoSType::RESPONSE_FINAL,
+        ext_tstamp: None,
+        ext_cancel: None,
+    });
+    assert_eq!(zlock!(querier.finals).len(), 1);
+    assert!(zlock!(querier_face.state.forwarded_queries).is_empty());
+
+    // Cancelling a finished query has no effect
+    querier_face.send_response_final(cancel);
+    assert_eq!(zlock!(queryables[0].finals).len(), 1);
+    assert!(zlock!(queryables[1].finals).is_empty());
+}
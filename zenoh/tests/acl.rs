@@ -72,6 +72,31 @@ async fn test_acl_liveliness() {
     test_liveliness_deny_allow_query(27450).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_acl_reload() {
+    zenoh::init_log_from_env_or("error");
+    test_reload_from_config(27451).await;
+    test_reload_from_policy_file(27451).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_acl_audit() {
+    zenoh::init_log_from_env_or("error");
+    test_audit_deny_events(27452).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_acl_subjects() {
+    zenoh::init_log_from_env_or("error");
+    test_zid_whatami_subjects(27453).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_acl_key_expr_templates() {
+    zenoh::init_log_from_env_or("error");
+    test_zid_key_expr_template(27454).await;
+}
+
 async fn get_basic_router_config(port: u16) -> Config {
     let mut config = Config::default();
     config.set_mode(Some(WhatAmI::Router)).unwrap();
@@ -1529,3 +1554,374 @@ async fn test_liveliness_deny_allow_query(port: u16) {
     close_sessions(reader_session, writer_session).await;
     close_router_session(session).await;
 }
+
+const RELOAD_RULES_ALLOW_SUB: &str = r#"[
+    {
+        "id": "r1",
+        "permission": "allow",
+        "flows": ["ingress", "egress"],
+        "messages": ["put", "declare_subscriber"],
+        "key_exprs": ["test/demo"],
+    },
+]"#;
+
+const RELOAD_RULES_DENY_SUB: &str = r#"[
+    {
+        "id": "r1",
+        "permission": "allow",
+        "flows": ["ingress", "egress"],
+        "messages": ["put"],
+        "key_exprs": ["test/demo"],
+    },
+]"#;
+
+const RELOAD_SUBJECTS: &str = r#"[{ "id": "all" }]"#;
+
+const RELOAD_POLICIES: &str = r#"[{ "rules": ["r1"], "subjects": ["all"] }]"#;
+
+/// Checks that a value published after the access control was reloaded to deny subscriptions
+/// is not received by a subscriber declared while they were allowed.
+async fn check_subscriber_undeclared_on_reload(
+    port: u16,
+    session: &Session,
+    reload: impl FnOnce(&Session),
+    reload_delay: Duration,
+) {
+    let (sub_session, pub_session) = get_client_sessions(port).await;
+    let publisher = ztimeout!(pub_session.declare_publisher(KEY_EXPR)).unwrap();
+    let received_value = Arc::new(Mutex::new(String::new()));
+    let temp_recv_value = received_value.clone();
+    let subscriber = ztimeout!(sub_session
+        .declare_subscriber(KEY_EXPR)
+        .callback(move |sample| {
+            *zlock!(temp_recv_value) = sample.payload().try_to_string().unwrap().into_owned();
+        }))
+    .unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(publisher.put(VALUE)).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*zlock!(received_value), VALUE);
+
+    reload(session);
+    tokio::time::sleep(reload_delay).await;
+    zlock!(received_value).clear();
+    ztimeout!(publisher.put(VALUE)).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_ne!(*zlock!(received_value), VALUE);
+
+    ztimeout!(subscriber.undeclare()).unwrap();
+    ztimeout!(publisher.undeclare()).unwrap();
+    close_sessions(sub_session, pub_session).await;
+}
+
+async fn test_reload_from_config(port: u16) {
+    println!("test_reload_from_config");
+    let mut config_router = get_basic_router_config(port).await;
+    config_router
+        .insert_json5(
+            "access_control",
+            &format!(
+                r#"{{
+                    "enabled": true,
+                    "default_permission": "deny",
+                    "rules": {RELOAD_RULES_ALLOW_SUB},
+                    "subjects": {RELOAD_SUBJECTS},
+                    "policies": {RELOAD_POLICIES},
+                }}"#
+            ),
+        )
+        .unwrap();
+    println!("Opening router session");
+    let session = ztimeout!(zenoh::open(config_router)).unwrap();
+
+    check_subscriber_undeclared_on_reload(
+        port,
+        &session,
+        |session| {
+            session
+                .config()
+                .insert_json5("access_control/rules", RELOAD_RULES_DENY_SUB)
+                .unwrap();
+        },
+        SLEEP,
+    )
+    .await;
+
+    close_router_session(session).await;
+}
+
+async fn test_reload_from_policy_file(port: u16) {
+    println!("test_reload_from_policy_file");
+    let policy_file = std::env::temp_dir().join(format!("zenoh_acl_policy_{port}.json5"));
+    let write_policy_file = |rules: &str| {
+        std::fs::write(
+            &policy_file,
+            format!(
+                r#"{{
+                    "rules": {rules},
+                    "subjects": {RELOAD_SUBJECTS},
+                    "policies": {RELOAD_POLICIES},
+                }}"#
+            ),
+        )
+        .unwrap();
+    };
+    write_policy_file(RELOAD_RULES_ALLOW_SUB);
+
+    let mut config_router = get_basic_router_config(port).await;
+    config_router
+        .insert_json5(
+            "access_control",
+            &format!(
+                r#"{{
+                    "enabled": true,
+                    "default_permission": "deny",
+                    "policy_file": {:?},
+                }}"#,
+                policy_file.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+    println!("Opening router session");
+    let session = ztimeout!(zenoh::open(config_router)).unwrap();
+
+    check_subscriber_undeclared_on_reload(
+        port,
+        &session,
+        |_| {
+            write_policy_file(RELOAD_RULES_DENY_SUB);
+        },
+        // Let the policy file watcher notice the change
+        3 * SLEEP,
+    )
+    .await;
+
+    close_router_session(session).await;
+    std::fs::remove_file(&policy_file).unwrap();
+}
+
+async fn test_audit_deny_events(port: u16) {
+    println!("test_audit_deny_events");
+    let audit_file = std::env::temp_dir().join(format!("zenoh_acl_audit_{port}.jsonl"));
+    let _ = std::fs::remove_file(&audit_file);
+
+    let mut config_router = get_basic_router_config(port).await;
+    config_router
+        .insert_json5(
+            "access_control",
+            &format!(
+                r#"{{
+                    "enabled": true,
+                    "default_permission": "deny",
+                    "rules": [
+                        {{
+                            "id": "allow_events",
+                            "permission": "allow",
+                            "flows": ["ingress", "egress"],
+                            "messages": ["put", "declare_subscriber"],
+                            "key_exprs": ["@/*/router/acl/events"],
+                        }},
+                        {{
+                            "id": "deny_demo_put",
+                            "permission": "deny",
+                            "flows": ["ingress"],
+                            "messages": ["put"],
+                            "key_exprs": ["test/demo"],
+                        }},
+                    ],
+                    "subjects": [{{ "id": "all" }}],
+                    "policies": [{{ "rules": ["allow_events", "deny_demo_put"], "subjects": ["all"] }}],
+                    "audit": {{ "file": {:?} }},
+                }}"#,
+                audit_file.to_str().unwrap()
+            ),
+        )
+        .unwrap();
+    println!("Opening router session");
+    let session = ztimeout!(zenoh::open(config_router)).unwrap();
+    let (sub_session, pub_session) = get_client_sessions(port).await;
+
+    let events = Arc::new(Mutex::new(Vec::<serde_json::Value>::new()));
+    let temp_events = events.clone();
+    let subscriber = ztimeout!(sub_session
+        .declare_subscriber("@/*/router/acl/events")
+        .callback(move |sample| {
+            let event = serde_json::from_slice(&sample.payload().to_bytes()).unwrap();
+            zlock!(temp_events).push(event);
+        }))
+    .unwrap();
+
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(pub_session.put(KEY_EXPR, VALUE)).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let is_expected_event = |event: &serde_json::Value| {
+        event["key_expr"] == KEY_EXPR
+            && event["message"] == "put"
+            && event["flow"] == "ingress"
+            && event["rule_id"] == "deny_demo_put"
+            && event["decision"] == "deny"
+            && event["zid"] == pub_session.zid().to_string()
+    };
+    assert!(zlock!(events).iter().any(is_expected_event));
+    let audit_lines = std::fs::read_to_string(&audit_file).unwrap();
+    assert!(audit_lines
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .any(|event| is_expected_event(&event)));
+
+    ztimeout!(subscriber.undeclare()).unwrap();
+    close_sessions(sub_session, pub_session).await;
+    close_router_session(session).await;
+    std::fs::remove_file(&audit_file).unwrap();
+}
+
+async fn get_client_session_with_zid(port: u16, zid: &str) -> Session {
+    let mut config = zenoh::Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    config.set_id(zid.parse().unwrap()).unwrap();
+    config
+        .connect
+        .set_endpoints(ModeDependentValue::Unique(vec![format!(
+            "tcp/127.0.0.1:{port}"
+        )
+        .parse::<EndPoint>()
+        .unwrap()]))
+        .unwrap();
+    ztimeout!(zenoh::open(config)).unwrap()
+}
+
+async fn test_zid_whatami_subjects(port: u16) {
+    println!("test_zid_whatami_subjects");
+    let mut config_router = get_basic_router_config(port).await;
+    config_router
+        .insert_json5(
+            "access_control",
+            r#"{
+                    "enabled": true,
+                    "default_permission": "deny",
+                    "rules": [
+                        {
+                            "id": "allow_pub_sub",
+                            "permission": "allow",
+                            "flows": ["ingress", "egress"],
+                            "messages": ["put", "declare_subscriber"],
+                            "key_exprs": ["test/demo"],
+                        },
+                    ],
+                    "subjects": [
+                        { "id": "known_clients", "zids": ["a1", "a2"], "whatamis": ["client"] },
+                        { "id": "known_peers", "zids": ["a3"], "whatamis": ["peer"] },
+                    ],
+                    "policies": [
+                        { "rules": ["allow_pub_sub"], "subjects": ["known_clients", "known_peers"] },
+                    ],
+                }"#,
+        )
+        .unwrap();
+    println!("Opening router session");
+    let session = ztimeout!(zenoh::open(config_router)).unwrap();
+
+    let sub_session = get_client_session_with_zid(port, "a1").await;
+    let received_value = Arc::new(Mutex::new(String::new()));
+    let temp_recv_value = received_value.clone();
+    let subscriber = sub_session
+        .declare_subscriber(KEY_EXPR)
+        .callback(move |sample| {
+            *zlock!(temp_recv_value) = sample.payload().try_to_string().unwrap().into_owned();
+        })
+        .await
+        .unwrap();
+
+    // a2 is a known client: its publications are allowed
+    let pub_session = get_client_session_with_zid(port, "a2").await;
+    tokio::time::sleep(SLEEP).await;
+    pub_session.put(KEY_EXPR, VALUE).await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*zlock!(received_value), VALUE);
+    *zlock!(received_value) = String::new();
+    ztimeout!(pub_session.close()).unwrap();
+
+    // a3 is only allowed as a peer: its publications as a client are denied
+    let pub_session = get_client_session_with_zid(port, "a3").await;
+    tokio::time::sleep(SLEEP).await;
+    pub_session.put(KEY_EXPR, VALUE).await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_ne!(*zlock!(received_value), VALUE);
+    ztimeout!(pub_session.close()).unwrap();
+
+    ztimeout!(subscriber.undeclare()).unwrap();
+    ztimeout!(sub_session.close()).unwrap();
+    close_router_session(session).await;
+}
+
+async fn test_zid_key_expr_template(port: u16) {
+    println!("test_zid_key_expr_template");
+    let mut config_router = get_basic_router_config(port).await;
+    config_router
+        .insert_json5(
+            "access_control",
+            r#"{
+                    "enabled": true,
+                    "default_permission": "deny",
+                    "rules": [
+                        {
+                            "id": "allow_sub",
+                            "permission": "allow",
+                            "flows": ["ingress", "egress"],
+                            "messages": ["declare_subscriber"],
+                            "key_exprs": ["fleet/**"],
+                        },
+                        {
+                            "id": "allow_put_out",
+                            "permission": "allow",
+                            "flows": ["egress"],
+                            "messages": ["put"],
+                            "key_exprs": ["fleet/**"],
+                        },
+                        {
+                            "id": "allow_put_own",
+                            "permission": "allow",
+                            "flows": ["ingress"],
+                            "messages": ["put"],
+                            "key_exprs": ["fleet/${zid}/**"],
+                        },
+                    ],
+                    "subjects": [{ "id": "all" }],
+                    "policies": [
+                        {
+                            "rules": ["allow_sub", "allow_put_out", "allow_put_own"],
+                            "subjects": ["all"],
+                        },
+                    ],
+                }"#,
+        )
+        .unwrap();
+    println!("Opening router session");
+    let session = ztimeout!(zenoh::open(config_router)).unwrap();
+
+    let sub_session = get_client_session_with_zid(port, "a1").await;
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let temp_received = received.clone();
+    let subscriber = sub_session
+        .declare_subscriber("fleet/**")
+        .callback(move |sample| {
+            zlock!(temp_received).push(sample.key_expr().to_string());
+        })
+        .await
+        .unwrap();
+
+    let pub_session = get_client_session_with_zid(port, "a2").await;
+    tokio::time::sleep(SLEEP).await;
+    // a2 may only publish under its own zid
+    pub_session.put("fleet/a2/pos", VALUE).await.unwrap();
+    pub_session.put("fleet/a3/pos", VALUE).await.unwrap();
+    pub_session.put("fleet/a2", VALUE).await.unwrap();
+    tokio::time::sleep(SLEEP).await;
+    assert_eq!(*zlock!(received), vec!["fleet/a2/pos", "fleet/a2"]);
+
+    ztimeout!(subscriber.undeclare()).unwrap();
+    close_sessions(sub_session, pub_session).await;
+    close_router_session(session).await;
+}
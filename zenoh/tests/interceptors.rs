@@ -24,7 +24,10 @@ use std::{
 };
 
 use zenoh::{key_expr::KeyExpr, Config, Wait};
-use zenoh_config::{DownsamplingItemConf, DownsamplingRuleConf, InterceptorFlow};
+use zenoh_config::{
+    DownsamplingItemConf, DownsamplingRuleConf, InterceptorFlow, KeyRewritingItemConf,
+    KeyRewritingRuleConf, RateLimitItemConf, RateLimitRuleConf, RateLimitStrategy,
+};
 
 // Tokio's time granularity on different platforms
 #[cfg(target_os = "windows")]
@@ -273,3 +276,340 @@ fn downsampling_config_error_wrong_strategy() {
 
     zenoh::open(config).wait().unwrap();
 }
+
+fn build_rate_limiting_config(
+    locator: &str,
+    rl_config: Vec<RateLimitItemConf>,
+    flow: InterceptorFlow,
+) -> (Config, Config) {
+    let (mut pub_config, mut sub_config) = build_config(locator, vec![], flow);
+    match flow {
+        InterceptorFlow::Egress => pub_config.set_rate_limiting(rl_config).unwrap(),
+        InterceptorFlow::Ingress => sub_config.set_rate_limiting(rl_config).unwrap(),
+    };
+    (pub_config, sub_config)
+}
+
+fn rate_limiting_by_keyexpr_impl(flow: InterceptorFlow, strategy: RateLimitStrategy) {
+    let ke_prefix = "test/rate_limiting_by_keyexpr";
+    let locator = "tcp/127.0.0.1:31448";
+
+    let ke_10msgs: KeyExpr = format!("{ke_prefix}/10msgs").try_into().unwrap();
+    // The published payload "message" is 7 bytes long: 140 bytes/s allows 20 messages/s
+    let ke_140bytes: KeyExpr = format!("{ke_prefix}/140bytes").try_into().unwrap();
+    let ke_no_effect: KeyExpr = format!("{ke_prefix}/no_effect").try_into().unwrap();
+    let ke_of_rates: Vec<KeyExpr<'static>> =
+        vec![ke_10msgs.clone(), ke_140bytes.clone(), ke_no_effect.clone()];
+
+    let rl_config = RateLimitItemConf {
+        flow,
+        interfaces: None,
+        cert_common_names: None,
+        usernames: None,
+        strategy,
+        max_delay_ms: Some(10),
+        rules: vec![
+            RateLimitRuleConf {
+                key_expr: ke_10msgs.clone().into(),
+                messages_per_sec: Some(10.0),
+                bytes_per_sec: None,
+                messages_burst: Some(1.0),
+                bytes_burst: None,
+            },
+            RateLimitRuleConf {
+                key_expr: ke_140bytes.clone().into(),
+                messages_per_sec: None,
+                bytes_per_sec: Some(140.0),
+                messages_burst: None,
+                bytes_burst: Some(7.0),
+            },
+        ],
+    };
+
+    let rate_check = move |ke: KeyExpr, rate: usize| -> bool {
+        tracing::info!("keyexpr: {ke}, rate: {rate}");
+        if ke == ke_10msgs {
+            rate > 0 && rate <= 10 + 1
+        } else if ke == ke_140bytes {
+            rate > 0 && rate <= 20 + 1
+        } else if ke == ke_no_effect {
+            rate > 20
+        } else {
+            tracing::error!("Shouldn't reach this case. Invalid keyexpr {ke} detected.");
+            false
+        }
+    };
+
+    let (pub_config, sub_config) = build_rate_limiting_config(locator, vec![rl_config], flow);
+
+    downsampling_test(pub_config, sub_config, ke_prefix, ke_of_rates, rate_check);
+}
+
+#[test]
+fn rate_limiting_by_keyexpr() {
+    zenoh::init_log_from_env_or("error");
+    rate_limiting_by_keyexpr_impl(InterceptorFlow::Ingress, RateLimitStrategy::Drop);
+    rate_limiting_by_keyexpr_impl(InterceptorFlow::Egress, RateLimitStrategy::Drop);
+    rate_limiting_by_keyexpr_impl(InterceptorFlow::Ingress, RateLimitStrategy::Delay);
+}
+
+fn rate_limiting_delay_impl(flow: InterceptorFlow, locator: &str) {
+    let ke_prefix = "test/rate_limiting_delay";
+    let ke_20msgs: KeyExpr = format!("{ke_prefix}/20msgs").try_into().unwrap();
+    let ke_100msgs: KeyExpr = format!("{ke_prefix}/100msgs").try_into().unwrap();
+    let ke_no_effect: KeyExpr = format!("{ke_prefix}/no_effect").try_into().unwrap();
+
+    let rl_config = RateLimitItemConf {
+        flow,
+        interfaces: None,
+        cert_common_names: None,
+        usernames: None,
+        strategy: RateLimitStrategy::Delay,
+        max_delay_ms: Some(1000),
+        rules: vec![
+            RateLimitRuleConf {
+                key_expr: ke_20msgs.clone().into(),
+                messages_per_sec: Some(20.0),
+                bytes_per_sec: None,
+                messages_burst: Some(1.0),
+                bytes_burst: None,
+            },
+            RateLimitRuleConf {
+                key_expr: ke_100msgs.clone().into(),
+                messages_per_sec: Some(100.0),
+                bytes_per_sec: None,
+                messages_burst: Some(1.0),
+                bytes_burst: None,
+            },
+        ],
+    };
+    let (pub_config, sub_config) = build_rate_limiting_config(locator, vec![rl_config], flow);
+
+    let sub_session = zenoh::open(sub_config).wait().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let _sub = sub_session
+        .declare_subscriber(format!("{ke_prefix}/*"))
+        .callback(move |sample| {
+            let _ = tx.send((
+                sample.key_expr().as_str().to_string(),
+                sample.payload().try_to_string().unwrap().into_owned(),
+                std::time::Instant::now(),
+            ));
+        })
+        .wait()
+        .unwrap();
+    let pub_session = zenoh::open(pub_config).wait().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    let start = std::time::Instant::now();
+    for i in 0..10 {
+        pub_session.put(&ke_20msgs, i.to_string()).wait().unwrap();
+    }
+    pub_session.put(&ke_no_effect, "message").wait().unwrap();
+    // The second message is delayed by 10ms, far less than the last ones of the other rule
+    for i in 0..2 {
+        pub_session.put(&ke_100msgs, i.to_string()).wait().unwrap();
+    }
+    // Delaying the messages does not block the publication
+    assert!(start.elapsed() < std::time::Duration::from_millis(200));
+
+    let mut delayed = Vec::new();
+    let mut fast = Vec::new();
+    let mut no_effect_rank = None;
+    for rank in 0..13 {
+        let (ke, payload, received_at) =
+            rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        if ke == ke_no_effect.as_str() {
+            no_effect_rank = Some(rank);
+        } else if ke == ke_100msgs.as_str() {
+            fast.push((payload, rank));
+        } else {
+            delayed.push((payload, received_at));
+        }
+    }
+    // No message is dropped and their order is preserved
+    let payloads: Vec<_> = delayed.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(payloads, ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]);
+    // With a burst of 1 message at 20 messages/s, the last message is delayed by 450ms
+    let (_, last_received_at) = delayed.last().unwrap();
+    assert!(last_received_at.duration_since(start) >= std::time::Duration::from_millis(400));
+    // The message that is not rate limited is not held back by the delayed ones
+    assert!(no_effect_rank.unwrap() < 10);
+    // Neither are the messages delayed less by another rule
+    let fast_payloads: Vec<_> = fast.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(fast_payloads, ["0", "1"]);
+    assert!(fast.iter().all(|(_, rank)| *rank < 10));
+}
+
+#[test]
+fn rate_limiting_delay() {
+    zenoh::init_log_from_env_or("error");
+    rate_limiting_delay_impl(InterceptorFlow::Ingress, "tcp/127.0.0.1:31451");
+    rate_limiting_delay_impl(InterceptorFlow::Egress, "tcp/127.0.0.1:31452");
+}
+
+#[cfg(unix)]
+#[test]
+fn rate_limiting_by_interface() {
+    zenoh::init_log_from_env_or("error");
+    let ke_prefix = "test/rate_limiting_by_interface";
+    let locator = "tcp/127.0.0.1:31449";
+    let flow = InterceptorFlow::Ingress;
+
+    let ke_10msgs: KeyExpr = format!("{ke_prefix}/10msgs").try_into().unwrap();
+    let ke_no_effect: KeyExpr = format!("{ke_prefix}/no_effect").try_into().unwrap();
+    let ke_of_rates: Vec<KeyExpr<'static>> = vec![ke_10msgs.clone(), ke_no_effect.clone()];
+
+    let rule = |key_expr: &KeyExpr| RateLimitRuleConf {
+        key_expr: key_expr.clone().into(),
+        messages_per_sec: Some(10.0),
+        bytes_per_sec: None,
+        messages_burst: Some(1.0),
+        bytes_burst: None,
+    };
+    let rl_config = vec![
+        RateLimitItemConf {
+            flow,
+            interfaces: Some(vec![
+                zenoh_config::Interface("lo".to_string()),
+                zenoh_config::Interface("lo0".to_string()),
+            ]),
+            cert_common_names: None,
+            usernames: None,
+            strategy: RateLimitStrategy::Drop,
+            max_delay_ms: None,
+            rules: vec![rule(&ke_10msgs)],
+        },
+        RateLimitItemConf {
+            flow,
+            interfaces: Some(vec![zenoh_config::Interface(
+                "some_unknown_interface".to_string(),
+            )]),
+            cert_common_names: None,
+            usernames: None,
+            strategy: RateLimitStrategy::Drop,
+            max_delay_ms: None,
+            rules: vec![rule(&ke_no_effect)],
+        },
+    ];
+
+    let rate_check = move |ke: KeyExpr, rate: usize| -> bool {
+        tracing::info!("keyexpr: {ke}, rate: {rate}");
+        if ke == ke_10msgs {
+            rate > 0 && rate <= 10 + 1
+        } else if ke == ke_no_effect {
+            rate > 10
+        } else {
+            tracing::error!("Shouldn't reach this case. Invalid keyexpr {ke} detected.");
+            false
+        }
+    };
+
+    let (pub_config, sub_config) = build_rate_limiting_config(locator, rl_config, flow);
+
+    downsampling_test(pub_config, sub_config, ke_prefix, ke_of_rates, rate_check);
+}
+
+#[test]
+#[should_panic(expected = "must set `messages_per_sec` and/or `bytes_per_sec`")]
+fn rate_limiting_config_error_no_limit() {
+    zenoh::init_log_from_env_or("error");
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "rate_limiting",
+            r#"
+              [
+                {
+                  flow: "ingress",
+                  rules: [
+                    { key_expr: "test/rate_limiting/no_limit" },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+
+    zenoh::open(config).wait().unwrap();
+}
+
+#[test]
+fn key_rewriting_pub_sub_and_query() {
+    zenoh::init_log_from_env_or("error");
+    let locator = "tcp/127.0.0.1:31450";
+
+    let (robot_config, mut fleet_config) = build_config(locator, vec![], InterceptorFlow::Ingress);
+    fleet_config
+        .set_key_rewriting(vec![KeyRewritingItemConf {
+            interfaces: None,
+            cert_common_names: None,
+            usernames: None,
+            flows: None,
+            rules: vec![KeyRewritingRuleConf {
+                remote: "robot".try_into().unwrap(),
+                local: "fleet/robot42".try_into().unwrap(),
+            }],
+        }])
+        .unwrap();
+
+    let fleet_session = zenoh::open(fleet_config).wait().unwrap();
+    let robot_session = zenoh::open(robot_config).wait().unwrap();
+
+    let received = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+    let _sub = fleet_session
+        .declare_subscriber("fleet/robot42/**")
+        .callback({
+            let received = received.clone();
+            move |sample| received.lock().unwrap().push(sample.key_expr().to_string())
+        })
+        .wait()
+        .unwrap();
+    let _qbl = robot_session
+        .declare_queryable("robot/status")
+        .callback(|query| {
+            query.reply(query.key_expr().clone(), "ok").wait().unwrap();
+        })
+        .wait()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+
+    robot_session.put("robot/data", "message").wait().unwrap();
+    robot_session.put("other/data", "message").wait().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(WARMUP_MS));
+    assert_eq!(*received.lock().unwrap(), vec!["fleet/robot42/data"]);
+
+    let replies = fleet_session
+        .get("fleet/robot42/status")
+        .wait()
+        .unwrap()
+        .iter()
+        .map(|reply| reply.result().unwrap().key_expr().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(replies, vec!["fleet/robot42/status"]);
+}
+
+#[test]
+#[should_panic(expected = "prefixes cannot contain wildcards")]
+fn key_rewriting_config_error_wild_prefix() {
+    zenoh::init_log_from_env_or("error");
+
+    let mut config = Config::default();
+    config
+        .insert_json5(
+            "key_rewriting",
+            r#"
+              [
+                {
+                  rules: [
+                    { remote: "robot/**", local: "fleet/robot42" },
+                  ],
+                },
+              ]
+            "#,
+        )
+        .unwrap();
+
+    zenoh::open(config).wait().unwrap();
+}
@@ -0,0 +1,291 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+#![cfg(feature = "internal_config")]
+use std::time::Duration;
+
+use zenoh::{config::WhatAmI, Config, Session};
+use zenoh_config::{EndPoint, ModeDependentValue};
+use zenoh_core::ztimeout;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+fn get_router_config(port: u16) -> Config {
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Router)).unwrap();
+    config
+        .listen
+        .endpoints
+        .set(vec![format!("tcp/127.0.0.1:{port}").parse().unwrap()])
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.adminspace.set_enabled(true).unwrap();
+    config
+}
+
+async fn get_router_session(port: u16) -> Session {
+    ztimeout!(zenoh::open(get_router_config(port))).unwrap()
+}
+
+async fn get_client_session(port: u16) -> Session {
+    let mut config = Config::default();
+    config.set_mode(Some(WhatAmI::Client)).unwrap();
+    config
+        .connect
+        .set_endpoints(ModeDependentValue::Unique(vec![format!(
+            "tcp/127.0.0.1:{port}"
+        )
+        .parse::<EndPoint>()
+        .unwrap()]))
+        .unwrap();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    ztimeout!(zenoh::open(config)).unwrap()
+}
+
+async fn get_metrics(session: &Session) -> String {
+    let key_expr = format!("@/{}/router/metrics", session.zid());
+    let replies = ztimeout!(session.get(key_expr)).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    reply
+        .result()
+        .unwrap()
+        .payload()
+        .try_to_string()
+        .unwrap()
+        .into_owned()
+}
+
+fn gauge(metrics: &str, name: &str) -> usize {
+    metrics
+        .lines()
+        .find_map(|l| l.strip_prefix(&format!("{name} ")))
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_metrics() {
+    zenoh::init_log_from_env_or("error");
+
+    let router = get_router_session(27460).await;
+    let client = get_client_session(27460).await;
+    tokio::time::sleep(SLEEP).await;
+
+    // The adminspace declares its own subscriber and queryable
+    let metrics = get_metrics(&router).await;
+    assert!(metrics.contains("# TYPE routing_subscribers gauge\n"));
+    let subscribers = gauge(&metrics, "routing_subscribers");
+    let queryables = gauge(&metrics, "routing_queryables");
+    #[cfg(feature = "unstable")]
+    let tokens = gauge(&metrics, "routing_tokens");
+    let resources = gauge(&metrics, "routing_resources");
+
+    let _sub = ztimeout!(client.declare_subscriber("test/metrics/sub")).unwrap();
+    let _qbl = ztimeout!(client.declare_queryable("test/metrics/qbl")).unwrap();
+    #[cfg(feature = "unstable")]
+    let _token = ztimeout!(client.liveliness().declare_token("test/metrics/token")).unwrap();
+    ztimeout!(client.put("test/metrics/sub", "value")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    // Once the subscriber is known to the router, it sends the publication to the client
+    ztimeout!(router.put("test/metrics/sub", "value")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let metrics = get_metrics(&router).await;
+    assert_eq!(gauge(&metrics, "routing_subscribers"), subscribers + 1);
+    assert_eq!(gauge(&metrics, "routing_queryables"), queryables + 1);
+    #[cfg(feature = "unstable")]
+    assert_eq!(gauge(&metrics, "routing_tokens"), tokens + 1);
+    // test, test/metrics and the sub, qbl and token resources
+    assert!(gauge(&metrics, "routing_resources") >= resources + 4);
+
+    #[cfg(feature = "stats")]
+    {
+        let labels = format!("zid=\"{}\",whatami=\"client\"", client.zid());
+        assert!(metrics.contains(&format!("\ntransport_rx_n_msgs{{{labels}}} ")));
+        assert!(metrics.contains(&format!(
+            "\ntransport_rx_z_put_msgs{{{labels},space=\"user\"}} 1\n"
+        )));
+        assert!(metrics.contains(&format!(
+            "\nlink_rx_bytes{{{labels},src=\"tcp/127.0.0.1:27460\""
+        )));
+        assert!(metrics.contains(&format!(
+            "\npriority_rx_n_msgs{{{labels},priority=\"Data\"}} 1\n"
+        )));
+        // The network messages are accounted on the link they were sent or received on
+        for name in ["link_rx_n_msgs", "link_tx_n_msgs"] {
+            let count: usize = metrics
+                .lines()
+                .find(|l| l.starts_with(&format!("{name}{{{labels},")))
+                .and_then(|l| l.rsplit(' ').next())
+                .unwrap()
+                .parse()
+                .unwrap();
+            assert!(count > 0, "{name} is {count}");
+        }
+    }
+
+    ztimeout!(client.close()).unwrap();
+    ztimeout!(router.close()).unwrap();
+}
+
+#[cfg(feature = "stats")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_key_expr_metrics() {
+    zenoh::init_log_from_env_or("error");
+
+    let mut config = get_router_config(27461);
+    config
+        .stats
+        .set_key_exprs(vec!["test/metrics/a/**".parse().unwrap()])
+        .unwrap();
+    config.stats.set_key_expr_depth(Some(1)).unwrap();
+    let router = ztimeout!(zenoh::open(config)).unwrap();
+    let client1 = get_client_session(27461).await;
+    let client2 = get_client_session(27461).await;
+
+    let _sub = ztimeout!(client2.declare_subscriber("**")).unwrap();
+    let _qbl = ztimeout!(client2
+        .declare_queryable("test/metrics/a/qbl")
+        .callback(|query| zenoh::Wait::wait(query.reply(query.key_expr(), "reply")).unwrap()))
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    ztimeout!(client1.put("test/metrics/a/x", "value")).unwrap();
+    ztimeout!(client1.put("test/metrics/a/y", "value")).unwrap();
+    ztimeout!(client1.put("test/metrics/b", "value")).unwrap();
+    ztimeout!(client1.put("other", "value")).unwrap();
+    let replies = ztimeout!(client1.get("test/metrics/a/qbl")).unwrap();
+    while ztimeout!(replies.recv_async()).is_ok() {}
+    tokio::time::sleep(SLEEP).await;
+
+    let metrics = get_metrics(&router).await;
+    for expected in [
+        "key_expr_rx_z_put_msgs{key_expr=\"test/metrics/a/**\"} 2",
+        "key_expr_rx_z_put_pl_bytes{key_expr=\"test/metrics/a/**\"} 10",
+        "key_expr_tx_z_put_msgs{key_expr=\"test/metrics/a/**\"} 2",
+        "key_expr_rx_z_query_msgs{key_expr=\"test/metrics/a/**\"} 1",
+        "key_expr_tx_z_query_msgs{key_expr=\"test/metrics/a/**\"} 1",
+        "key_expr_rx_z_reply_msgs{key_expr=\"test/metrics/a/**\"} 1",
+        "key_expr_tx_z_reply_msgs{key_expr=\"test/metrics/a/**\"} 1",
+        "key_expr_rx_z_put_msgs{prefix=\"test\"} 3",
+        "key_expr_rx_z_put_msgs{prefix=\"other\"} 1",
+    ] {
+        assert!(metrics.contains(&format!("\n{expected}\n")), "{expected}");
+    }
+
+    let replies = ztimeout!(router.get(format!("@/{}/router?_stats=true", router.zid()))).unwrap();
+    let reply = ztimeout!(replies.recv_async()).unwrap();
+    let json: serde_json::Value =
+        serde_json::from_slice(&reply.result().unwrap().payload().to_bytes()).unwrap();
+    assert_eq!(
+        json["key_expr_stats"]["key_exprs"]["test/metrics/a/**"]["rx_z_put_msgs"],
+        2
+    );
+    assert_eq!(
+        json["key_expr_stats"]["prefixes"]["other"]["rx_z_put_msgs"],
+        1
+    );
+
+    ztimeout!(client1.close()).unwrap();
+    ztimeout!(client2.close()).unwrap();
+    ztimeout!(router.close()).unwrap();
+}
+
+#[cfg(feature = "stats")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_histogram_metrics() {
+    zenoh::init_log_from_env_or("error");
+
+    // Send a keep alive every 250ms
+    let mut config = get_router_config(27462);
+    config.transport.link.tx.set_lease(1000).unwrap();
+    let router = ztimeout!(zenoh::open(config)).unwrap();
+    let client = get_client_session(27462).await;
+
+    let _sub = ztimeout!(client.declare_subscriber("test/metrics/histogram")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    ztimeout!(router.put("test/metrics/histogram", "value")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let metrics = get_metrics(&router).await;
+    let labels = format!("zid=\"{}\",whatami=\"client\"", client.zid());
+    assert!(metrics.contains("# TYPE transport_keepalive_rtt histogram\n"));
+    assert!(
+        gauge(
+            &metrics,
+            &format!("transport_keepalive_rtt_count{{{labels}}}")
+        ) > 0
+    );
+    assert!(
+        gauge(
+            &metrics,
+            &format!("transport_tx_batch_fill_ratio_count{{{labels}}}")
+        ) > 0
+    );
+    assert!(metrics.contains(&format!(
+        "\ntransport_tx_batch_fill_ratio_bucket{{{labels},le=\"+Inf\"}} "
+    )));
+    assert_eq!(
+        gauge(
+            &metrics,
+            &format!("priority_tx_queuing_delay_count{{{labels},priority=\"Data\"}}")
+        ),
+        1
+    );
+
+    ztimeout!(client.close()).unwrap();
+    ztimeout!(router.close()).unwrap();
+}
+
+#[cfg(all(feature = "stats", feature = "unstable"))]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_session_stats() {
+    zenoh::init_log_from_env_or("error");
+
+    let router = get_router_session(27463).await;
+    let client1 = get_client_session(27463).await;
+    let client2 = get_client_session(27463).await;
+
+    let _sub = ztimeout!(client2.declare_subscriber("test/stats")).unwrap();
+    let publisher = ztimeout!(client1.declare_publisher("test/stats")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    for _ in 0..3 {
+        ztimeout!(publisher.put("value")).unwrap();
+    }
+    ztimeout!(client1.put("test/stats", "value")).unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    let stats = ztimeout!(client1.info().stats());
+    assert_eq!(stats.transports().len(), 1);
+    assert_eq!(stats.transports()[0].zid(), router.zid());
+    assert_eq!(stats.transports()[0].whatami(), WhatAmI::Router);
+    assert!(stats.total().tx_n_msgs >= 4);
+    assert_eq!(stats.publishers().len(), 1);
+    assert_eq!(stats.publishers()[0].key_expr().as_str(), "test/stats");
+    assert_eq!(stats.publishers()[0].samples_sent(), 3);
+    assert_eq!(stats.publishers()[0].bytes_sent(), 15);
+    assert_eq!(stats.publishers()[0].samples_dropped(), 0);
+
+    let stats = ztimeout!(client2.info().stats());
+    assert_eq!(stats.subscribers().len(), 1);
+    assert_eq!(stats.subscribers()[0].samples_received(), 4);
+    assert_eq!(stats.subscribers()[0].bytes_received(), 20);
+
+    ztimeout!(client1.close()).unwrap();
+    ztimeout!(client2.close()).unwrap();
+    ztimeout!(router.close()).unwrap();
+}
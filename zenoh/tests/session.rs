@@ -341,6 +341,58 @@ async fn zenoh_session_unicast() {
     close_session(peer01, peer02).await;
 }
 
+#[cfg(feature = "unstable")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn zenoh_session_query_cancellation() {
+    use zenoh::query::CancellationToken;
+
+    zenoh::init_log_from_env_or("error");
+    let (peer01, peer02) = open_session_unicast(&["tcp/127.0.0.1:17520"]).await;
+    let key_expr = "test/session/cancellation";
+
+    println!("[QC][01a] Declaring Queryable on peer01 session");
+    let (cancelled_tx, cancelled_rx) = flume::unbounded();
+    let _queryable = ztimeout!(peer01.declare_queryable(key_expr).callback(move |query| {
+        let cancelled_tx = cancelled_tx.clone();
+        tokio::spawn(async move {
+            query.cancelled().await;
+            cancelled_tx.send(query.is_cancelled()).unwrap();
+            // Replies to a cancelled query are discarded
+            query.reply(key_expr, "late").await.unwrap();
+        });
+    }))
+    .unwrap();
+    tokio::time::sleep(SLEEP).await;
+
+    // Remote and session local queries
+    for session in [&peer02, &peer01] {
+        let token = CancellationToken::default();
+        let replies = ztimeout!(session
+            .get(key_expr)
+            .timeout(TIMEOUT)
+            .cancellation_token(token.clone()))
+        .unwrap();
+        tokio::time::sleep(SLEEP).await;
+        assert!(cancelled_rx.is_empty());
+        println!("[QC][02a] Cancelling the query");
+        token.cancel();
+        assert!(ztimeout!(cancelled_rx.recv_async()).unwrap());
+        // The reply channel is closed without waiting for the timeout
+        assert!(ztimeout!(replies.recv_async()).is_err());
+    }
+
+    println!("[QC][03a] Declaring Querier on peer02 session");
+    let querier = ztimeout!(peer02.declare_querier(key_expr).timeout(TIMEOUT)).unwrap();
+    let token = CancellationToken::default();
+    let replies = ztimeout!(querier.get().cancellation_token(token.clone())).unwrap();
+    tokio::time::sleep(SLEEP).await;
+    token.cancel();
+    assert!(ztimeout!(cancelled_rx.recv_async()).unwrap());
+    assert!(ztimeout!(replies.recv_async()).is_err());
+
+    close_session(peer01, peer02).await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn zenoh_session_multicast() {
     zenoh::init_log_from_env_or("error");